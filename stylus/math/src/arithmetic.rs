@@ -0,0 +1,100 @@
+// Arithmetic the curve math runs on: Saturating clamps on overflow like the plain helpers of this
+// crate, Checked fails with the operation that overflowed. Curves written against Arithmetic
+// evaluate identically in both modes until something overflows.
+
+use alloy_primitives::ruint::UintTryFrom;
+use alloy_primitives::{U256, U512};
+
+use crate::{divide_fixed_point, mul_div, multiply_fixed_point, Rounding, SCALE_FACTOR};
+
+// Operation that overflowed under checked arithmetic
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MathOp {
+    Add = 0,
+    Mul = 1,
+    Div = 2,
+}
+
+pub trait Arithmetic {
+    fn add(a: U256, b: U256) -> Result<U256, MathOp>;
+
+    fn mul(a: U256, b: U256) -> Result<U256, MathOp>;
+
+    // a * b / denominator, rounded in the given direction
+    fn mul_div(a: U256, b: U256, denominator: U256, rounding: Rounding) -> Result<U256, MathOp>;
+
+    // (a * b) / 10^18
+    fn multiply_fixed_point(a: U256, b: U256) -> Result<U256, MathOp>;
+
+    // (a * 10^18) / b
+    fn divide_fixed_point(a: U256, b: U256) -> Result<U256, MathOp>;
+}
+
+// Clamps overflows to U256::MAX (and divisions by zero to 0), never failing
+pub struct Saturating;
+
+impl Arithmetic for Saturating {
+    fn add(a: U256, b: U256) -> Result<U256, MathOp> {
+        Ok(a.saturating_add(b))
+    }
+
+    fn mul(a: U256, b: U256) -> Result<U256, MathOp> {
+        Ok(a.saturating_mul(b))
+    }
+
+    fn mul_div(a: U256, b: U256, denominator: U256, rounding: Rounding) -> Result<U256, MathOp> {
+        Ok(mul_div(a, b, denominator, rounding))
+    }
+
+    fn multiply_fixed_point(a: U256, b: U256) -> Result<U256, MathOp> {
+        Ok(multiply_fixed_point(a, b))
+    }
+
+    fn divide_fixed_point(a: U256, b: U256) -> Result<U256, MathOp> {
+        Ok(divide_fixed_point(a, b))
+    }
+}
+
+// Fails on any result that does not fit in 256 bits and on division by zero. Fixed-point products
+// go through a 512-bit intermediate, so only results that overflow fail.
+pub struct Checked;
+
+impl Arithmetic for Checked {
+    fn add(a: U256, b: U256) -> Result<U256, MathOp> {
+        a.checked_add(b).ok_or(MathOp::Add)
+    }
+
+    fn mul(a: U256, b: U256) -> Result<U256, MathOp> {
+        a.checked_mul(b).ok_or(MathOp::Mul)
+    }
+
+    fn mul_div(a: U256, b: U256, denominator: U256, rounding: Rounding) -> Result<U256, MathOp> {
+        if denominator.is_zero() {
+            return Err(MathOp::Div);
+        }
+
+        let product: U512 = a.widening_mul(b);
+        let (quotient, remainder) = product.div_rem(U512::from(denominator));
+        let quotient = if rounding == Rounding::Up && !remainder.is_zero() {
+            quotient + U512::from(1u8)
+        } else {
+            quotient
+        };
+
+        U256::uint_try_from(quotient).map_err(|_| MathOp::Mul)
+    }
+
+    fn multiply_fixed_point(a: U256, b: U256) -> Result<U256, MathOp> {
+        Self::mul_div(a, b, SCALE_FACTOR, Rounding::Down)
+    }
+
+    fn divide_fixed_point(a: U256, b: U256) -> Result<U256, MathOp> {
+        Self::mul_div(a, SCALE_FACTOR, b, Rounding::Down)
+    }
+}
+
+// Value of a computation run on Saturating arithmetic, which never fails
+pub fn saturated(result: Result<U256, MathOp>) -> U256 {
+    result.unwrap_or(U256::MAX)
+}
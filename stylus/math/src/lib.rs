@@ -0,0 +1,245 @@
+//!
+//! Fixed-point math and sigmoid pricing shared by PumpUp bonding curves
+//!
+//! Everything here is integer math over 18-decimal fixed point, so frontends and tests get
+//! byte-for-byte the quotes the contracts compute. The `std` feature adds an f64 reference
+//! implementation to check them against.
+//!
+
+#![cfg_attr(not(test), no_std)]
+
+use alloy_primitives::{U256, U512};
+
+pub mod arithmetic;
+pub mod sigmoid;
+
+#[cfg(any(test, feature = "std"))]
+pub mod reference;
+
+#[cfg(test)]
+mod tests;
+
+// Scaling factor for fixed-point math (10^18)
+pub const SCALE_FACTOR: U256 = U256::from_limbs([1_000_000_000_000_000_000u64, 0, 0, 0]);
+pub const TWO: U256 = U256::from_limbs([2u64, 0, 0, 0]);
+
+// Largest exponent exp_approx evaluates before saturating (scaled by 10^18)
+pub const MAX_EXP_INPUT: U256 = U256::from_limbs([0xb5e3_af16_b188_0000u64, 2, 0, 0]); // 50.0
+
+// ln(2) (scaled by 10^18)
+const LN_2: U256 = U256::from_limbs([693_147_180_559_945_309u64, 0, 0, 0]);
+
+// log2(e) (scaled by 10^18)
+const LOG2_E: U256 = U256::from_limbs([1_442_695_040_888_963_407u64, 0, 0, 0]);
+
+// Fractional bits of the exponent resolved by exp_approx
+const EXP_FRACTION_BITS: usize = 60;
+
+// 2^(2^-i) for i = 1..=60 (scaled by 10^18)
+const TWO_POW_NEG_POWERS: [u64; EXP_FRACTION_BITS] = [
+    1_414_213_562_373_095_049,
+    1_189_207_115_002_721_067,
+    1_090_507_732_665_257_659,
+    1_044_273_782_427_413_840,
+    1_021_897_148_654_116_678,
+    1_010_889_286_051_700_460,
+    1_005_429_901_112_802_821,
+    1_002_711_275_050_202_485,
+    1_001_354_719_892_108_206,
+    1_000_677_130_693_066_357,
+    1_000_338_508_052_682_313,
+    1_000_169_239_705_302_231,
+    1_000_084_616_272_694_313,
+    1_000_042_307_241_395_819,
+    1_000_021_153_396_964_808,
+    1_000_010_576_642_549_720,
+    1_000_005_288_307_291_763,
+    1_000_002_644_150_150_117,
+    1_000_001_322_074_201_118,
+    1_000_000_661_036_882_074,
+    1_000_000_330_518_386_416,
+    1_000_000_165_259_179_553,
+    1_000_000_082_629_586_363,
+    1_000_000_041_314_792_328,
+    1_000_000_020_657_395_951,
+    1_000_000_010_328_697_922,
+    1_000_000_005_164_348_948,
+    1_000_000_002_582_174_470,
+    1_000_000_001_291_087_234,
+    1_000_000_000_645_543_617,
+    1_000_000_000_322_771_808,
+    1_000_000_000_161_385_904,
+    1_000_000_000_080_692_952,
+    1_000_000_000_040_346_476,
+    1_000_000_000_020_173_238,
+    1_000_000_000_010_086_619,
+    1_000_000_000_005_043_310,
+    1_000_000_000_002_521_655,
+    1_000_000_000_001_260_827,
+    1_000_000_000_000_630_414,
+    1_000_000_000_000_315_207,
+    1_000_000_000_000_157_603,
+    1_000_000_000_000_078_802,
+    1_000_000_000_000_039_401,
+    1_000_000_000_000_019_700,
+    1_000_000_000_000_009_850,
+    1_000_000_000_000_004_925,
+    1_000_000_000_000_002_463,
+    1_000_000_000_000_001_231,
+    1_000_000_000_000_000_616,
+    1_000_000_000_000_000_308,
+    1_000_000_000_000_000_154,
+    1_000_000_000_000_000_077,
+    1_000_000_000_000_000_038,
+    1_000_000_000_000_000_019,
+    1_000_000_000_000_000_010,
+    1_000_000_000_000_000_005,
+    1_000_000_000_000_000_002,
+    1_000_000_000_000_000_001,
+    1_000_000_000_000_000_001,
+];
+
+pub fn multiply_fixed_point(a: U256, b: U256) -> U256 {
+    // To avoid overflow: (a * b) / SCALE_FACTOR
+    // This implementation assumes a and b are already scaled by SCALE_FACTOR
+    if a.is_zero() || b.is_zero() {
+        return U256::ZERO;
+    }
+
+    // Check if the multiplication would overflow
+    if a > U256::MAX / b {
+        return U256::MAX; // Return max on overflow
+    }
+
+    a.saturating_mul(b) / SCALE_FACTOR
+}
+
+pub fn divide_fixed_point(a: U256, b: U256) -> U256 {
+    // To maintain precision: (a * SCALE_FACTOR) / b
+    if b.is_zero() {
+        return U256::ZERO; // Return 0 for division by zero
+    }
+
+    // Check if the multiplication would overflow
+    if a > U256::MAX / SCALE_FACTOR {
+        return a / b * SCALE_FACTOR; // Alternative calculation to avoid overflow
+    }
+
+    a.saturating_mul(SCALE_FACTOR) / b
+}
+
+// Direction a division rounds its result in
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+impl Rounding {
+    // The other direction, for terms that are subtracted
+    pub fn opposite(self) -> Self {
+        match self {
+            Rounding::Down => Rounding::Up,
+            Rounding::Up => Rounding::Down,
+        }
+    }
+}
+
+// a * b / denominator rounded in the given direction, through a 512-bit product so the
+// multiplication cannot overflow. Returns 0 for a zero denominator and U256::MAX when the
+// quotient does not fit.
+pub fn mul_div(a: U256, b: U256, denominator: U256, rounding: Rounding) -> U256 {
+    if denominator.is_zero() {
+        return U256::ZERO;
+    }
+
+    let product: U512 = a.widening_mul(b);
+    let (quotient, remainder) = product.div_rem(U512::from(denominator));
+    let quotient = if rounding == Rounding::Up && !remainder.is_zero() {
+        quotient + U512::from(1u8)
+    } else {
+        quotient
+    };
+
+    U256::saturating_from(quotient)
+}
+
+// a * b / denominator, rounded down
+pub fn mul_div_rounding_down(a: U256, b: U256, denominator: U256) -> U256 {
+    mul_div(a, b, denominator, Rounding::Down)
+}
+
+// a * b / denominator, rounded up
+pub fn mul_div_rounding_up(a: U256, b: U256, denominator: U256) -> U256 {
+    mul_div(a, b, denominator, Rounding::Up)
+}
+
+// Exponential function via binary decomposition: e^x = 2^(x * log2(e)), with the integer part of
+// the power applied as a shift and each set bit b_i of the fraction as a factor 2^(2^-i)
+pub fn exp_approx(x: U256) -> U256 {
+    // Handle the base case
+    if x.is_zero() {
+        return SCALE_FACTOR; // e^0 = 1
+    }
+
+    // Saturate above the largest supported exponent to avoid overflow
+    if x > MAX_EXP_INPUT {
+        return U256::MAX / TWO; // Very large number
+    }
+
+    let power = multiply_fixed_point(x, LOG2_E);
+    let whole = (power / SCALE_FACTOR).to::<usize>();
+    let fraction_bits = ((power % SCALE_FACTOR) << EXP_FRACTION_BITS) / SCALE_FACTOR;
+
+    let mut result = SCALE_FACTOR;
+    for (i, factor) in TWO_POW_NEG_POWERS.iter().enumerate() {
+        if fraction_bits.bit(EXP_FRACTION_BITS - 1 - i) {
+            result = multiply_fixed_point(result, U256::from(*factor));
+        }
+    }
+
+    result << whole
+}
+
+// Integer square root (floor) using the Babylonian method
+pub fn sqrt(x: U256) -> U256 {
+    if x.is_zero() {
+        return U256::ZERO;
+    }
+
+    let mut y = x;
+    let mut z = x / TWO + U256::from(1u8);
+    while z < y {
+        y = z;
+        z = (x / z + z) / TWO;
+    }
+
+    y
+}
+
+// Natural logarithm of x >= 1.0 (scaled by 10^18); values at or below 1.0 return 0
+pub fn ln_fixed_point(x: U256) -> U256 {
+    if x <= SCALE_FACTOR {
+        return U256::ZERO;
+    }
+
+    // Factor out powers of two so that x = 2^n * y with y in [1, 2)
+    let n = (x / SCALE_FACTOR).bit_len() - 1;
+    let y = x >> n;
+
+    // ln(y) = 2 * atanh(t) = 2 * (t + t^3/3 + t^5/5 + ...) with t = (y - 1) / (y + 1) < 1/3
+    let t = divide_fixed_point(y - SCALE_FACTOR, y + SCALE_FACTOR);
+    let t_squared = multiply_fixed_point(t, t);
+
+    let mut sum = U256::ZERO;
+    let mut term = t;
+    let mut divisor = U256::from(1u8);
+    while !term.is_zero() {
+        sum = sum.saturating_add(term / divisor);
+        term = multiply_fixed_point(term, t_squared);
+        divisor += TWO;
+    }
+
+    LN_2.saturating_mul(U256::from(n))
+        .saturating_add(sum.saturating_mul(TWO))
+}
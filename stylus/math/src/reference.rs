@@ -0,0 +1,82 @@
+// f64 reference implementation of the sigmoid curve, for checking the fixed-point math off-chain.
+// Inputs and outputs are plain numbers (1.0 = 10^18 in the fixed-point functions).
+
+extern crate std;
+
+use crate::sigmoid::SigmoidCurve;
+use alloy_primitives::U256;
+
+// Fixed-point value as a float (1.0 = 10^18)
+pub fn from_fixed(value: U256) -> f64 {
+    let limbs = value.as_limbs();
+    let whole = limbs.iter().rev().fold(0.0, |acc, limb| {
+        acc * 18_446_744_073_709_551_616.0 + *limb as f64
+    });
+
+    whole / 1e18
+}
+
+// e^x
+pub fn exp(x: f64) -> f64 {
+    x.exp()
+}
+
+// Natural logarithm of x
+pub fn ln(x: f64) -> f64 {
+    x.ln()
+}
+
+// Spot price at `supply` tokens sold (the initial price exactly at zero, as on-chain)
+pub fn price_at(supply: f64, params: &SigmoidCurve) -> f64 {
+    let (a, range, k, m, total) = unpack(params);
+    if supply == 0.0 {
+        return a;
+    }
+    let x = if total == 0.0 { 1.0 } else { supply / total };
+
+    a + range / (1.0 + (-k * (x - m)).exp())
+}
+
+// Price change per token at `supply` tokens sold, differentiating the price exactly
+pub fn slope_at(supply: f64, params: &SigmoidCurve) -> f64 {
+    let (_, range, k, m, total) = unpack(params);
+    if total == 0.0 {
+        return 0.0;
+    }
+    let logistic = 1.0 / (1.0 + (-k * (supply / total - m)).exp());
+
+    range * k / total * logistic * (1.0 - logistic)
+}
+
+// WETH between two supplies, integrating the price exactly
+pub fn cost_between(start_supply: f64, end_supply: f64, params: &SigmoidCurve) -> f64 {
+    let (a, range, k, m, total) = unpack(params);
+    let tokens = end_supply - start_supply;
+    if total == 0.0 {
+        return a * tokens;
+    }
+    if k == 0.0 {
+        return (a + range / 2.0) * tokens;
+    }
+
+    let softplus = |supply: f64| {
+        let z = k * (supply / total - m);
+        z.max(0.0) + (-z.abs()).exp().ln_1p()
+    };
+
+    a * tokens + range * total / k * (softplus(end_supply) - softplus(start_supply))
+}
+
+// (initial price, price range, steepness, midpoint, total supply) as floats
+fn unpack(params: &SigmoidCurve) -> (f64, f64, f64, f64, f64) {
+    let initial_price = from_fixed(params.initial_price);
+    let max_price = initial_price * from_fixed(params.max_price_factor);
+
+    (
+        initial_price,
+        (max_price - initial_price).max(0.0),
+        from_fixed(params.steepness),
+        from_fixed(params.midpoint),
+        from_fixed(params.total_supply),
+    )
+}
@@ -0,0 +1,199 @@
+// Sigmoid bonding curve pricing: price = a + R / (1 + e^(-k * (x - m))) with a the initial
+// price, R the price range, k the steepness, m the midpoint and x the fraction of supply sold
+
+use alloy_primitives::U256;
+
+use crate::arithmetic::{saturated, Arithmetic, MathOp, Saturating};
+use crate::{
+    divide_fixed_point, exp_approx, ln_fixed_point, multiply_fixed_point, Rounding, SCALE_FACTOR,
+    TWO,
+};
+
+// Parameters of a sigmoid curve (all scaled by 10^18)
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SigmoidCurve {
+    pub initial_price: U256,
+    pub max_price_factor: U256,
+    pub steepness: U256,
+    pub midpoint: U256,
+    pub total_supply: U256,
+}
+
+// Spot price at a given supply
+pub fn price_at(supply: U256, params: &SigmoidCurve) -> U256 {
+    saturated(try_price_at::<Saturating>(supply, params))
+}
+
+// Spot price at a given supply on the given arithmetic
+pub fn try_price_at<A: Arithmetic>(supply: U256, params: &SigmoidCurve) -> Result<U256, MathOp> {
+    if supply.is_zero() {
+        return Ok(params.initial_price);
+    }
+
+    // Calculate percentage sold (normalized to 0-1)
+    let percentage_sold = if params.total_supply.is_zero() {
+        SCALE_FACTOR // 100% if total supply is zero (edge case)
+    } else {
+        A::divide_fixed_point(supply, params.total_supply)?
+    };
+
+    // Calculate max price from initial price and factor
+    let max_price = A::multiply_fixed_point(params.initial_price, params.max_price_factor)?;
+
+    // Calculate price range
+    let price_range = max_price.saturating_sub(params.initial_price);
+
+    // Check if percentage_sold is less than midpoint
+    if percentage_sold < params.midpoint {
+        // Percentage_sold < midpoint case
+        let midpoint_diff = params.midpoint.saturating_sub(percentage_sold);
+        let exponent_term = A::multiply_fixed_point(params.steepness, midpoint_diff)?;
+
+        // Calculate e^(exponent_term) using approximation
+        let exp_value = exp_approx(exponent_term);
+
+        // Calculate denominator: 1 + e^(exponent_term)
+        let denominator = SCALE_FACTOR.saturating_add(exp_value);
+
+        // Calculate final price: initialPrice + priceRange / denominator
+        A::add(
+            params.initial_price,
+            A::divide_fixed_point(price_range, denominator)?,
+        )
+    } else {
+        // Percentage_sold >= midpoint case
+        let midpoint_diff = percentage_sold.saturating_sub(params.midpoint);
+        let exponent_term = A::multiply_fixed_point(params.steepness, midpoint_diff)?;
+
+        // Calculate e^(exponent_term)
+        let exp_value = exp_approx(exponent_term);
+
+        // Calculate denominator: (exp_value + 1) / exp_value = 1 + 1/exp_value
+        // For numerical stability, use: 1 + exp_value^-1
+        let denominator = if exp_value.is_zero() {
+            // Handle divide by zero - rare case
+            SCALE_FACTOR.saturating_mul(U256::from(1000u64)) // Large number
+        } else {
+            SCALE_FACTOR.saturating_add(divide_fixed_point(SCALE_FACTOR, exp_value))
+        };
+
+        // Calculate final price
+        A::add(
+            params.initial_price,
+            A::divide_fixed_point(price_range, denominator)?,
+        )
+    }
+}
+
+// Price change per whole token at a given supply, the closed-form derivative
+// dP/dS = (R * k / T) * e^z / (1 + e^z)^2 with z = k * (supply / total_supply - m). The logistic
+// term is symmetric in z, so it is evaluated as 1 / (e^|z| + 2 + e^-|z|) to keep exp bounded.
+pub fn slope_at(supply: U256, params: &SigmoidCurve) -> U256 {
+    if params.total_supply.is_zero() {
+        return U256::ZERO;
+    }
+
+    let max_price = multiply_fixed_point(params.initial_price, params.max_price_factor);
+    let price_range = max_price.saturating_sub(params.initial_price);
+
+    let percentage_sold = divide_fixed_point(supply, params.total_supply);
+    let distance = if percentage_sold >= params.midpoint {
+        percentage_sold - params.midpoint
+    } else {
+        params.midpoint - percentage_sold
+    };
+
+    let exp_value = exp_approx(multiply_fixed_point(params.steepness, distance));
+    let denominator = exp_value
+        .saturating_add(TWO * SCALE_FACTOR)
+        .saturating_add(divide_fixed_point(SCALE_FACTOR, exp_value));
+
+    // R * k * 10^18 / (T * denominator), dividing once to keep the precision of small slopes
+    price_range
+        .saturating_mul(params.steepness)
+        .saturating_mul(SCALE_FACTOR)
+        / params.total_supply.saturating_mul(denominator)
+}
+
+// WETH between two supplies from the closed-form integral of the sigmoid:
+// cost = a * t + (R * T / k) * (softplus(k * (x2 - m)) - softplus(k * (x1 - m)))
+// where x1 and x2 are the fractions of total supply sold at the start and end supply, rounded in
+// the given direction
+pub fn cost_between(
+    start_supply: U256,
+    end_supply: U256,
+    params: &SigmoidCurve,
+    rounding: Rounding,
+) -> U256 {
+    saturated(try_cost_between::<Saturating>(
+        start_supply,
+        end_supply,
+        params,
+        rounding,
+    ))
+}
+
+// WETH between two supplies on the given arithmetic
+pub fn try_cost_between<A: Arithmetic>(
+    start_supply: U256,
+    end_supply: U256,
+    params: &SigmoidCurve,
+    rounding: Rounding,
+) -> Result<U256, MathOp> {
+    let token_amount = end_supply - start_supply;
+
+    let base_cost = A::mul_div(params.initial_price, token_amount, SCALE_FACTOR, rounding)?;
+    if params.total_supply.is_zero() {
+        return Ok(base_cost);
+    }
+
+    let max_price = A::multiply_fixed_point(params.initial_price, params.max_price_factor)?;
+    let price_range = max_price.saturating_sub(params.initial_price);
+
+    // A flat sigmoid sits at half the price range everywhere
+    if params.steepness.is_zero() {
+        return A::add(
+            base_cost,
+            A::mul_div(price_range, token_amount, SCALE_FACTOR * TWO, rounding)?,
+        );
+    }
+
+    let start_softplus = softplus::<A>(start_supply, params)?;
+    let end_softplus = softplus::<A>(end_supply, params)?;
+    let scale = A::multiply_fixed_point(
+        price_range,
+        A::divide_fixed_point(params.total_supply, params.steepness)?,
+    )?;
+
+    A::add(
+        base_cost,
+        A::mul_div(
+            scale,
+            end_softplus.saturating_sub(start_softplus),
+            SCALE_FACTOR,
+            rounding,
+        )?,
+    )
+}
+
+// softplus(z) = ln(1 + e^z) at z = k * (supply / total_supply - m), the antiderivative of the
+// logistic term, evaluated as ln(1 + e^-|z|) (+ z when z > 0) to keep exp bounded
+fn softplus<A: Arithmetic>(supply: U256, params: &SigmoidCurve) -> Result<U256, MathOp> {
+    let percentage_sold = A::divide_fixed_point(supply, params.total_supply)?;
+    let (distance, above_midpoint) = if percentage_sold >= params.midpoint {
+        (percentage_sold - params.midpoint, true)
+    } else {
+        (params.midpoint - percentage_sold, false)
+    };
+
+    let z = A::multiply_fixed_point(params.steepness, distance)?;
+    let tail = ln_fixed_point(
+        SCALE_FACTOR.saturating_add(divide_fixed_point(SCALE_FACTOR, exp_approx(z))),
+    );
+
+    if above_midpoint {
+        A::add(z, tail)
+    } else {
+        Ok(tail)
+    }
+}
@@ -0,0 +1,158 @@
+// Differential tests: the fixed-point math against the f64 reference over randomized inputs
+
+use alloy_primitives::U256;
+use proptest::prelude::*;
+
+use crate::arithmetic::Checked;
+use crate::reference::{self, from_fixed};
+use crate::sigmoid::{self, SigmoidCurve};
+use crate::{exp_approx, ln_fixed_point, mul_div, Rounding, MAX_EXP_INPUT, SCALE_FACTOR};
+
+// Relative error of `actual` against `expected`, treating two values near zero as equal
+fn relative_error(actual: f64, expected: f64) -> f64 {
+    let scale = expected.abs().max(actual.abs());
+    if scale < f64::MIN_POSITIVE {
+        0.0
+    } else {
+        (actual - expected).abs() / scale
+    }
+}
+
+// Fixed-point value from a float (1.0 = 10^18)
+fn to_fixed(value: f64) -> U256 {
+    U256::from((value * 1e18) as u128)
+}
+
+// Parts per billion of a curve's total supply
+fn supply_at(params: &SigmoidCurve, parts_per_billion: u64) -> U256 {
+    params.total_supply * U256::from(parts_per_billion) / U256::from(BILLION)
+}
+
+const BILLION: u64 = 1_000_000_000;
+
+// Largest relative errors accepted against the reference. exp and ln land within a few ulps of
+// f64; prices inherit exp's error, and costs also lose digits subtracting two softplus values.
+const EXP_TOLERANCE: f64 = 1e-13;
+const LN_TOLERANCE: f64 = 1e-15;
+const PRICE_TOLERANCE: f64 = 1e-12;
+const COST_TOLERANCE: f64 = 1e-9;
+const SLOPE_TOLERANCE: f64 = 1e-9;
+
+// Absolute slack for costs too small to be compared relatively (in WETH)
+const COST_DUST: f64 = 1e-12;
+
+// Absolute slack for slopes flattened below fixed-point resolution (in WETH per token)
+const SLOPE_DUST: f64 = 1e-18;
+
+prop_compose! {
+    // Curves across the ranges pools are created with: prices from 1 gwei to 1 ETH, up to 1000x
+    // growth, steepness up to 50 and supplies from one to a trillion tokens
+    fn curve()(
+        initial_price in 1_000_000_000u64..=SCALE_FACTOR.to::<u64>(),
+        max_price_factor in 1.0f64..1000.0,
+        steepness in 0.01f64..50.0,
+        midpoint in 0.01f64..0.99,
+        total_tokens in 1u64..1_000_000_000_000,
+    ) -> SigmoidCurve {
+        SigmoidCurve {
+            initial_price: U256::from(initial_price),
+            max_price_factor: to_fixed(max_price_factor),
+            steepness: to_fixed(steepness),
+            midpoint: to_fixed(midpoint),
+            total_supply: U256::from(total_tokens) * SCALE_FACTOR,
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn exp_matches_reference(x in 0u128..=MAX_EXP_INPUT.to::<u128>()) {
+        let x = U256::from(x);
+        let expected = reference::exp(from_fixed(x));
+        prop_assert!(relative_error(from_fixed(exp_approx(x)), expected) < EXP_TOLERANCE);
+    }
+
+    #[test]
+    fn ln_matches_reference(x in SCALE_FACTOR.to::<u128>()..u128::MAX) {
+        let x = U256::from(x);
+        let expected = reference::ln(from_fixed(x));
+
+        // Absolute near ln(1) = 0, relative beyond
+        let error = (from_fixed(ln_fixed_point(x)) - expected).abs();
+        prop_assert!(error < LN_TOLERANCE * expected.max(1.0));
+    }
+
+    #[test]
+    fn mul_div_rounds_in_the_given_direction(a: u128, b: u128, denominator in 1u128..) {
+        let (a, b, denominator) = (U256::from(a), U256::from(b), U256::from(denominator));
+        let down = mul_div(a, b, denominator, Rounding::Down);
+        let up = mul_div(a, b, denominator, Rounding::Up);
+
+        prop_assert!(down * denominator <= a * b && a * b < (down + U256::from(1u8)) * denominator);
+        prop_assert_eq!(up - down, U256::from(!(a * b % denominator).is_zero()));
+    }
+
+    #[test]
+    fn price_matches_reference(params in curve(), sold in 0..=BILLION) {
+        let supply = supply_at(&params, sold);
+        let expected = reference::price_at(from_fixed(supply), &params);
+        let actual = from_fixed(sigmoid::price_at(supply, &params));
+        prop_assert!(relative_error(actual, expected) < PRICE_TOLERANCE);
+    }
+
+    #[test]
+    fn slope_matches_reference(params in curve(), sold in 0..=BILLION) {
+        let supply = supply_at(&params, sold);
+        let expected = reference::slope_at(from_fixed(supply), &params);
+        let actual = from_fixed(sigmoid::slope_at(supply, &params));
+        prop_assert!(
+            relative_error(actual, expected) < SLOPE_TOLERANCE || (actual - expected).abs() < SLOPE_DUST
+        );
+    }
+
+    #[test]
+    fn cost_matches_reference(params in curve(), start in 0..=BILLION, end in 0..=BILLION) {
+        let start_supply = supply_at(&params, start.min(end));
+        let end_supply = supply_at(&params, start.max(end));
+
+        let expected = reference::cost_between(
+            from_fixed(start_supply),
+            from_fixed(end_supply),
+            &params,
+        );
+        let actual = from_fixed(sigmoid::cost_between(start_supply, end_supply, &params, Rounding::Down));
+        prop_assert!(
+            relative_error(actual, expected) < COST_TOLERANCE || (actual - expected).abs() < COST_DUST
+        );
+    }
+
+    #[test]
+    fn cost_is_additive(params in curve(), mut cuts in proptest::array::uniform3(0..=BILLION)) {
+        cuts.sort_unstable();
+        let [start, middle, end] = cuts.map(|cut| supply_at(&params, cut));
+
+        let whole = from_fixed(sigmoid::cost_between(start, end, &params, Rounding::Down));
+        let split = from_fixed(sigmoid::cost_between(start, middle, &params, Rounding::Down))
+            + from_fixed(sigmoid::cost_between(middle, end, &params, Rounding::Down));
+        prop_assert!(relative_error(split, whole) < COST_TOLERANCE || (split - whole).abs() < COST_DUST);
+    }
+
+    #[test]
+    fn checked_matches_saturating_without_overflow(
+        params in curve(),
+        start in 0..=BILLION,
+        end in 0..=BILLION,
+    ) {
+        let start_supply = supply_at(&params, start.min(end));
+        let end_supply = supply_at(&params, start.max(end));
+
+        prop_assert_eq!(
+            sigmoid::try_price_at::<Checked>(end_supply, &params),
+            Ok(sigmoid::price_at(end_supply, &params))
+        );
+        prop_assert_eq!(
+            sigmoid::try_cost_between::<Checked>(start_supply, end_supply, &params, Rounding::Up),
+            Ok(sigmoid::cost_between(start_supply, end_supply, &params, Rounding::Up))
+        );
+    }
+}
@@ -0,0 +1,427 @@
+//!
+//! Exponential Bonding Curve for Arbitrum Stylus
+//!
+//! Price grows as initial_price * e^(growth_rate * percentage_sold).
+//!
+//! The curve math is shared with the sigmoid entrypoint, which can host exponential pools itself.
+//!
+
+use alloc::string::String;
+use alloy_primitives::{Address, B256, U256};
+use pumpup_strategy::BondingCurveStrategy;
+use stylus_sdk::{abi::Bytes, evm, msg, prelude::*};
+
+use crate::host::{host, HostCalls};
+use crate::{
+    extract_u256_from_bytes, CurveParameters, InconsistentTokenState, InsufficientLiquidity,
+    InvalidAmount, InvalidParametersLength, InvalidParametersZero, InvalidPoolId,
+    NotPoolStateManager, PoolTransitioned, PumpUpErrors, TokensPurchased, TokensSold,
+    MAX_EXP_INPUT, SCALE_FACTOR, STRATEGY_TYPE, TWO,
+};
+use pumpup_math::arithmetic::{saturated, Arithmetic, MathOp, Saturating};
+use pumpup_math::{divide_fixed_point, exp_approx, ln_fixed_point, multiply_fixed_point, Rounding};
+
+pub(crate) const EXPONENTIAL_STRATEGY_NAME: &str = "Exponential";
+
+// Default growth rate (scaled by 10^18); e^3 puts the final price at ~20x the initial price
+const DEFAULT_GROWTH_RATE: U256 = U256::from_limbs([3_000_000_000_000_000_000u64, 0, 0, 0]); // 3.0
+
+// Curve parameters for an exponential pool
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct ExponentialParameters {
+    initial_price: U256,
+    growth_rate: U256,
+    total_supply: U256,
+}
+
+// Exponential pools hosted by the sigmoid entrypoint keep their growth rate in steepness
+impl From<&CurveParameters> for ExponentialParameters {
+    fn from(params: &CurveParameters) -> Self {
+        ExponentialParameters {
+            initial_price: params.initial_price,
+            growth_rate: if params.steepness.is_zero() {
+                DEFAULT_GROWTH_RATE
+            } else {
+                params.steepness.min(MAX_EXP_INPUT)
+            },
+            total_supply: params.total_supply,
+        }
+    }
+}
+
+sol_storage! {
+    #[cfg_attr(feature = "exponential", entrypoint)]
+    pub struct ExponentialBondingCurve {
+        // Admin management
+        address owner;
+
+        // Pool state manager
+        address pool_state_manager;
+
+        // Curve parameters for each pool
+        mapping(bytes32 => uint256) initial_prices;
+        mapping(bytes32 => uint256) growth_rates;
+        mapping(bytes32 => uint256) total_supplies;
+    }
+}
+
+#[public]
+impl ExponentialBondingCurve {
+    pub fn constructor(&mut self, pool_state_manager: Address) {
+        self.owner.set(msg::sender());
+        self.pool_state_manager.set(pool_state_manager);
+    }
+
+    // Strategy type identifier
+    pub fn strategy_type(&self) -> String {
+        STRATEGY_TYPE.into()
+    }
+
+    // Strategy name
+    pub fn name(&self) -> String {
+        EXPONENTIAL_STRATEGY_NAME.into()
+    }
+
+    // Initialize the strategy for a new pool
+    pub fn initialize(&mut self, pool_id: B256, params: Bytes) -> Result<(), PumpUpErrors> {
+        // Only pool state manager can initialize
+        if msg::sender() != *self.pool_state_manager {
+            return Err(NotPoolStateManager {}.into());
+        }
+
+        let params_bytes = params.0;
+
+        // Parse parameters - 3 U256 values packed in sequence
+        if params_bytes.len() < 96 {
+            return Err(InvalidParametersLength {}.into());
+        }
+
+        let initial_price = extract_u256_from_bytes(&params_bytes, 0)?;
+        let growth_rate = extract_u256_from_bytes(&params_bytes, 32)?;
+        let total_supply = extract_u256_from_bytes(&params_bytes, 64)?;
+
+        if total_supply.is_zero() || initial_price.is_zero() {
+            return Err(InvalidParametersZero {}.into());
+        }
+
+        // Use the default growth rate if not provided, and keep the exponent within the range
+        // exp_approx evaluates without saturating (percentage sold never exceeds 1.0)
+        let growth_rate = if growth_rate.is_zero() {
+            DEFAULT_GROWTH_RATE
+        } else {
+            growth_rate.min(MAX_EXP_INPUT)
+        };
+
+        self.initial_prices.setter(pool_id).set(initial_price);
+        self.growth_rates.setter(pool_id).set(growth_rate);
+        self.total_supplies.setter(pool_id).set(total_supply);
+
+        Ok(())
+    }
+
+    // Calculate tokens received for a WETH amount (only pool state manager). Takes the trader
+    // like the sigmoid entrypoint so the manager calls every curve the same way.
+    pub fn calculate_buy(
+        &mut self,
+        pool_id: B256,
+        _trader: Address,
+        weth_amount: U256,
+    ) -> Result<(U256, U256), PumpUpErrors> {
+        if msg::sender() != *self.pool_state_manager {
+            return Err(NotPoolStateManager {}.into());
+        }
+
+        let (token_amount, new_price) =
+            BondingCurveStrategy::quote_buy(self, pool_id, weth_amount)?;
+
+        // Emit event - Tokens Purchased
+        evm::log(TokensPurchased {
+            poolId: pool_id,
+            baseAmount: weth_amount,
+            tokenAmount: token_amount,
+            newPrice: new_price,
+        });
+
+        Ok((token_amount, new_price))
+    }
+
+    // Calculate WETH returned for a token amount (only pool state manager)
+    pub fn calculate_sell(
+        &mut self,
+        pool_id: B256,
+        _trader: Address,
+        token_amount: U256,
+    ) -> Result<(U256, U256), PumpUpErrors> {
+        if msg::sender() != *self.pool_state_manager {
+            return Err(NotPoolStateManager {}.into());
+        }
+
+        let (weth_to_return, new_price) =
+            BondingCurveStrategy::quote_sell(self, pool_id, token_amount)?;
+
+        // Emit event - Tokens Sold
+        evm::log(TokensSold {
+            poolId: pool_id,
+            tokenAmount: token_amount,
+            baseAmount: weth_to_return,
+            newPrice: new_price,
+        });
+
+        Ok((weth_to_return, new_price))
+    }
+
+    // Get current token price
+    pub fn get_current_price(&self, pool_id: B256) -> Result<U256, PumpUpErrors> {
+        let (token_address, _creator, _weth_collected, last_price, is_transitioned, _strategy) =
+            host().pool_info(*self.pool_state_manager, pool_id)?;
+
+        if is_transitioned {
+            return Ok(last_price);
+        }
+
+        let params = self.get_params(pool_id)?;
+        let circulating_supply = self.circulating_supply(&token_address, &params)?;
+
+        Ok(price_at(circulating_supply, &params))
+    }
+
+    // Curve parameters for a pool as (initial_price, growth_rate, total_supply)
+    pub fn get_curve_params(&self, pool_id: B256) -> Result<(U256, U256, U256), PumpUpErrors> {
+        let params = self.get_params(pool_id)?;
+        Ok((
+            params.initial_price,
+            params.growth_rate,
+            params.total_supply,
+        ))
+    }
+
+    pub fn owner(&self) -> Address {
+        *self.owner
+    }
+}
+
+// Shared strategy interface; calculate_buy and calculate_sell add events on top of the quotes
+impl BondingCurveStrategy for ExponentialBondingCurve {
+    type Error = PumpUpErrors;
+
+    fn initialize(&mut self, pool_id: B256, params: &[u8]) -> Result<(), PumpUpErrors> {
+        ExponentialBondingCurve::initialize(self, pool_id, Bytes(params.to_vec()))
+    }
+
+    fn quote_buy(&self, pool_id: B256, weth_amount: U256) -> Result<(U256, U256), PumpUpErrors> {
+        let (token_address, _creator, _weth_collected, _last_price, is_transitioned, _strategy) =
+            host().pool_info(*self.pool_state_manager, pool_id)?;
+
+        if is_transitioned {
+            return Err(PoolTransitioned {}.into());
+        }
+
+        if weth_amount.is_zero() {
+            return Err(InvalidAmount {}.into());
+        }
+
+        let params = self.get_params(pool_id)?;
+        let circulating_supply = self.circulating_supply(&token_address, &params)?;
+
+        let token_amount = tokens_for_weth(circulating_supply, weth_amount, &params);
+        if token_amount.is_zero() {
+            return Err(InsufficientLiquidity {
+                available: U256::ZERO,
+                requested: weth_amount,
+            }
+            .into());
+        }
+
+        let new_price = price_at(circulating_supply + token_amount, &params);
+
+        Ok((token_amount, new_price))
+    }
+
+    fn quote_sell(&self, pool_id: B256, token_amount: U256) -> Result<(U256, U256), PumpUpErrors> {
+        let (token_address, _creator, weth_collected, _last_price, is_transitioned, _strategy) =
+            host().pool_info(*self.pool_state_manager, pool_id)?;
+
+        if is_transitioned {
+            return Err(PoolTransitioned {}.into());
+        }
+
+        if token_amount.is_zero() {
+            return Err(InvalidAmount {}.into());
+        }
+
+        let params = self.get_params(pool_id)?;
+        let circulating_supply = self.circulating_supply(&token_address, &params)?;
+
+        if token_amount > circulating_supply {
+            return Err(InvalidAmount {}.into());
+        }
+
+        let new_circulating_supply = circulating_supply - token_amount;
+        let weth_to_return = cost_between(
+            new_circulating_supply,
+            token_amount,
+            &params,
+            Rounding::Down,
+        );
+
+        if weth_to_return > weth_collected {
+            return Err(InsufficientLiquidity {
+                available: weth_collected,
+                requested: weth_to_return,
+            }
+            .into());
+        }
+
+        let new_price = price_at(new_circulating_supply, &params);
+
+        Ok((weth_to_return, new_price))
+    }
+
+    fn spot_price(&self, pool_id: B256) -> Result<U256, PumpUpErrors> {
+        self.get_current_price(pool_id)
+    }
+}
+
+// Internal functions
+impl ExponentialBondingCurve {
+    fn get_params(&self, pool_id: B256) -> Result<ExponentialParameters, PumpUpErrors> {
+        let initial_price = self.initial_prices.get(pool_id);
+
+        if initial_price.is_zero() {
+            return Err(InvalidPoolId {}.into());
+        }
+
+        Ok(ExponentialParameters {
+            initial_price,
+            growth_rate: self.growth_rates.get(pool_id),
+            total_supply: self.total_supplies.get(pool_id),
+        })
+    }
+
+    // Circulating supply (total supply minus the manager's balance), rejecting token reads that
+    // contradict the pool's stored total supply
+    fn circulating_supply(
+        &self,
+        token_address: &Address,
+        params: &ExponentialParameters,
+    ) -> Result<U256, PumpUpErrors> {
+        let total_token_supply = host().total_supply(*token_address)?;
+        let held_by_manager = host().balance_of(*token_address, *self.pool_state_manager)?;
+
+        if total_token_supply.is_zero() || held_by_manager > total_token_supply {
+            return Err(InconsistentTokenState {}.into());
+        }
+
+        let circulating_supply = total_token_supply - held_by_manager;
+        if circulating_supply > params.total_supply {
+            return Err(InconsistentTokenState {}.into());
+        }
+
+        Ok(circulating_supply)
+    }
+}
+
+// Price at a given supply: initial_price * e^(growth_rate * supply / total_supply)
+pub(crate) fn price_at(supply: U256, params: &ExponentialParameters) -> U256 {
+    saturated(try_price_at::<Saturating>(supply, params))
+}
+
+// price_at on the given arithmetic
+pub(crate) fn try_price_at<A: Arithmetic>(
+    supply: U256,
+    params: &ExponentialParameters,
+) -> Result<U256, MathOp> {
+    let percentage_sold =
+        A::divide_fixed_point(supply.min(params.total_supply), params.total_supply)?;
+    let exponent = A::multiply_fixed_point(params.growth_rate, percentage_sold)?;
+
+    A::multiply_fixed_point(params.initial_price, exp_approx(exponent))
+}
+
+// Price at the full supply: initial_price * e^growth_rate
+pub(crate) fn max_price(params: &ExponentialParameters) -> U256 {
+    multiply_fixed_point(params.initial_price, exp_approx(params.growth_rate))
+}
+
+// Price change per whole token at a given supply: price * growth_rate / total_supply
+pub(crate) fn slope_at(supply: U256, params: &ExponentialParameters) -> U256 {
+    divide_fixed_point(
+        multiply_fixed_point(price_at(supply, params), params.growth_rate),
+        params.total_supply,
+    )
+}
+
+// Supply at which the price reaches `price`: total_supply * ln(price / initial_price) / growth_rate
+pub(crate) fn supply_at_price(price: U256, params: &ExponentialParameters) -> U256 {
+    if price <= params.initial_price {
+        return U256::ZERO;
+    }
+
+    let log_growth = ln_fixed_point(divide_fixed_point(price, params.initial_price));
+    let percentage_sold = divide_fixed_point(log_growth, params.growth_rate).min(SCALE_FACTOR);
+
+    multiply_fixed_point(percentage_sold, params.total_supply)
+}
+
+// WETH cost of token_amount tokens starting at supply, from the closed-form integral
+// (price(supply + amount) - price(supply)) * total_supply / growth_rate, rounded in the given
+// direction
+pub(crate) fn cost_between(
+    supply: U256,
+    token_amount: U256,
+    params: &ExponentialParameters,
+    rounding: Rounding,
+) -> U256 {
+    saturated(try_cost_between::<Saturating>(
+        supply,
+        token_amount,
+        params,
+        rounding,
+    ))
+}
+
+// cost_between on the given arithmetic
+pub(crate) fn try_cost_between<A: Arithmetic>(
+    supply: U256,
+    token_amount: U256,
+    params: &ExponentialParameters,
+    rounding: Rounding,
+) -> Result<U256, MathOp> {
+    let start_price = try_price_at::<A>(supply, params)?;
+    let end_price = try_price_at::<A>(A::add(supply, token_amount)?, params)?;
+
+    A::mul_div(
+        end_price.saturating_sub(start_price),
+        params.total_supply,
+        params.growth_rate,
+        rounding,
+    )
+}
+
+// Tokens bought for weth_amount starting at supply; inverting the integral needs a log, so
+// binary search for the largest amount whose cost fits within weth_amount
+fn tokens_for_weth(supply: U256, weth_amount: U256, params: &ExponentialParameters) -> U256 {
+    let remaining = params.total_supply.saturating_sub(supply);
+    if cost_between(supply, remaining, params, Rounding::Up) <= weth_amount {
+        return remaining;
+    }
+
+    let mut min_tokens = U256::ZERO;
+    let mut max_tokens = remaining;
+
+    // Limit iterations
+    for _ in 0..100 {
+        if max_tokens.saturating_sub(min_tokens) <= U256::from(1u8) {
+            break;
+        }
+
+        let mid_tokens = min_tokens.saturating_add(max_tokens.saturating_sub(min_tokens) / TWO);
+        if cost_between(supply, mid_tokens, params, Rounding::Up) <= weth_amount {
+            min_tokens = mid_tokens;
+        } else {
+            max_tokens = mid_tokens;
+        }
+    }
+
+    min_tokens
+}
@@ -0,0 +1,365 @@
+// Reads the strategies make from other contracts: ERC20 supply, balances and decimals of a
+// pool's token and pool info from its manager. They sit behind HostCalls so the curve logic runs
+// against canned answers under test. On chain they go through the typed sol_interface! bindings,
+// or through hand-encoded RawCalls when built with the `raw-calls` feature.
+
+use alloy_primitives::{Address, B256, U256};
+
+use crate::{PoolInfo, PumpUpErrors};
+
+// External reads used by pricing. None of them may change state: on chain each is a static call,
+// and a revert surfaces as the Erc20CallFailed or ManagerCallFailed error.
+pub(crate) trait HostCalls {
+    // ERC20 totalSupply() of a token
+    fn total_supply(&self, token: Address) -> Result<U256, PumpUpErrors>;
+
+    // ERC20 balanceOf(account) of a token
+    fn balance_of(&self, token: Address, account: Address) -> Result<U256, PumpUpErrors>;
+
+    // ERC20 decimals() of a token
+    fn decimals(&self, token: Address) -> Result<u8, PumpUpErrors>;
+
+    // getPoolInfo(pool_id) of a manager
+    fn pool_info(&self, manager: Address, pool_id: B256) -> Result<PoolInfo, PumpUpErrors>;
+}
+
+// Host the contracts read through: live calls on chain, the mock under test
+#[cfg(all(not(test), not(feature = "raw-calls")))]
+pub(crate) fn host() -> typed::InterfaceHost {
+    typed::InterfaceHost
+}
+
+#[cfg(all(not(test), feature = "raw-calls"))]
+pub(crate) fn host() -> raw::RawCallHost {
+    raw::RawCallHost
+}
+
+#[cfg(test)]
+pub(crate) fn host() -> mock::MockHost {
+    mock::MockHost
+}
+
+// Typed calls, the default on chain. The interface functions are declared view, so the bindings
+// make static calls.
+#[cfg_attr(any(test, feature = "raw-calls"), allow(dead_code))]
+pub(crate) mod typed {
+    use alloy_primitives::{Address, B256, U256};
+    use stylus_sdk::call::{Call, Error};
+    use stylus_sdk::prelude::*;
+
+    use super::HostCalls;
+    use crate::{
+        Erc20CallFailed, Erc20InvalidResult, ManagerCallFailed, ManagerInvalidResult, PoolInfo,
+        PumpUpErrors,
+    };
+
+    sol_interface! {
+        interface IERC20 {
+            function totalSupply() external view returns (uint256);
+            function balanceOf(address account) external view returns (uint256);
+            function decimals() external view returns (uint8);
+        }
+
+        interface IPoolStateManager {
+            function getPoolInfo(bytes32 pool_id)
+                external
+                view
+                returns (address, address, uint256, uint256, bool, bytes32);
+        }
+    }
+
+    // Map a failed typed call to the caller's errors: a revert to `failed`, undecodable return
+    // data to `invalid`
+    fn call_error(error: Error, failed: PumpUpErrors, invalid: PumpUpErrors) -> PumpUpErrors {
+        match error {
+            Error::Revert(_) => failed,
+            Error::AbiDecodingFailed(_) => invalid,
+        }
+    }
+
+    fn erc20_error(error: Error) -> PumpUpErrors {
+        call_error(
+            error,
+            Erc20CallFailed {}.into(),
+            Erc20InvalidResult {}.into(),
+        )
+    }
+
+    fn manager_error(error: Error) -> PumpUpErrors {
+        call_error(
+            error,
+            ManagerCallFailed {}.into(),
+            ManagerInvalidResult {}.into(),
+        )
+    }
+
+    // HostCalls over the sol_interface! bindings
+    pub(crate) struct InterfaceHost;
+
+    impl HostCalls for InterfaceHost {
+        fn total_supply(&self, token: Address) -> Result<U256, PumpUpErrors> {
+            IERC20::new(token)
+                .total_supply(Call::new())
+                .map_err(erc20_error)
+        }
+
+        fn balance_of(&self, token: Address, account: Address) -> Result<U256, PumpUpErrors> {
+            IERC20::new(token)
+                .balance_of(Call::new(), account)
+                .map_err(erc20_error)
+        }
+
+        fn decimals(&self, token: Address) -> Result<u8, PumpUpErrors> {
+            IERC20::new(token)
+                .decimals(Call::new())
+                .map_err(erc20_error)
+        }
+
+        fn pool_info(&self, manager: Address, pool_id: B256) -> Result<PoolInfo, PumpUpErrors> {
+            IPoolStateManager::new(manager)
+                .get_pool_info(Call::new(), pool_id)
+                .map_err(manager_error)
+        }
+    }
+}
+
+// Hand-encoded calls, behind the `raw-calls` feature
+#[cfg_attr(any(test, not(feature = "raw-calls")), allow(dead_code))]
+pub(crate) mod raw {
+    use alloc::vec::Vec;
+    use alloy_primitives::{Address, B256, U256};
+    use stylus_sdk::call::RawCall;
+
+    use super::HostCalls;
+    use crate::{
+        Erc20CallFailed, Erc20InvalidResult, ManagerCallFailed, ManagerInvalidResult, PoolInfo,
+        PumpUpErrors,
+    };
+
+    // keccak256("totalSupply()")
+    pub(crate) const TOTAL_SUPPLY_SELECTOR: [u8; 4] = [0x18, 0x16, 0x0d, 0xdd];
+    // keccak256("balanceOf(address)")
+    pub(crate) const BALANCE_OF_SELECTOR: [u8; 4] = [0x70, 0xa0, 0x82, 0x31];
+    // keccak256("decimals()")
+    pub(crate) const DECIMALS_SELECTOR: [u8; 4] = [0x31, 0x3c, 0xe5, 0x67];
+    // keccak256("getPoolInfo(bytes32)")
+    pub(crate) const GET_POOL_INFO_SELECTOR: [u8; 4] = [0x09, 0xf2, 0xc0, 0x19];
+
+    // HostCalls over hand-encoded RawCalls, for builds where the typed bindings can't be used.
+    // Every read is a static call, so the callee can't change state.
+    pub(crate) struct RawCallHost;
+
+    impl HostCalls for RawCallHost {
+        fn total_supply(&self, token: Address) -> Result<U256, PumpUpErrors> {
+            let result = RawCall::new_static()
+                .call(token, &TOTAL_SUPPLY_SELECTOR)
+                .map_err(|_| -> PumpUpErrors { Erc20CallFailed {}.into() })?;
+
+            decode_uint(&result).ok_or_else(|| Erc20InvalidResult {}.into())
+        }
+
+        fn balance_of(&self, token: Address, account: Address) -> Result<U256, PumpUpErrors> {
+            let mut call_data = Vec::with_capacity(36);
+            call_data.extend_from_slice(&BALANCE_OF_SELECTOR);
+            call_data.extend_from_slice(&[0; 12]);
+            call_data.extend_from_slice(account.as_slice());
+
+            let result = RawCall::new_static()
+                .call(token, &call_data)
+                .map_err(|_| -> PumpUpErrors { Erc20CallFailed {}.into() })?;
+
+            decode_uint(&result).ok_or_else(|| Erc20InvalidResult {}.into())
+        }
+
+        fn decimals(&self, token: Address) -> Result<u8, PumpUpErrors> {
+            let result = RawCall::new_static()
+                .call(token, &DECIMALS_SELECTOR)
+                .map_err(|_| -> PumpUpErrors { Erc20CallFailed {}.into() })?;
+
+            // Parse uint8 from the result
+            if result.len() < 32 || result[0..31].iter().any(|b| *b != 0) {
+                return Err(Erc20InvalidResult {}.into());
+            }
+
+            Ok(result[31])
+        }
+
+        fn pool_info(&self, manager: Address, pool_id: B256) -> Result<PoolInfo, PumpUpErrors> {
+            let mut call_data = Vec::with_capacity(36);
+            call_data.extend_from_slice(&GET_POOL_INFO_SELECTOR);
+            call_data.extend_from_slice(pool_id.as_slice());
+
+            let result = RawCall::new_static()
+                .call(manager, &call_data)
+                .map_err(|_| -> PumpUpErrors { ManagerCallFailed {}.into() })?;
+
+            decode_pool_info(&result).ok_or_else(|| ManagerInvalidResult {}.into())
+        }
+    }
+
+    // First word of return data as a uint256
+    fn decode_uint(result: &[u8]) -> Option<U256> {
+        result.get(0..32).map(U256::from_be_slice)
+    }
+
+    // getPoolInfo return data: six words, the bool in the fifth and the strategy id in the sixth
+    pub(crate) fn decode_pool_info(result: &[u8]) -> Option<PoolInfo> {
+        if result.len() < 192 {
+            return None;
+        }
+
+        let word = |index: usize| &result[index * 32..(index + 1) * 32];
+        let address = |index: usize| Address::from_slice(&word(index)[12..]);
+
+        let is_transitioned = match U256::from_be_slice(word(4)) {
+            value if value.is_zero() => false,
+            value if value == U256::from(1u8) => true,
+            _ => return None,
+        };
+
+        Some((
+            address(0),
+            address(1),
+            U256::from_be_slice(word(2)),
+            U256::from_be_slice(word(3)),
+            is_transitioned,
+            B256::from_slice(word(5)),
+        ))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod mock {
+    use super::HostCalls;
+    use crate::{Erc20CallFailed, ManagerCallFailed, PoolInfo, PumpUpErrors};
+    use alloy_primitives::{Address, B256, U256};
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    struct MockState {
+        total_supplies: HashMap<Address, U256>,
+        balances: HashMap<(Address, Address), U256>,
+        decimals: HashMap<Address, u8>,
+        pool_infos: HashMap<(Address, B256), PoolInfo>,
+        pool_info_reads: usize,
+    }
+
+    std::thread_local! {
+        static STATE: RefCell<MockState> = RefCell::new(MockState::default());
+    }
+
+    // HostCalls answered from per-thread canned values. Reads nothing was set for fail as a
+    // reverting call would.
+    pub(crate) struct MockHost;
+
+    impl MockHost {
+        // Forget every canned value
+        pub(crate) fn reset() {
+            STATE.with(|state| *state.borrow_mut() = MockState::default());
+        }
+
+        // Token with `total_supply` of which `holder` has `balance`
+        pub(crate) fn set_token(
+            token: Address,
+            total_supply: U256,
+            holder: Address,
+            balance: U256,
+        ) {
+            STATE.with(|state| {
+                let mut state = state.borrow_mut();
+                state.total_supplies.insert(token, total_supply);
+                state.balances.insert((token, holder), balance);
+            });
+        }
+
+        // Make every read of a token fail
+        pub(crate) fn fail_token(token: Address) {
+            STATE.with(|state| {
+                let mut state = state.borrow_mut();
+                state.total_supplies.remove(&token);
+                state
+                    .balances
+                    .retain(|(balance_token, _), _| *balance_token != token);
+                state.decimals.remove(&token);
+            });
+        }
+
+        // Make a token report `decimals`
+        pub(crate) fn set_decimals(token: Address, decimals: u8) {
+            STATE.with(|state| {
+                state.borrow_mut().decimals.insert(token, decimals);
+            });
+        }
+
+        // Move `amount` of a token from `from` to `to` the way an approved transferFrom would.
+        // Returns false, as the token would revert, unless `from` holds the amount.
+        pub(crate) fn transfer_from(
+            token: Address,
+            from: Address,
+            to: Address,
+            amount: U256,
+        ) -> bool {
+            STATE.with(|state| {
+                let mut state = state.borrow_mut();
+                let from_balance = state
+                    .balances
+                    .get(&(token, from))
+                    .copied()
+                    .unwrap_or_default();
+                if from_balance < amount {
+                    return false;
+                }
+
+                state.balances.insert((token, from), from_balance - amount);
+                *state.balances.entry((token, to)).or_default() += amount;
+                true
+            })
+        }
+
+        // Number of pool info reads since the last reset
+        pub(crate) fn pool_info_reads() -> usize {
+            STATE.with(|state| state.borrow().pool_info_reads)
+        }
+
+        // Pool info a manager reports for a pool
+        pub(crate) fn set_pool_info(manager: Address, pool_id: B256, info: PoolInfo) {
+            STATE.with(|state| {
+                state
+                    .borrow_mut()
+                    .pool_infos
+                    .insert((manager, pool_id), info);
+            });
+        }
+    }
+
+    impl HostCalls for MockHost {
+        fn total_supply(&self, token: Address) -> Result<U256, PumpUpErrors> {
+            STATE
+                .with(|state| state.borrow().total_supplies.get(&token).copied())
+                .ok_or_else(|| Erc20CallFailed {}.into())
+        }
+
+        fn balance_of(&self, token: Address, account: Address) -> Result<U256, PumpUpErrors> {
+            STATE
+                .with(|state| state.borrow().balances.get(&(token, account)).copied())
+                .ok_or_else(|| Erc20CallFailed {}.into())
+        }
+
+        fn decimals(&self, token: Address) -> Result<u8, PumpUpErrors> {
+            STATE
+                .with(|state| state.borrow().decimals.get(&token).copied())
+                .ok_or_else(|| Erc20CallFailed {}.into())
+        }
+
+        fn pool_info(&self, manager: Address, pool_id: B256) -> Result<PoolInfo, PumpUpErrors> {
+            STATE
+                .with(|state| {
+                    let mut state = state.borrow_mut();
+                    state.pool_info_reads += 1;
+                    state.pool_infos.get(&(manager, pool_id)).copied()
+                })
+                .ok_or_else(|| ManagerCallFailed {}.into())
+        }
+    }
+}
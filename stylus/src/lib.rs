@@ -2,28 +2,375 @@
 //! Sigmoid Bonding Curve for Arbitrum Stylus
 //!
 
-#![cfg_attr(not(feature = "export-abi"), no_main)]
+#![cfg_attr(not(any(feature = "export-abi", test)), no_main)]
+#![recursion_limit = "256"]
 extern crate alloc;
 
-use alloc::{string::String, vec, vec::Vec};
-use alloy_primitives::{Address, B256, U256};
-use stylus_sdk::{abi::Bytes, call::RawCall, evm, msg, prelude::*};
+pub mod exponential;
+mod host;
+pub mod linear;
+mod piecewise;
+mod pool_info_cache;
+mod roles;
+mod tick_math;
+mod virtual_reserves;
+
+#[cfg(test)]
+mod tests;
+
+// Each strategy feature makes its curve the contract entrypoint, so only one can be on
+#[cfg(all(feature = "linear", feature = "exponential"))]
+compile_error!("features `linear` and `exponential` are mutually exclusive");
+
+use alloc::{format, string::String, vec, vec::Vec};
+use alloy_primitives::{Address, FixedBytes, B256, I256, U128, U256, U32, U64, U8};
+use alloy_sol_types::{sol, sol_data::Array, SolError, SolType};
+use host::{host, HostCalls};
+use pumpup_math::arithmetic::{saturated, Arithmetic, Checked, MathOp, Saturating};
+use pumpup_math::sigmoid::{self, SigmoidCurve};
+use pumpup_math::{
+    divide_fixed_point, exp_approx, ln_fixed_point, mul_div_rounding_down, mul_div_rounding_up,
+    multiply_fixed_point, Rounding, MAX_EXP_INPUT, SCALE_FACTOR, TWO,
+};
+use pumpup_strategy::BondingCurveStrategy;
+use roles::{is_known_role, INITIALIZER_ROLE, PARAM_ADMIN_ROLE, PAUSER_ROLE};
+use stylus_sdk::{
+    abi::{AbiType, Bytes, ConstString, Router},
+    block,
+    call::RawCall,
+    contract, crypto, evm, msg,
+    prelude::*,
+    types::AddressVM,
+};
+use tick_math::{
+    amounts_for_liquidity, get_sqrt_price_at_tick, get_tick_at_sqrt_price, liquidity_for_amounts,
+    sqrt_price_x96_from_price, usable_tick_range,
+};
 
 // Constants for curve parameters
 const STRATEGY_TYPE: &str = "BondingCurve";
 const STRATEGY_NAME: &str = "Sigmoid";
+const CODE_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 // Default parameters (scaled by 10^18)
 const DEFAULT_MAX_PRICE_FACTOR: U256 = U256::from_limbs([10_000_000_000_000_000_000u64, 0, 0, 0]); // 10.0
 const DEFAULT_STEEPNESS: U256 = U256::from_limbs([10_000_000_000_000_000_000u64, 0, 0, 0]); // 10.0
 const DEFAULT_MIDPOINT: U256 = U256::from_limbs([500_000_000_000_000_000u64, 0, 0, 0]); // 0.5 (50%)
 
-// Scaling factor for fixed-point math (10^18)
-const SCALE_FACTOR: U256 = U256::from_limbs([1_000_000_000_000_000_000u64, 0, 0, 0]);
-const TWO: U256 = U256::from_limbs([2u64, 0, 0, 0]);
-const THOUSAND: U256 = U256::from_limbs([1000u64, 0, 0, 0]);
+// Bounds for owner-set default parameters (scaled by 10^18)
+const MIN_DEFAULT_MAX_PRICE_FACTOR: U256 =
+    U256::from_limbs([1_000_000_000_000_000_000u64, 0, 0, 0]); // 1.0
+const MAX_DEFAULT_MAX_PRICE_FACTOR: U256 =
+    U256::from_limbs([3_875_820_019_684_212_736u64, 54, 0, 0]); // 1000.0
+const MAX_DEFAULT_STEEPNESS: U256 = U256::from_limbs([13_106_511_852_580_896_768u64, 2, 0, 0]); // 50.0
+
+// Curve parameter ids used by the owner-set parameter bounds
+const CURVE_PARAM_INITIAL_PRICE: u8 = 0;
+const CURVE_PARAM_MAX_PRICE_FACTOR: u8 = 1;
+const CURVE_PARAM_STEEPNESS: u8 = 2;
+const CURVE_PARAM_MIDPOINT: u8 = 3;
+const CURVE_PARAM_TOTAL_SUPPLY: u8 = 4;
+
+// Pricing models a pool can use: the sigmoid curve, constant product over virtual reserves, a
+// piecewise curve of flat, linear and sigmoid segments, or the linear and exponential curves
+const CURVE_KIND_SIGMOID: u8 = 0;
+const CURVE_KIND_VIRTUAL_RESERVES: u8 = 1;
+const CURVE_KIND_PIECEWISE: u8 = 2;
+const CURVE_KIND_LINEAR: u8 = 3;
+const CURVE_KIND_EXPONENTIAL: u8 = 4;
+
+// Pricing models hosted by this entrypoint as (curve kind, strategy name)
+const REGISTERED_STRATEGIES: [(u8, &str); 5] = [
+    (CURVE_KIND_SIGMOID, STRATEGY_NAME),
+    (CURVE_KIND_VIRTUAL_RESERVES, "VirtualReserves"),
+    (CURVE_KIND_PIECEWISE, "Piecewise"),
+    (CURVE_KIND_LINEAR, linear::LINEAR_STRATEGY_NAME),
+    (
+        CURVE_KIND_EXPONENTIAL,
+        exponential::EXPONENTIAL_STRATEGY_NAME,
+    ),
+];
+
+// Bit flags for the DefaultsApplied event's `which_fields`
+const DEFAULT_FIELD_MAX_PRICE_FACTOR: u8 = 1 << 0;
+const DEFAULT_FIELD_STEEPNESS: u8 = 1 << 1;
+const DEFAULT_FIELD_MIDPOINT: u8 = 1 << 2;
+
+// Fixed-point constants beyond the shared SCALE_FACTOR and TWO
 const MILLION: U256 = U256::from_limbs([1_000_000u64, 0, 0, 0]);
 
+// Newton-Raphson steps tried before the token amount solver falls back to binary search
+const NEWTON_MAX_ITERATIONS: usize = 8;
+
+// Largest gap the token amount solver leaves between the cost of its answer and the base asset
+// amount, always in the pool's favour (0.001 in 18-decimal units)
+const SOLVER_TOLERANCE: U256 = U256::from_limbs([1_000_000_000_000_000u64, 0, 0, 0]);
+
+// Basis points denominator (100%)
+const BPS_DENOMINATOR: U256 = U256::from_limbs([10_000u64, 0, 0, 0]);
+
+// Supply breakpoints of the per-pool cumulative cost table
+const COST_TABLE_BREAKPOINTS: u64 = 32;
+
+// Most payees the protocol fee can be split across
+const MAX_FEE_PAYEES: usize = 8;
+
+// Most segments a piecewise curve can have
+const MAX_CURVE_SEGMENTS: usize = 8;
+
+// Highest combined protocol and creator fee in bps (10%)
+const MAX_TOTAL_FEE_BPS: U256 = U256::from_limbs([1_000u64, 0, 0, 0]);
+
+// Default quote commitment settings
+const DEFAULT_QUOTE_VALIDITY_BLOCKS: u64 = 20;
+const DEFAULT_QUOTE_TOLERANCE_BPS: U256 = U256::from_limbs([50u64, 0, 0, 0]); // 0.5%
+
+// EIP-712 domain and type of price attestations
+const EIP712_DOMAIN_TYPE: &str =
+    "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+const EIP712_NAME: &str = "SigmoidBondingCurve";
+const EIP712_VERSION: &str = "1";
+const PRICE_ATTESTATION_TYPE: &str =
+    "PriceAttestation(bytes32 poolId,uint256 price,uint256 supply,uint256 blockNumber)";
+
+// Upper bound on the per-pool trade log ring buffer
+const MAX_TRADE_LOG_CAPACITY: u64 = 256;
+
+// Price observations kept per pool for TWAPs, at most one per second
+const OBSERVATION_CAPACITY: u64 = 128;
+
+// Decimals of the answers the AggregatorV3-style price feed reports (prices are 18-decimal
+// amounts of the base asset)
+const PRICE_FEED_DECIMALS: u8 = 18;
+
+// Wash trading heuristics: opposite trades by the same address within this many blocks offset
+// each other, and an address is flagged after this many offsetting trades
+const WASH_TRADE_WINDOW_BLOCKS: u64 = 10;
+const WASH_FLAG_THRESHOLD: u64 = 3;
+
+// Tick spacing of the Uniswap v4 pool PoolStateManager graduates pools into
+const GRADUATION_TICK_SPACING: i32 = 60;
+
+// Revert messages
+const ERR_NOT_POOL_STATE_MANAGER: &str = "Not Pool State Manager";
+const ERR_NOT_OWNER: &str = "Ownable: caller is not the owner";
+const ERR_ZERO_ADDRESS_OWNER: &str = "New owner cannot be the zero address";
+const ERR_ZERO_ADDRESS_MANAGER: &str = "New manager cannot be the zero address";
+const ERR_POOL_NOT_BOUND_TO_MANAGER: &str = "Pool not bound to manager";
+const ERR_INVALID_POOL_ID: &str = "Invalid Pool ID";
+const ERR_POOL_TRANSITIONED: &str = "Pool has transitioned";
+const ERR_INVALID_AMOUNT: &str = "Invalid Amount";
+const ERR_INSUFFICIENT_LIQUIDITY: &str = "Insufficient Liquidity";
+const ERR_INVALID_PARAMETERS_LENGTH: &str = "Invalid Parameters - not enough data";
+const ERR_INVALID_PARAMETERS_ZERO: &str = "Invalid Parameters - zero values not allowed";
+const ERR_INSUFFICIENT_DATA_LENGTH: &str = "Insufficient data length";
+const ERR_ERC20_CALL_FAILED: &str = "ERC20 call failed";
+const ERR_ERC20_INVALID_RESULT: &str = "Invalid result length from ERC20 call";
+const ERR_MANAGER_CALL_FAILED: &str = "Pool state manager call failed";
+const ERR_MANAGER_INVALID_RESULT: &str = "Invalid result length from pool state manager";
+const ERR_INVALID_DISCOUNT: &str = "Discount exceeds 100%";
+const ERR_TRANSITION_PENDING: &str = "Transition pending";
+const ERR_NO_TRANSITION_PENDING: &str = "No transition pending";
+const ERR_INVALID_TOLERANCE: &str = "Tolerance exceeds 100%";
+const ERR_UNKNOWN_QUOTE_COMMITMENT: &str = "Unknown quote commitment";
+const ERR_QUOTE_COMMITMENT_EXPIRED: &str = "Quote commitment expired";
+const ERR_QUOTE_MOVED: &str = "Quote moved beyond tolerance";
+const ERR_ZERO_ADDRESS_QUOTE_TOKEN: &str = "Quote token cannot be the zero address";
+const ERR_INVALID_DEFAULT_PARAMETERS: &str = "Invalid default parameters";
+const ERR_STRATEGY_DEPRECATED: &str = "Strategy deprecated";
+const ERR_LAUNCH_NOT_STARTED: &str = "Launch not started";
+const ERR_NOT_ALLOWLISTED: &str = "Not allowlisted";
+const ERR_LAUNCH_CAP_EXCEEDED: &str = "Buy exceeds launch cap";
+const ERR_INVALID_LAUNCH_PHASES: &str = "Invalid launch phases";
+const ERR_NOT_CREATOR_OR_OWNER: &str = "Caller is not the pool creator or owner";
+const ERR_INVALID_TRADE_LOG_CAPACITY: &str = "Trade log capacity too large";
+const ERR_INCONSISTENT_TOKEN_STATE: &str = "Inconsistent token state";
+const ERR_ERC20_TRANSFER_FAILED: &str = "ERC20 transfer failed";
+const ERR_RAISE_CAP_EXCEEDED: &str = "Raise cap exceeded";
+const ERR_BELOW_MINIMUM_TRADE: &str = "Trade below minimum size";
+const ERR_NO_GRADUATION_TARGET: &str = "No graduation target";
+const ERR_NOT_POOL_CREATOR: &str = "Not pool creator";
+const ERR_BELOW_EARLY_GRADUATION_MINIMUM: &str = "Raised below early graduation minimum";
+const ERR_NO_EARLY_GRADUATION_REQUEST: &str = "No early graduation request";
+const ERR_EARLY_GRADUATION_TIMELOCKED: &str = "Early graduation timelocked";
+const ERR_INVALID_FEE_SPLIT: &str = "Invalid fee split";
+const ERR_NOT_GUARDIAN: &str = "Not guardian";
+const ERR_BLACKLISTED: &str = "Address blacklisted";
+const ERR_COMPLIANCE_DENIED: &str = "Trade denied by compliance module";
+const ERR_COMPLIANCE_CALL_FAILED: &str = "Compliance module call failed";
+const ERR_INVALID_CASHBACK: &str = "Cashback exceeds 100%";
+const ERR_POOL_WINDING_DOWN: &str = "Pool winding down";
+const ERR_POOL_NOT_WINDING_DOWN: &str = "Pool not winding down";
+const ERR_MALFORMED_CURVE_PARAMS: &str = "Malformed curve parameters";
+const ERR_MISSING_ROLE: &str = "Caller is missing the required role";
+const ERR_UNKNOWN_ROLE: &str = "Unknown role";
+const ERR_TRADING_PAUSED: &str = "Trading is paused";
+const ERR_CURVE_UPDATE_OUT_OF_BOUNDS: &str = "Curve update out of bounds";
+const ERR_INITIAL_PRICE_OUT_OF_BOUNDS: &str = "Initial price out of bounds";
+const ERR_MAX_PRICE_FACTOR_OUT_OF_BOUNDS: &str = "Max price factor out of bounds";
+const ERR_STEEPNESS_OUT_OF_BOUNDS: &str = "Steepness out of bounds";
+const ERR_MIDPOINT_OUT_OF_BOUNDS: &str = "Midpoint out of bounds";
+const ERR_TOTAL_SUPPLY_OUT_OF_BOUNDS: &str = "Total supply out of bounds";
+const ERR_INVALID_PARAM_BOUNDS: &str = "Invalid parameter bounds";
+const ERR_POOL_ALREADY_INITIALIZED: &str = "Pool already initialized";
+const ERR_INVALID_FEE: &str = "Invalid fee";
+const ERR_NOTHING_TO_CLAIM: &str = "Nothing to claim";
+const ERR_INVALID_REFERRAL_SHARE: &str = "Invalid referral share";
+const ERR_SLIPPAGE_EXCEEDED: &str = "Slippage exceeded";
+const ERR_EXPIRED: &str = "Deadline expired";
+const ERR_INVALID_CURVE_KIND: &str = "Unknown curve kind";
+const ERR_CURVE_KIND_LOCKED: &str = "Curve kind cannot change once tokens are sold";
+const ERR_INVALID_CURVE_SEGMENTS: &str = "Invalid curve segments";
+const ERR_SUPPLY_NOT_SYNCED: &str = "Circulating supply is not tracked for this pool yet";
+const ERR_UNKNOWN_SELECTOR: &str = "No function matches the selector";
+const ERR_INSUFFICIENT_OBSERVATIONS: &str = "Pool has no price observations yet";
+const ERR_OBSERVATION_TOO_OLD: &str = "Requested time is before the oldest observation";
+const ERR_MATH_OVERFLOW: &str = "Curve math overflowed";
+const ERR_INVARIANT_VIOLATED: &str = "Curve math left the curve's price envelope";
+const ERR_INVALID_BASE_ASSET: &str =
+    "Base asset decimals are out of range or do not match the asset";
+const ERR_BASE_ASSET_LOCKED: &str = "Base asset cannot change once the pool has traded";
+const ERR_NATIVE_TRANSFER_FAILED: &str = "Native currency transfer failed";
+
+// Error catalog: (error signature, message) for every custom error the contract can revert with
+const ERROR_CATALOG: &[(&str, &str)] = &[
+    (NotPoolStateManager::SIGNATURE, ERR_NOT_POOL_STATE_MANAGER),
+    (NotOwner::SIGNATURE, ERR_NOT_OWNER),
+    (ZeroAddressOwner::SIGNATURE, ERR_ZERO_ADDRESS_OWNER),
+    (ZeroAddressManager::SIGNATURE, ERR_ZERO_ADDRESS_MANAGER),
+    (
+        PoolNotBoundToManager::SIGNATURE,
+        ERR_POOL_NOT_BOUND_TO_MANAGER,
+    ),
+    (InvalidPoolId::SIGNATURE, ERR_INVALID_POOL_ID),
+    (PoolTransitioned::SIGNATURE, ERR_POOL_TRANSITIONED),
+    (InvalidAmount::SIGNATURE, ERR_INVALID_AMOUNT),
+    (InsufficientLiquidity::SIGNATURE, ERR_INSUFFICIENT_LIQUIDITY),
+    (
+        InvalidParametersLength::SIGNATURE,
+        ERR_INVALID_PARAMETERS_LENGTH,
+    ),
+    (
+        InvalidParametersZero::SIGNATURE,
+        ERR_INVALID_PARAMETERS_ZERO,
+    ),
+    (
+        InsufficientDataLength::SIGNATURE,
+        ERR_INSUFFICIENT_DATA_LENGTH,
+    ),
+    (Erc20CallFailed::SIGNATURE, ERR_ERC20_CALL_FAILED),
+    (Erc20InvalidResult::SIGNATURE, ERR_ERC20_INVALID_RESULT),
+    (ManagerCallFailed::SIGNATURE, ERR_MANAGER_CALL_FAILED),
+    (ManagerInvalidResult::SIGNATURE, ERR_MANAGER_INVALID_RESULT),
+    (InvalidDiscount::SIGNATURE, ERR_INVALID_DISCOUNT),
+    (TransitionPending::SIGNATURE, ERR_TRANSITION_PENDING),
+    (NoTransitionPending::SIGNATURE, ERR_NO_TRANSITION_PENDING),
+    (InvalidTolerance::SIGNATURE, ERR_INVALID_TOLERANCE),
+    (
+        UnknownQuoteCommitment::SIGNATURE,
+        ERR_UNKNOWN_QUOTE_COMMITMENT,
+    ),
+    (
+        QuoteCommitmentExpired::SIGNATURE,
+        ERR_QUOTE_COMMITMENT_EXPIRED,
+    ),
+    (QuoteMoved::SIGNATURE, ERR_QUOTE_MOVED),
+    (
+        ZeroAddressQuoteToken::SIGNATURE,
+        ERR_ZERO_ADDRESS_QUOTE_TOKEN,
+    ),
+    (
+        InvalidDefaultParameters::SIGNATURE,
+        ERR_INVALID_DEFAULT_PARAMETERS,
+    ),
+    (StrategyDeprecated::SIGNATURE, ERR_STRATEGY_DEPRECATED),
+    (LaunchNotStarted::SIGNATURE, ERR_LAUNCH_NOT_STARTED),
+    (NotAllowlisted::SIGNATURE, ERR_NOT_ALLOWLISTED),
+    (LaunchCapExceeded::SIGNATURE, ERR_LAUNCH_CAP_EXCEEDED),
+    (InvalidLaunchPhases::SIGNATURE, ERR_INVALID_LAUNCH_PHASES),
+    (NotCreatorOrOwner::SIGNATURE, ERR_NOT_CREATOR_OR_OWNER),
+    (
+        InvalidTradeLogCapacity::SIGNATURE,
+        ERR_INVALID_TRADE_LOG_CAPACITY,
+    ),
+    (
+        InconsistentTokenState::SIGNATURE,
+        ERR_INCONSISTENT_TOKEN_STATE,
+    ),
+    (Erc20TransferFailed::SIGNATURE, ERR_ERC20_TRANSFER_FAILED),
+    (RaiseCapExceeded::SIGNATURE, ERR_RAISE_CAP_EXCEEDED),
+    (BelowMinimumTrade::SIGNATURE, ERR_BELOW_MINIMUM_TRADE),
+    (NoGraduationTarget::SIGNATURE, ERR_NO_GRADUATION_TARGET),
+    (NotPoolCreator::SIGNATURE, ERR_NOT_POOL_CREATOR),
+    (
+        BelowEarlyGraduationMinimum::SIGNATURE,
+        ERR_BELOW_EARLY_GRADUATION_MINIMUM,
+    ),
+    (
+        NoEarlyGraduationRequest::SIGNATURE,
+        ERR_NO_EARLY_GRADUATION_REQUEST,
+    ),
+    (
+        EarlyGraduationTimelocked::SIGNATURE,
+        ERR_EARLY_GRADUATION_TIMELOCKED,
+    ),
+    (InvalidFeeSplit::SIGNATURE, ERR_INVALID_FEE_SPLIT),
+    (NotGuardian::SIGNATURE, ERR_NOT_GUARDIAN),
+    (Blacklisted::SIGNATURE, ERR_BLACKLISTED),
+    (ComplianceDenied::SIGNATURE, ERR_COMPLIANCE_DENIED),
+    (ComplianceCallFailed::SIGNATURE, ERR_COMPLIANCE_CALL_FAILED),
+    (InvalidCashback::SIGNATURE, ERR_INVALID_CASHBACK),
+    (PoolWindingDown::SIGNATURE, ERR_POOL_WINDING_DOWN),
+    (PoolNotWindingDown::SIGNATURE, ERR_POOL_NOT_WINDING_DOWN),
+    (MalformedCurveParams::SIGNATURE, ERR_MALFORMED_CURVE_PARAMS),
+    (MissingRole::SIGNATURE, ERR_MISSING_ROLE),
+    (UnknownRole::SIGNATURE, ERR_UNKNOWN_ROLE),
+    (TradingPaused::SIGNATURE, ERR_TRADING_PAUSED),
+    (
+        CurveUpdateOutOfBounds::SIGNATURE,
+        ERR_CURVE_UPDATE_OUT_OF_BOUNDS,
+    ),
+    (
+        InitialPriceOutOfBounds::SIGNATURE,
+        ERR_INITIAL_PRICE_OUT_OF_BOUNDS,
+    ),
+    (
+        MaxPriceFactorOutOfBounds::SIGNATURE,
+        ERR_MAX_PRICE_FACTOR_OUT_OF_BOUNDS,
+    ),
+    (SteepnessOutOfBounds::SIGNATURE, ERR_STEEPNESS_OUT_OF_BOUNDS),
+    (MidpointOutOfBounds::SIGNATURE, ERR_MIDPOINT_OUT_OF_BOUNDS),
+    (
+        TotalSupplyOutOfBounds::SIGNATURE,
+        ERR_TOTAL_SUPPLY_OUT_OF_BOUNDS,
+    ),
+    (InvalidParamBounds::SIGNATURE, ERR_INVALID_PARAM_BOUNDS),
+    (
+        PoolAlreadyInitialized::SIGNATURE,
+        ERR_POOL_ALREADY_INITIALIZED,
+    ),
+    (InvalidFee::SIGNATURE, ERR_INVALID_FEE),
+    (NothingToClaim::SIGNATURE, ERR_NOTHING_TO_CLAIM),
+    (InvalidReferralShare::SIGNATURE, ERR_INVALID_REFERRAL_SHARE),
+    (SlippageExceeded::SIGNATURE, ERR_SLIPPAGE_EXCEEDED),
+    (Expired::SIGNATURE, ERR_EXPIRED),
+    (InvalidCurveKind::SIGNATURE, ERR_INVALID_CURVE_KIND),
+    (CurveKindLocked::SIGNATURE, ERR_CURVE_KIND_LOCKED),
+    (InvalidCurveSegments::SIGNATURE, ERR_INVALID_CURVE_SEGMENTS),
+    (SupplyNotSynced::SIGNATURE, ERR_SUPPLY_NOT_SYNCED),
+    (UnknownSelector::SIGNATURE, ERR_UNKNOWN_SELECTOR),
+    (
+        InsufficientObservations::SIGNATURE,
+        ERR_INSUFFICIENT_OBSERVATIONS,
+    ),
+    (ObservationTooOld::SIGNATURE, ERR_OBSERVATION_TOO_OLD),
+    (MathOverflow::SIGNATURE, ERR_MATH_OVERFLOW),
+    (InvariantViolated::SIGNATURE, ERR_INVARIANT_VIOLATED),
+    (InvalidBaseAsset::SIGNATURE, ERR_INVALID_BASE_ASSET),
+    (BaseAssetLocked::SIGNATURE, ERR_BASE_ASSET_LOCKED),
+    (NativeTransferFailed::SIGNATURE, ERR_NATIVE_TRANSFER_FAILED),
+];
+
 // Storage structure for curve parameters
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub struct CurveParameters {
@@ -32,11 +379,526 @@ pub struct CurveParameters {
     pub steepness: U256,
     pub midpoint: U256,
     pub total_supply: U256,
+    pub curve_kind: u8,
+    pub segments: [CurveSegment; MAX_CURVE_SEGMENTS],
+    pub segment_count: usize,
+}
+
+// Sigmoid pools price through the shared math crate
+impl From<&CurveParameters> for SigmoidCurve {
+    fn from(params: &CurveParameters) -> Self {
+        SigmoidCurve {
+            initial_price: params.initial_price,
+            max_price_factor: params.max_price_factor,
+            steepness: params.steepness,
+            midpoint: params.midpoint,
+            total_supply: params.total_supply,
+        }
+    }
+}
+
+// Segment of a piecewise curve, running from where the previous segment ends (zero supply at
+// the initial price for the first) to end_supply at end_price
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CurveSegment {
+    pub shape: u8,
+    pub end_supply: U256,
+    pub end_price: U256,
+    pub steepness: U256,
+    pub midpoint: U256,
+}
+
+sol! {
+    // Parameters initialize expects, abi.encode'd by the pool state manager
+    struct CurveParams {
+        uint256 initialPrice;
+        uint256 maxPriceFactor;
+        uint256 steepness;
+        uint256 midpoint;
+        uint256 totalSupply;
+    }
+
+    // Segment of a piecewise curve as set_curve_segments expects it: shape 0 flat, 1 linear or
+    // 2 sigmoid, with steepness and midpoint only read for sigmoid segments
+    struct CurveSegmentParams {
+        uint8 shape;
+        uint256 endSupply;
+        uint256 endPrice;
+        uint256 steepness;
+        uint256 midpoint;
+    }
+
+    // Uniswap v4 pool key, identifying a pool by its currencies, fee, tick spacing and hooks
+    struct PoolKey {
+        address currency0;
+        address currency1;
+        uint24 fee;
+        int24 tickSpacing;
+        address hooks;
+    }
+
+    // Per-pool state exported by export_snapshot
+    struct PoolSnapshot {
+        bytes32 poolId;
+        address manager;
+        uint256 initialPrice;
+        uint256 maxPriceFactor;
+        uint256 steepness;
+        uint256 midpoint;
+        uint256 totalSupply;
+        uint256 sellPriceFloor;
+        uint256 insuranceReserve;
+        uint256 graduationBaseTarget;
+        uint256 raiseCap;
+        bool transitionPending;
+        uint256 minBuyBase;
+        uint256 minSellTokens;
+        uint256 priceTick;
+        uint256 tradeLogCount;
+        uint256 washVolume;
+    }
+
+    // Events
+    event TokensPurchased(
+        bytes32 indexed poolId,
+        uint256 baseAmount,
+        uint256 tokenAmount,
+        uint256 newPrice
+    );
+    event TokensSold(
+        bytes32 indexed poolId,
+        uint256 tokenAmount,
+        uint256 baseAmount,
+        uint256 newPrice
+    );
+    event CurveInitialized(
+        bytes32 indexed poolId,
+        uint256 initialPrice,
+        uint256 maxPriceFactor,
+        uint256 steepness,
+        uint256 midpoint,
+        uint256 totalSupply
+    );
+    event CurveReinitialized(
+        bytes32 indexed poolId,
+        uint256 initialPrice,
+        uint256 maxPriceFactor,
+        uint256 steepness,
+        uint256 midpoint,
+        uint256 totalSupply
+    );
+    event DefaultsApplied(bytes32 indexed poolId, uint8 fields);
+    event CurveParamsChanged(
+        bytes32 indexed poolId,
+        uint256 previousInitialPrice,
+        uint256 previousMaxPriceFactor,
+        uint256 previousSteepness,
+        uint256 previousMidpoint,
+        uint256 previousTotalSupply,
+        uint256 initialPrice,
+        uint256 maxPriceFactor,
+        uint256 steepness,
+        uint256 midpoint,
+        uint256 totalSupply
+    );
+    event TokensPurchasedCompact(bytes32 indexed poolId, uint256 packedAmounts);
+    event TokensSoldCompact(bytes32 indexed poolId, uint256 packedAmounts);
+    event FeeSplitSet(
+        address[] previousPayees,
+        uint256[] previousShares,
+        address[] payees,
+        uint256[] shares
+    );
+    event SteepnessClamped(bytes32 indexed poolId, uint256 requestedSteepness, uint256 steepness);
+    event QuoteCommitted(
+        bytes32 indexed commitment,
+        bytes32 indexed poolId,
+        uint256 baseAmount,
+        uint256 tokenAmount,
+        uint64 expiryBlock
+    );
+    event SellFloorApplied(bytes32 indexed poolId, uint256 shortfall, uint256 remainingReserve);
+    event PriceAttesterSet(address previousAttester, address attester);
+    event TradeLogCapacitySet(uint64 previousCapacity, uint64 capacity);
+    event SellPriceFloorSet(bytes32 indexed poolId, uint256 previousFloorPrice, uint256 floorPrice);
+    event InsuranceReserveFunded(bytes32 indexed poolId, uint256 amount, uint256 newReserve);
+    event GraduationTargetSet(
+        bytes32 indexed poolId,
+        uint256 previousBaseTarget,
+        uint256 baseTarget
+    );
+    event RaiseCapSet(bytes32 indexed poolId, uint256 previousRaiseCap, uint256 raiseCap);
+    event PoolExpirySet(bytes32 indexed poolId, uint64 previousExpiry, uint64 expiry);
+    event TransitionFinalized(bytes32 indexed poolId);
+    event TransitionAborted(bytes32 indexed poolId);
+    event EarlyGraduationConfigSet(
+        uint256 previousMinBase,
+        uint64 previousTimelock,
+        uint256 minBase,
+        uint64 timelock
+    );
+    event EarlyGraduationRequested(bytes32 indexed poolId, uint64 executableAt);
+    event QuoteCommitmentConfigSet(
+        uint64 previousValidityBlocks,
+        uint256 previousToleranceBps,
+        uint64 validityBlocks,
+        uint256 toleranceBps
+    );
+    event DefaultCurveParamsSet(
+        uint256 previousMaxPriceFactor,
+        uint256 previousSteepness,
+        uint256 previousMidpoint,
+        uint256 maxPriceFactor,
+        uint256 steepness,
+        uint256 midpoint
+    );
+    event LaunchPhasesSet(
+        bytes32 indexed poolId,
+        uint64 previousStartBlock,
+        uint64 previousAllowlistEndBlock,
+        uint64 previousCappedEndBlock,
+        uint256 previousMaxBuyBase,
+        uint64 startBlock,
+        uint64 allowlistEndBlock,
+        uint64 cappedEndBlock,
+        uint256 maxBuyBase
+    );
+    event LaunchAllowlistUpdated(bytes32 indexed poolId, address indexed account, bool allowed);
+    event MarketMakerExemptionSet(
+        bytes32 indexed poolId,
+        address indexed account,
+        bool previousExempt,
+        bool exempt
+    );
+    event MinTradeSizeSet(
+        bytes32 indexed poolId,
+        uint256 previousMinBuyBase,
+        uint256 previousMinSellTokens,
+        uint256 minBuyBase,
+        uint256 minSellTokens
+    );
+    event PriceTickSet(bytes32 indexed poolId, uint256 previousTick, uint256 tick);
+    event ComplianceModuleSet(bytes32 indexed poolId, address previousModule, address module);
+    event CashbackSet(bytes32 indexed poolId, uint256 previousCashbackBps, uint256 cashbackBps);
+    event IncentiveReserveFunded(bytes32 indexed poolId, uint256 amount, uint256 newReserve);
+    event IntegratorDiscountSet(
+        address indexed integrator,
+        uint256 previousDiscountBps,
+        uint256 discountBps
+    );
+    event QuoteTokenSet(address indexed previousQuoteToken, address indexed newQuoteToken);
+    event DefaultFeesSet(
+        uint256 previousProtocolFeeBps,
+        uint256 previousCreatorFeeBps,
+        uint256 protocolFeeBps,
+        uint256 creatorFeeBps
+    );
+    event PoolFeesSet(
+        bytes32 indexed poolId,
+        bool previousEnabled,
+        uint256 previousProtocolFeeBps,
+        uint256 previousCreatorFeeBps,
+        bool enabled,
+        uint256 protocolFeeBps,
+        uint256 creatorFeeBps
+    );
+    event FeesAccrued(
+        bytes32 indexed poolId,
+        address indexed trader,
+        uint256 protocolFee,
+        uint256 creatorFee
+    );
+    event ProtocolFeesClaimed(uint256 amount);
+    event ReferralShareSet(uint256 previousShareBps, uint256 shareBps);
+    event ReferralPaid(
+        bytes32 indexed poolId,
+        address indexed referrer,
+        address indexed trader,
+        uint256 amount
+    );
+    event ReferralFeesClaimed(address indexed referrer, uint256 amount);
+    event CreatorFeesClaimed(bytes32 indexed poolId, address indexed creator, uint256 amount);
+    event DeprecationSet(bool previousDeprecated, bool deprecated);
+    event CompactEventsSet(bool previousCompact, bool compact);
+    event TokensRescued(address indexed token, address indexed to, uint256 amount);
+    event GuardianSet(address indexed previousGuardian, address indexed newGuardian);
+    event BlacklistSet(
+        bytes32 indexed poolId,
+        address indexed account,
+        bool previousBlocked,
+        bool blocked
+    );
+    event DegradedModeSet(bool previousEnabled, bool enabled);
+    event ParamBoundsSet(
+        uint8 indexed parameter,
+        uint256 previousMin,
+        uint256 previousMax,
+        uint256 min,
+        uint256 max
+    );
+    event CurveUpdateBoundSet(uint256 previousMaxPriceChangeBps, uint256 maxPriceChangeBps);
+    event PoolPauseSet(bytes32 indexed poolId, bool paused, address indexed account);
+    event CurveKindSet(bytes32 indexed poolId, uint8 previousCurveKind, uint8 curveKind);
+    event DefaultCurveKindSet(uint8 previousCurveKind, uint8 curveKind);
+    event CurveSegmentsSet(
+        bytes32 indexed poolId,
+        uint256 previousSegmentCount,
+        uint256 segmentCount
+    );
+    event GlobalPauseSet(bool paused, address indexed account);
+    event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+    event RoleGranted(bytes32 indexed role, address indexed account, address indexed sender);
+    event RoleRevoked(bytes32 indexed role, address indexed account, address indexed sender);
+    event PoolStateManagerSet(
+        address indexed previousPoolStateManager,
+        address indexed newPoolStateManager
+    );
+    event PoolManagerMigrated(bytes32 indexed poolId, address indexed from, address indexed to);
+    event TransitionReady(bytes32 indexed poolId, uint256 finalPrice, uint256 baseCollected);
+    event GraduationThresholdReached(
+        bytes32 indexed poolId,
+        uint256 baseCollected,
+        uint256 baseTarget
+    );
+    event GraduationCallbackSet(bool previousEnabled, bool enabled);
+    event TransitionMarkedPending(bytes32 indexed poolId, uint256 baseCollected);
+    event CashbackPaid(
+        bytes32 indexed poolId,
+        address indexed buyer,
+        uint256 cashback,
+        uint256 remainingReserve
+    );
+    event IncentiveReserveDepleted(bytes32 indexed poolId);
+    event CirculatingSupplyUpdated(
+        bytes32 indexed poolId,
+        uint256 previousCirculatingSupply,
+        uint256 circulatingSupply
+    );
+    event PriceFeedTwapWindowSet(bytes32 indexed poolId, uint32 previousWindow, uint32 window);
+    event CheckedMathSet(bytes32 indexed poolId, bool previousEnabled, bool enabled);
+    event BaseAssetSet(
+        bytes32 indexed poolId,
+        address previousAsset,
+        uint8 previousDecimals,
+        address asset,
+        uint8 decimals
+    );
+    event BaseAssetFeesClaimed(address indexed asset, uint256 amount);
+
+    // Custom errors (messages are listed in ERROR_CATALOG)
+    error NotPoolStateManager();
+    error NotOwner();
+    error ZeroAddressOwner();
+    error ZeroAddressManager();
+    error PoolNotBoundToManager();
+    error InvalidPoolId();
+    error PoolTransitioned();
+    error InvalidAmount();
+    error InsufficientLiquidity(uint256 available, uint256 requested);
+    error InvalidParametersLength();
+    error InvalidParametersZero();
+    error InsufficientDataLength();
+    error Erc20CallFailed();
+    error Erc20InvalidResult();
+    error ManagerCallFailed();
+    error ManagerInvalidResult();
+    error InvalidDiscount();
+    error TransitionPending();
+    error NoTransitionPending();
+    error InvalidTolerance();
+    error UnknownQuoteCommitment();
+    error QuoteCommitmentExpired();
+    error QuoteMoved();
+    error ZeroAddressQuoteToken();
+    error InvalidDefaultParameters();
+    error StrategyDeprecated();
+    error LaunchNotStarted();
+    error NotAllowlisted();
+    error LaunchCapExceeded();
+    error InvalidLaunchPhases();
+    error NotCreatorOrOwner();
+    error InvalidTradeLogCapacity();
+    error InconsistentTokenState();
+    error Erc20TransferFailed();
+    error RaiseCapExceeded();
+    error BelowMinimumTrade();
+    error NoGraduationTarget();
+    error NotPoolCreator();
+    error BelowEarlyGraduationMinimum();
+    error NoEarlyGraduationRequest();
+    error EarlyGraduationTimelocked();
+    error InvalidFeeSplit();
+    error NotGuardian();
+    error Blacklisted();
+    error ComplianceDenied();
+    error ComplianceCallFailed();
+    error InvalidCashback();
+    error PoolWindingDown();
+    error PoolNotWindingDown();
+    error MalformedCurveParams();
+    error MissingRole(bytes32 role, address account);
+    error UnknownRole(bytes32 role);
+    error TradingPaused();
+    error CurveUpdateOutOfBounds();
+    error InitialPriceOutOfBounds(uint256 value, uint256 min, uint256 max);
+    error MaxPriceFactorOutOfBounds(uint256 value, uint256 min, uint256 max);
+    error SteepnessOutOfBounds(uint256 value, uint256 min, uint256 max);
+    error MidpointOutOfBounds(uint256 value, uint256 min, uint256 max);
+    error TotalSupplyOutOfBounds(uint256 value, uint256 min, uint256 max);
+    error InvalidParamBounds();
+    error PoolAlreadyInitialized();
+    error InvalidFee();
+    error NothingToClaim();
+    error InvalidReferralShare();
+    error SlippageExceeded(uint256 limit, uint256 actual);
+    error Expired(uint64 deadline, uint64 timestamp);
+    error InvalidCurveKind();
+    error CurveKindLocked();
+    error InvalidCurveSegments();
+    error SupplyNotSynced();
+    error UnknownSelector(bytes4 selector);
+    error InsufficientObservations();
+    error ObservationTooOld(uint64 oldestTimestamp);
+    error MathOverflow(uint8 op);
+    error InvariantViolated();
+    error InvalidBaseAsset();
+    error BaseAssetLocked();
+    error NativeTransferFailed();
+}
+
+// Every error the contract's public functions revert with
+#[derive(SolidityError)]
+pub enum PumpUpErrors {
+    NotPoolStateManager(NotPoolStateManager),
+    NotOwner(NotOwner),
+    ZeroAddressOwner(ZeroAddressOwner),
+    ZeroAddressManager(ZeroAddressManager),
+    PoolNotBoundToManager(PoolNotBoundToManager),
+    InvalidPoolId(InvalidPoolId),
+    PoolTransitioned(PoolTransitioned),
+    InvalidAmount(InvalidAmount),
+    InsufficientLiquidity(InsufficientLiquidity),
+    InvalidParametersLength(InvalidParametersLength),
+    InvalidParametersZero(InvalidParametersZero),
+    InsufficientDataLength(InsufficientDataLength),
+    Erc20CallFailed(Erc20CallFailed),
+    Erc20InvalidResult(Erc20InvalidResult),
+    ManagerCallFailed(ManagerCallFailed),
+    ManagerInvalidResult(ManagerInvalidResult),
+    InvalidDiscount(InvalidDiscount),
+    TransitionPending(TransitionPending),
+    NoTransitionPending(NoTransitionPending),
+    InvalidTolerance(InvalidTolerance),
+    UnknownQuoteCommitment(UnknownQuoteCommitment),
+    QuoteCommitmentExpired(QuoteCommitmentExpired),
+    QuoteMoved(QuoteMoved),
+    ZeroAddressQuoteToken(ZeroAddressQuoteToken),
+    InvalidDefaultParameters(InvalidDefaultParameters),
+    StrategyDeprecated(StrategyDeprecated),
+    LaunchNotStarted(LaunchNotStarted),
+    NotAllowlisted(NotAllowlisted),
+    LaunchCapExceeded(LaunchCapExceeded),
+    InvalidLaunchPhases(InvalidLaunchPhases),
+    NotCreatorOrOwner(NotCreatorOrOwner),
+    InvalidTradeLogCapacity(InvalidTradeLogCapacity),
+    InconsistentTokenState(InconsistentTokenState),
+    Erc20TransferFailed(Erc20TransferFailed),
+    RaiseCapExceeded(RaiseCapExceeded),
+    BelowMinimumTrade(BelowMinimumTrade),
+    NoGraduationTarget(NoGraduationTarget),
+    NotPoolCreator(NotPoolCreator),
+    BelowEarlyGraduationMinimum(BelowEarlyGraduationMinimum),
+    NoEarlyGraduationRequest(NoEarlyGraduationRequest),
+    EarlyGraduationTimelocked(EarlyGraduationTimelocked),
+    InvalidFeeSplit(InvalidFeeSplit),
+    NotGuardian(NotGuardian),
+    Blacklisted(Blacklisted),
+    ComplianceDenied(ComplianceDenied),
+    ComplianceCallFailed(ComplianceCallFailed),
+    InvalidCashback(InvalidCashback),
+    PoolWindingDown(PoolWindingDown),
+    PoolNotWindingDown(PoolNotWindingDown),
+    MalformedCurveParams(MalformedCurveParams),
+    MissingRole(MissingRole),
+    UnknownRole(UnknownRole),
+    TradingPaused(TradingPaused),
+    CurveUpdateOutOfBounds(CurveUpdateOutOfBounds),
+    InitialPriceOutOfBounds(InitialPriceOutOfBounds),
+    MaxPriceFactorOutOfBounds(MaxPriceFactorOutOfBounds),
+    SteepnessOutOfBounds(SteepnessOutOfBounds),
+    MidpointOutOfBounds(MidpointOutOfBounds),
+    TotalSupplyOutOfBounds(TotalSupplyOutOfBounds),
+    InvalidParamBounds(InvalidParamBounds),
+    PoolAlreadyInitialized(PoolAlreadyInitialized),
+    InvalidFee(InvalidFee),
+    NothingToClaim(NothingToClaim),
+    InvalidReferralShare(InvalidReferralShare),
+    SlippageExceeded(SlippageExceeded),
+    Expired(Expired),
+    InvalidCurveKind(InvalidCurveKind),
+    CurveKindLocked(CurveKindLocked),
+    InvalidCurveSegments(InvalidCurveSegments),
+    SupplyNotSynced(SupplyNotSynced),
+    UnknownSelector(UnknownSelector),
+    InsufficientObservations(InsufficientObservations),
+    ObservationTooOld(ObservationTooOld),
+    MathOverflow(MathOverflow),
+    InvariantViolated(InvariantViolated),
+    InvalidBaseAsset(InvalidBaseAsset),
+    BaseAssetLocked(BaseAssetLocked),
+    NativeTransferFailed(NativeTransferFailed),
+}
+
+impl AbiType for PoolKey {
+    type SolType = Self;
+
+    const ABI: ConstString = ConstString::new("(address,address,uint24,int24,address)");
 }
 
+// Pool info as returned by the pool state manager:
+// (token_address, creator, weth_collected, last_price, is_transitioned, bonding_curve_strategy)
+pub type PoolInfo = (Address, Address, U256, U256, bool, B256);
+
+// Trade directions as reported by `last_trade`
+const TRADE_DIRECTION_NONE: u8 = 0;
+const TRADE_DIRECTION_BUY: u8 = 1;
+const TRADE_DIRECTION_SELL: u8 = 2;
+
+// Failure bits reported by `health_check`
+const HEALTH_MANAGER_UNREACHABLE: u32 = 1 << 0;
+const HEALTH_QUOTE_TOKEN_UNSET: u32 = 1 << 1;
+const HEALTH_QUOTE_TOKEN_DECIMALS: u32 = 1 << 2;
+const HEALTH_MATH_SANITY: u32 = 1 << 3;
+
+// Decimals the quote token is expected to have
+const QUOTE_TOKEN_DECIMALS: u8 = 18;
+
+// Decimals the curve math prices token amounts in; pool tokens with other decimals are scaled
+const WAD_DECIMALS: u8 = 18;
+
+// Most decimals a pool token or base asset may have for its amounts to be scaled
+const MAX_TOKEN_DECIMALS: u8 = 36;
+
+// Base asset address standing for the chain's native currency
+const NATIVE_BASE_ASSET: Address = Address::ZERO;
+
+// Pool lifecycle states as reported by `pool_status` (5 = cleaned is reserved)
+const POOL_STATUS_UNINITIALIZED: u8 = 0;
+const POOL_STATUS_ACTIVE: u8 = 1;
+const POOL_STATUS_PAUSED: u8 = 2;
+const POOL_STATUS_TRANSITION_PENDING: u8 = 3;
+const POOL_STATUS_TRANSITIONED: u8 = 4;
+const POOL_STATUS_WINDING_DOWN: u8 = 6;
+
+// Launch phases as reported by `launch_phase`
+const LAUNCH_PHASE_OPEN: u8 = 0;
+const LAUNCH_PHASE_PRE_LAUNCH: u8 = 1;
+const LAUNCH_PHASE_ALLOWLIST: u8 = 2;
+const LAUNCH_PHASE_CAPPED: u8 = 3;
+
 // Define storage using sol_storage! macro as in the examples
 sol_storage! {
-    #[entrypoint]
+    #[cfg_attr(not(any(feature = "linear", feature = "exponential")), entrypoint)]
     pub struct SigmoidBondingCurve {
         // Admin management
         address owner;
@@ -45,14 +907,314 @@ sol_storage! {
         address pool_state_manager;
 
         // Curve parameters for each pool
-        mapping(bytes32 => uint256) initial_prices;
-        mapping(bytes32 => uint256) max_price_factors;
-        mapping(bytes32 => uint256) steepness_values;
-        mapping(bytes32 => uint256) midpoints;
-        mapping(bytes32 => uint256) total_supplies;
+        mapping(bytes32 => PoolCurveParams) curve_params;
+
+        // Manager each pool is bound to (set at initialization, re-pointed on migration)
+        mapping(bytes32 => address) pool_managers;
+
+        // All pool ids ever initialized, in initialization order
+        bytes32[] pool_ids;
+
+        // Protocol fee discount (in bps) for partner integrators, keyed by the trader the manager names
+        mapping(address => uint256) integrator_discounts;
+
+        // Per-pool minimum price sells are quoted at (zero disables the guard)
+        mapping(bytes32 => uint256) sell_price_floors;
+
+        // Per-pool insurance reserve covering sell floor shortfalls
+        mapping(bytes32 => uint256) insurance_reserves;
+
+        // Most recent trade of each pool, maintained by the record hook
+        mapping(bytes32 => TradeRecord) last_trades;
+
+        // Per-pool base asset collected at which the pool graduates (zero disables the threshold)
+        mapping(bytes32 => uint256) graduation_weth_targets;
+
+        // Pools that crossed their graduation threshold and await the manager
+        mapping(bytes32 => bool) transition_pending;
+
+        // Quote commitment settings (zero means use the defaults)
+        uint64 quote_validity_blocks;
+        uint256 quote_tolerance_bps;
+
+        // Outstanding quote commitments and the nonce keeping their hashes unique
+        mapping(bytes32 => QuoteCommitment) quote_commitments;
+        uint256 quote_nonce;
+
+        // Canonical quote token (WETH or its equivalent) of this deployment
+        address quote_token;
+
+        // Deployment-wide totals, maintained by the record hooks
+        uint256 total_pools_graduated;
+        uint256 total_weth_volume;
+        uint256 total_fees_collected;
+
+        // Owner-set default curve parameters (zero means use the compile-time defaults)
+        uint256 default_max_price_factor;
+        uint256 default_steepness;
+        uint256 default_midpoint;
+
+        // Blocks new pool initializations once the strategy is superseded
+        bool deprecated;
+
+        // Block-based launch phases and their allowlists
+        mapping(bytes32 => LaunchSchedule) launch_schedules;
+        mapping(bytes32 => mapping(address => bool)) launch_allowlists;
+
+        // Per-pool market makers exempt from launch allowlists and buy caps
+        mapping(bytes32 => mapping(address => bool)) market_maker_exemptions;
+
+        // Emit compact trade events (indexed pool id and one packed amounts word)
+        bool compact_events;
+
+        // Recent trades kept per pool (0 disables the trade log)
+        uint64 trade_log_capacity;
+        mapping(bytes32 => uint256) trade_log_counts;
+        mapping(bytes32 => mapping(uint256 => LoggedTrade)) trade_logs;
+
+        // Oracle allowed to sign price attestations besides the owner
+        address price_attester;
+
+        // Per-pool hard cap on cumulative base asset raised (0 disables the cap)
+        mapping(bytes32 => uint256) raise_caps;
+
+        // Per-pool minimum base asset in per buy and tokens in per sell (0 disables)
+        mapping(bytes32 => uint256) min_buy_weth;
+        mapping(bytes32 => uint256) min_sell_tokens;
+
+        // Per-pool tick size quoted prices are rounded to (0 disables)
+        mapping(bytes32 => uint256) price_ticks;
+
+        // Creator-initiated early graduation: minimum base asset raised, timelock in seconds and
+        // the timestamp each pending request becomes executable
+        uint256 early_graduation_min_weth;
+        uint64 early_graduation_timelock;
+        mapping(bytes32 => uint64) early_graduation_requests;
+
+        // Per-pool wash trading heuristics: trader patterns, estimated wash volume and flagged
+        // addresses
+        mapping(bytes32 => mapping(address => WashTracker)) wash_trackers;
+        mapping(bytes32 => uint256) wash_volumes;
+        mapping(bytes32 => address[]) wash_flagged;
+
+        // Protocol fee payees and their shares in bps (summing to 100%)
+        address[] fee_payees;
+        uint256[] fee_shares;
+
+        // Guardian allowed to block addresses from trading, and the per-pool blacklists
+        address guardian;
+        mapping(bytes32 => mapping(address => bool)) blacklists;
+
+        // Per-pool compliance module asked to allow each trade (zero disables)
+        mapping(bytes32 => address) compliance_modules;
+
+        // Per-pool cumulative buy cost at each of the COST_TABLE_BREAKPOINTS supply breakpoints
+        mapping(bytes32 => uint256[]) cost_tables;
+
+        // Serve quotes from cached pool state when token or manager reads fail
+        bool degraded_mode;
+        mapping(bytes32 => PoolCache) pool_caches;
+
+        // Per-pool buy cashback in bps, the creator-funded token reserve it draws from and the
+        // total paid out
+        mapping(bytes32 => uint256) cashback_bps;
+        mapping(bytes32 => uint256) incentive_reserves;
+        mapping(bytes32 => uint256) cashback_paid;
+
+        // Per-pool timestamp after which a pool that has not graduated winds down (0 disables)
+        mapping(bytes32 => uint64) pool_expiries;
+
+        // Accounts holding each access control role besides the owner
+        mapping(bytes32 => mapping(address => bool)) role_members;
+
+        // Emergency pause of every pool, and of individual pools
+        bool paused;
+        mapping(bytes32 => bool) paused_pools;
+
+        // Largest spot price move in bps a curve parameter update may cause (0 disables the bound)
+        uint256 curve_update_max_price_change_bps;
+
+        // Owner-set bounds pools' curve parameters must fall within, by parameter id (zero means
+        // use the compile-time bound)
+        mapping(uint8 => ParamBounds) param_bounds;
+
+        // Protocol and creator fees in bps charged on trades, per-pool overrides, and the fees
+        // accrued until claimed
+        uint256 protocol_fee_bps;
+        uint256 creator_fee_bps;
+        mapping(bytes32 => FeeOverride) fee_overrides;
+        uint256 protocol_fees_accrued;
+        mapping(bytes32 => uint256) creator_fees_accrued;
+
+        // Share of the protocol fee in bps accrued to the referrer of a buy, and the referral fees
+        // accrued until claimed
+        uint256 referral_share_bps;
+        mapping(address => uint256) referral_fees_accrued;
+
+        // Call the pool's manager back when a buy reaches the graduation target
+        bool graduation_callback_enabled;
+
+        // Pricing model assigned to newly initialized pools
+        uint8 default_curve_kind;
+
+        // Segments of piecewise pools' curves
+        mapping(bytes32 => mapping(uint256 => PoolCurveSegment)) curve_segments;
+        mapping(bytes32 => uint256) curve_segment_counts;
+
+        // Per-pool circulating supply reported by the manager, priced from instead of token reads
+        mapping(bytes32 => SupplyAccount) supply_accounts;
+
+        // Per-pool ring buffer of price observations for TWAPs, written by executed trades
+        mapping(bytes32 => uint256) observation_counts;
+        mapping(bytes32 => mapping(uint256 => Observation)) observations;
+
+        // Per-pool TWAP window in seconds of the price feed answers (0 answers the spot price)
+        mapping(bytes32 => uint32) price_feed_twap_windows;
+
+        // Per-pool base asset volume and number of executed trades
+        mapping(bytes32 => PoolStats) pool_stats;
+
+        // Pools whose trades revert with MathOverflow instead of pricing at saturated values
+        mapping(bytes32 => bool) checked_math_pools;
+
+        // Per-pool decimals of the pool token, read once at initialization or the first sync
+        mapping(bytes32 => TokenDecimals) token_decimals;
+
+        // Per-pool base asset, the quote token for pools without one
+        mapping(bytes32 => BaseAsset) base_assets;
+        // Protocol fees accrued by pools with a configured base asset, per asset
+        mapping(address => uint256) base_asset_fees_accrued;
+    }
+
+    // Curve parameters of a pool, written together at initialization. Steepness and midpoint
+    // share a slot: steepness is clamped to at most 100.0 and midpoints saturate at 2^128 - 1,
+    // far past the end of any curve
+    pub struct PoolCurveParams {
+        uint256 initial_price;
+        uint256 max_price_factor;
+        uint256 total_supply;
+        uint128 steepness;
+        uint128 midpoint;
+        uint8 curve_kind;
+    }
+
+    // Segment of a pool's piecewise curve
+    pub struct PoolCurveSegment {
+        uint8 shape;
+        uint256 end_supply;
+        uint256 end_price;
+        uint128 steepness;
+        uint128 midpoint;
+    }
+
+    // Per-pool fees replacing the deployment defaults while enabled
+    pub struct FeeOverride {
+        bool enabled;
+        uint256 protocol_fee_bps;
+        uint256 creator_fee_bps;
+    }
+
+    // Inclusive range a curve parameter must fall within
+    pub struct ParamBounds {
+        uint256 min;
+        uint256 max;
+    }
+
+    // Buy quote a caller committed to
+    pub struct QuoteCommitment {
+        bytes32 pool_id;
+        address committer;
+        uint256 amount_in;
+        uint256 amount_out;
+        uint64 expiry_block;
+    }
+
+    // Launch phase boundaries in block numbers: buys are rejected before `start_block`,
+    // restricted to allowlisted callers until `allowlist_end_block` and capped at
+    // `max_buy_weth` per trade until `capped_end_block`
+    pub struct LaunchSchedule {
+        uint64 start_block;
+        uint64 allowlist_end_block;
+        uint64 capped_end_block;
+        uint256 max_buy_weth;
+    }
+
+    // Per-address trade pattern used to spot offsetting trades
+    pub struct WashTracker {
+        uint64 last_buy_block;
+        uint64 last_sell_block;
+        uint256 last_buy_weth;
+        uint256 last_sell_weth;
+        uint64 offsetting_trades;
+        bool flagged;
+    }
+
+    // Circulating supply of a pool as tracked by this contract. Pools initialized before tracking
+    // existed stay unsynced, and priced from token reads, until the manager syncs them.
+    pub struct SupplyAccount {
+        uint256 circulating_supply;
+        bool synced;
+    }
+
+    // Pool state remembered for degraded-mode quotes
+    pub struct PoolCache {
+        uint256 circulating_supply;
+        uint256 weth_collected;
+        bool is_transitioned;
+        uint64 block_number;
+    }
+
+    // Entry of the per-pool trade log
+    pub struct LoggedTrade {
+        address trader;
+        uint64 block_number;
+        uint8 direction;
+        uint256 token_amount;
+        uint256 weth_amount;
+        uint256 price;
+    }
+
+    // Decimals of a pool's token, once read from the token
+    pub struct TokenDecimals {
+        bool recorded;
+        uint8 decimals;
+    }
+
+    // Asset a pool is priced and settled in, once configured
+    pub struct BaseAsset {
+        bool configured;
+        address asset;
+        uint8 decimals;
+    }
+
+    // Cumulative trading activity of a pool
+    pub struct PoolStats {
+        uint256 buy_volume;
+        uint256 sell_volume;
+        uint64 trade_count;
+    }
+
+    // Cumulative price (price x seconds) of a pool up to `timestamp`
+    pub struct Observation {
+        uint64 timestamp;
+        uint256 price_cumulative;
+    }
+
+    // Snapshot of a single trade
+    pub struct TradeRecord {
+        uint64 timestamp;
+        uint8 direction;
+        uint256 token_amount;
+        uint256 weth_amount;
+        uint256 price;
     }
 }
 
+// The entrypoint attribute marks the contract as top-level storage, which multicall needs to
+// route calls. Builds with another strategy as the entrypoint still compile the sigmoid contract.
+#[cfg(any(feature = "linear", feature = "exponential"))]
+unsafe impl TopLevelStorage for SigmoidBondingCurve {}
+
 // Implementation with public keyword instead of external
 #[public]
 impl SigmoidBondingCurve {
@@ -71,183 +1233,472 @@ impl SigmoidBondingCurve {
         STRATEGY_NAME.into()
     }
 
+    // Pricing models pools on this deployment can use as (curve kinds, strategy names)
+    pub fn registered_strategies(&self) -> (Vec<u8>, Vec<String>) {
+        REGISTERED_STRATEGIES
+            .iter()
+            .map(|(curve_kind, name)| (*curve_kind, String::from(*name)))
+            .unzip()
+    }
+
+    // Build identity for factories and registries as (strategy id = keccak256(type ++ name),
+    // code version, keccak256 of the abi-encoded default curve params, bound pool state manager)
+    pub fn deployment_info(&self) -> (B256, String, B256, Address) {
+        let strategy_id = crypto::keccak(format!("{}{}", STRATEGY_TYPE, STRATEGY_NAME));
+
+        let (max_price_factor, steepness, midpoint) = self.get_default_curve_params();
+        let mut defaults = Vec::with_capacity(96);
+        defaults.extend_from_slice(&max_price_factor.to_be_bytes::<32>());
+        defaults.extend_from_slice(&steepness.to_be_bytes::<32>());
+        defaults.extend_from_slice(&midpoint.to_be_bytes::<32>());
+        let default_params_hash = crypto::keccak(&defaults);
+
+        (
+            strategy_id,
+            CODE_VERSION.into(),
+            default_params_hash,
+            *self.pool_state_manager,
+        )
+    }
+
     // Initialize the strategy for a new pool
-    pub fn initialize(&mut self, pool_id: B256, params: Bytes) -> Result<(), Vec<u8>> {
-        // Only pool state manager can initialize
-        if msg::sender() != *self.owner {
-            return Err(Vec::<u8>::from("Not Pool State Manager"));
+    pub fn initialize(&mut self, pool_id: B256, params: Bytes) -> Result<(), PumpUpErrors> {
+        // Only the pool state manager or an initializer can initialize
+        let sender = msg::sender();
+        if sender != *self.pool_state_manager && !self.holds_role(INITIALIZER_ROLE, sender) {
+            return Err(NotPoolStateManager {}.into());
         }
 
-        let params_bytes = params.0;
-
-        // Parse parameters - assuming 5 U256 values packed in sequence
-        if params_bytes.len() < 160 {
-            // 5 * 32 bytes
-            return Err(Vec::<u8>::from("Invalid Parameters - not enough data"));
+        if self.pool_exists(pool_id) {
+            return Err(PoolAlreadyInitialized {}.into());
         }
 
-        // Extract U256 values from byte array
-        let initial_price = extract_u256_from_bytes(&params_bytes, 0)?;
-        let max_price_factor = extract_u256_from_bytes(&params_bytes, 32)?;
-        let steepness = extract_u256_from_bytes(&params_bytes, 64)?;
-        let midpoint = extract_u256_from_bytes(&params_bytes, 96)?;
-        let total_supply = extract_u256_from_bytes(&params_bytes, 128)?;
+        self.initialize_pool(pool_id, &params.0)
+    }
 
-        // Validate parameters
-        if total_supply.is_zero() || initial_price.is_zero() {
-            return Err(Vec::<u8>::from(
-                "Invalid Parameters - zero values not allowed",
-            ));
+    // Overwrite the curve parameters of an existing pool, e.g. to recover from a bad
+    // initialization, and rebind it to the current manager (only owner)
+    pub fn reinitialize(&mut self, pool_id: B256, params: Bytes) -> Result<(), PumpUpErrors> {
+        self.only_owner()?;
+
+        if !self.pool_exists(pool_id) {
+            return Err(InvalidPoolId {}.into());
         }
 
-        // Use default values if not provided
-        let max_price_factor = if max_price_factor.is_zero() {
-            DEFAULT_MAX_PRICE_FACTOR
-        } else {
-            max_price_factor
-        };
-        let steepness = if steepness.is_zero() {
-            DEFAULT_STEEPNESS
-        } else {
-            steepness
-        };
-        let midpoint = if midpoint.is_zero() {
-            DEFAULT_MIDPOINT
-        } else {
-            midpoint
-        };
+        self.initialize_pool(pool_id, &params.0)
+    }
 
-        // Store parameters using setters
-        let mut initial_price_setter = self.initial_prices.setter(pool_id);
-        initial_price_setter.set(initial_price);
+    // Update the curve parameters of a trading pool (only the pool's manager). Takes the same
+    // abi.encode'd CurveParams as initialize; zero fields keep their current value. Rejected
+    // once the pool is transitioning, if the total supply would fall below the circulating
+    // supply, or if the spot price would move more than the configured bound.
+    pub fn update_curve_params(
+        &mut self,
+        pool_id: B256,
+        params: Bytes,
+    ) -> Result<(), PumpUpErrors> {
+        self.check_pool_manager(pool_id)?;
 
-        let mut max_price_factor_setter = self.max_price_factors.setter(pool_id);
-        max_price_factor_setter.set(max_price_factor);
+        let previous_params = self.get_curve_params(pool_id)?;
+        let (token_address, _, _, _, is_transitioned, _) = self.get_pool_info(pool_id)?;
 
-        let mut steepness_setter = self.steepness_values.setter(pool_id);
-        steepness_setter.set(steepness);
+        if is_transitioned {
+            return Err(PoolTransitioned {}.into());
+        }
 
-        let mut midpoint_setter = self.midpoints.setter(pool_id);
-        midpoint_setter.set(midpoint);
+        if self.transition_pending.get(pool_id) {
+            return Err(TransitionPending {}.into());
+        }
 
-        let mut total_supply_setter = self.total_supplies.setter(pool_id);
-        total_supply_setter.set(total_supply);
+        let requested = decode_curve_params(&params.0)?;
+        let keep_if_zero =
+            |value: U256, current: U256| if value.is_zero() { current } else { value };
+        let mut params = CurveParameters {
+            initial_price: keep_if_zero(requested.initial_price, previous_params.initial_price),
+            max_price_factor: keep_if_zero(
+                requested.max_price_factor,
+                previous_params.max_price_factor,
+            ),
+            steepness: keep_if_zero(requested.steepness, previous_params.steepness),
+            midpoint: keep_if_zero(requested.midpoint, previous_params.midpoint),
+            total_supply: keep_if_zero(requested.total_supply, previous_params.total_supply),
+            ..previous_params
+        };
+        self.check_param_bounds(&params)?;
+        if params.curve_kind == CURVE_KIND_PIECEWISE {
+            piecewise::check_segments(&params)?;
+        }
+        params.steepness = self.clamp_steepness(pool_id, params.steepness, params.midpoint);
 
-        // Emit event using raw_log, simplified
-        let mut topics = Vec::new();
-        let sig = [
-            0x9b, 0xf7, 0xf4, 0xad, 0x1d, 0x0f, 0x9f, 0xa8, 0xaa, 0x5c, 0x45, 0x69, 0x13, 0xd7,
-            0x2b, 0x51, 0x41, 0x0f, 0x35, 0xa7, 0xc4, 0xcd, 0xf7, 0x34, 0x94, 0xb7, 0xa8, 0x1b,
-            0x53, 0x0d, 0x7e, 0x40,
-        ];
-        topics.push(B256::from_slice(&sig));
-        topics.push(pool_id);
+        let circulating_supply =
+            self.circulating_supply(pool_id, &token_address, &previous_params)?;
+        if params.total_supply < circulating_supply {
+            return Err(CurveUpdateOutOfBounds {}.into());
+        }
 
-        let mut data = Vec::new();
-        data.extend_from_slice(&initial_price.to_be_bytes::<32>());
-        data.extend_from_slice(&max_price_factor.to_be_bytes::<32>());
-        data.extend_from_slice(&steepness.to_be_bytes::<32>());
-        data.extend_from_slice(&midpoint.to_be_bytes::<32>());
-        data.extend_from_slice(&total_supply.to_be_bytes::<32>());
+        // Optionally bound the spot price jump at the current supply
+        let max_price_change_bps = self.curve_update_max_price_change_bps.get();
+        if !max_price_change_bps.is_zero() {
+            let previous_price = self.calculate_sigmoid_price(circulating_supply, &previous_params);
+            let price = self.calculate_sigmoid_price(circulating_supply, &params);
+            let price_change = price.abs_diff(previous_price);
+            if price_change * BPS_DENOMINATOR > previous_price * max_price_change_bps {
+                return Err(CurveUpdateOutOfBounds {}.into());
+            }
+        }
 
-        evm::raw_log(&topics, &data);
+        self.store_curve_params(pool_id, &params);
+        self.emit_curve_params_changed(pool_id, &previous_params, &params);
 
         Ok(())
     }
 
-    // Calculate token amount to receive for a given WETH amount
+    // Calculate token amount `trader` receives for a given base asset amount. Only the pool's
+    // manager executes trades, on behalf of the trader.
     pub fn calculate_buy(
         &mut self,
         pool_id: B256,
+        trader: Address,
         weth_amount: U256,
-    ) -> Result<(U256, U256), Vec<u8>> {
-        // Get pool info
-        let (
-            token_address,
-            _creator,
-            _weth_collected,
-            _last_price,
-            is_transitioned,
-            _bonding_curve_strategy,
-        ) = self.get_pool_info(pool_id)?;
+    ) -> Result<(U256, U256), PumpUpErrors> {
+        let (token_amount, new_price, _weth_unspent) =
+            self.execute_buy(pool_id, trader, weth_amount, false, Address::ZERO)?;
 
-        if is_transitioned {
-            return Err(Vec::<u8>::from("Pool has transitioned"));
-        }
+        Ok((token_amount, new_price))
+    }
 
-        if weth_amount.is_zero() {
-            return Err(Vec::<u8>::from("Invalid Amount"));
+    // Calculate a buy that reverts with SlippageExceeded if it yields fewer than
+    // `min_tokens_out` tokens, and with Expired once the block timestamp passes `deadline`
+    // (zero for no deadline)
+    pub fn calculate_buy_with_slippage(
+        &mut self,
+        pool_id: B256,
+        trader: Address,
+        weth_amount: U256,
+        min_tokens_out: U256,
+        deadline: u64,
+    ) -> Result<(U256, U256), PumpUpErrors> {
+        check_deadline(deadline)?;
+
+        let (token_amount, new_price, _weth_unspent) =
+            self.execute_buy(pool_id, trader, weth_amount, false, Address::ZERO)?;
+
+        if token_amount < min_tokens_out {
+            return Err(SlippageExceeded {
+                limit: min_tokens_out,
+                actual: token_amount,
+            }
+            .into());
         }
 
-        // Get curve parameters
+        Ok((token_amount, new_price))
+    }
+
+    // Calculate a buy that only spends the base asset the curve uses. Returns (token amount, new
+    // price, unspent base asset) so the manager can refund the remainder instead of absorbing it.
+    pub fn calculate_buy_with_remainder(
+        &mut self,
+        pool_id: B256,
+        trader: Address,
+        weth_amount: U256,
+    ) -> Result<(U256, U256, U256), PumpUpErrors> {
+        self.execute_buy(pool_id, trader, weth_amount, true, Address::ZERO)
+    }
+
+    // Calculate a buy referred by `referrer`, who accrues the referral share of the protocol fee.
+    // A zero referrer or the buyer themselves earns nothing.
+    pub fn calculate_buy_with_referrer(
+        &mut self,
+        pool_id: B256,
+        trader: Address,
+        weth_amount: U256,
+        referrer: Address,
+    ) -> Result<(U256, U256), PumpUpErrors> {
+        let (token_amount, new_price, _weth_unspent) =
+            self.execute_buy(pool_id, trader, weth_amount, false, referrer)?;
+
+        Ok((token_amount, new_price))
+    }
+
+    // Quote a buy for `trader` as (token amount, new price, unspent base asset)
+    pub fn quote_buy(
+        &self,
+        pool_id: B256,
+        trader: Address,
+        weth_amount: U256,
+    ) -> Result<(U256, U256, U256), PumpUpErrors> {
+        let (token_amount, new_price, _weth_collected, weth_unspent) =
+            self.quote_buy_amounts(pool_id, trader, weth_amount)?;
+
+        Ok((token_amount, new_price, weth_unspent))
+    }
+
+    // Quote a sell for `trader` as (base asset returned, new price), without emitting events or
+    // touching state
+    pub fn quote_sell(
+        &self,
+        pool_id: B256,
+        trader: Address,
+        token_amount: U256,
+    ) -> Result<(U256, U256), PumpUpErrors> {
+        let (weth_to_return, new_price, _shortfall, _circulating_supply, _weth_collected) =
+            self.quote_sell_amounts(pool_id, token_amount)?;
+        let (protocol_fee, creator_fee) = self.trade_fees(pool_id, trader, weth_to_return);
+
+        Ok((weth_to_return - protocol_fee - creator_fee, new_price))
+    }
+
+    // Quote a buy for `trader` with its price impact as (token amount, spot price before, average execution
+    // price, spot price after, price impact in bps), without emitting events or touching state. The
+    // average price is the base asset spent per 10^18 tokens, fees included, and the impact is how
+    // far it lies above the spot price before the buy.
+    pub fn quote_buy_detailed(
+        &self,
+        pool_id: B256,
+        trader: Address,
+        weth_amount: U256,
+    ) -> Result<(U256, U256, U256, U256, U256), PumpUpErrors> {
+        let (circulating_supply, weth_collected, is_transitioned, _stale) =
+            self.pool_state(pool_id, false)?;
+        let (token_amount, new_price, weth_unspent) = self.quote_buy_from_state(
+            pool_id,
+            trader,
+            circulating_supply,
+            weth_collected,
+            is_transitioned,
+            weth_amount,
+        )?;
+
         let params = self.get_curve_params(pool_id)?;
+        let spot_price = self.spot_price(circulating_supply, &params);
+        let average_price = average_price(
+            self.base_to_wad(pool_id, weth_amount - weth_unspent),
+            self.token_to_wad(pool_id, token_amount),
+        );
+        let impact_bps = price_impact_bps(spot_price, average_price);
 
-        // Get current circulating supply
-        let total_token_supply = self.call_total_supply(&token_address)?;
-        let held_by_manager = self.call_balance_of(&token_address, *self.pool_state_manager)?;
-        let circulating_supply = total_token_supply.saturating_sub(held_by_manager);
+        Ok((
+            token_amount,
+            spot_price,
+            average_price,
+            new_price,
+            impact_bps,
+        ))
+    }
 
-        // If no tokens have been sold yet, use a simpler calculation for the first buyer
-        if circulating_supply.is_zero() {
-            // For the first buyer, use the initial price directly
-            let token_amount = self.divide_fixed_point(weth_amount, params.initial_price);
-            let new_price = params.initial_price;
+    // Quote a sell for `trader` with its price impact as (base asset returned, spot price before, average
+    // execution price, spot price after, price impact in bps), without emitting events or touching
+    // state. The average price is the base asset returned per 10^18 tokens after fees, and the
+    // impact is how far it lies below the spot price before the sell.
+    pub fn quote_sell_detailed(
+        &self,
+        pool_id: B256,
+        trader: Address,
+        token_amount: U256,
+    ) -> Result<(U256, U256, U256, U256, U256), PumpUpErrors> {
+        let (weth_to_return, new_price, _shortfall, circulating_supply, _weth_collected) =
+            self.quote_sell_amounts(pool_id, token_amount)?;
+        let (protocol_fee, creator_fee) = self.trade_fees(pool_id, trader, weth_to_return);
+        let weth_out = weth_to_return - protocol_fee - creator_fee;
 
-            // Emit event - Tokens Purchased
-            let mut topics = Vec::new();
-            let sig = [
-                0xb5, 0x76, 0x4e, 0x7b, 0x82, 0xdd, 0x8f, 0x30, 0x19, 0x96, 0xd3, 0x71, 0x8c, 0xe0,
-                0xa3, 0x43, 0xf4, 0x74, 0xc9, 0x37, 0x93, 0xa6, 0xd3, 0x83, 0xcb, 0x65, 0x6f, 0x91,
-                0x78, 0x69, 0xaf, 0xcf,
-            ];
-            topics.push(B256::from_slice(&sig));
-            topics.push(pool_id);
+        let params = self.get_curve_params(pool_id)?;
+        let spot_price = self.spot_price(circulating_supply, &params);
+        let average_price = average_price(
+            self.base_to_wad(pool_id, weth_out),
+            self.token_to_wad(pool_id, token_amount),
+        );
+        let impact_bps = price_impact_bps(spot_price, average_price);
 
-            let mut data = Vec::new();
-            data.extend_from_slice(&weth_amount.to_be_bytes::<32>());
-            data.extend_from_slice(&token_amount.to_be_bytes::<32>());
-            data.extend_from_slice(&new_price.to_be_bytes::<32>());
+        Ok((weth_out, spot_price, average_price, new_price, impact_bps))
+    }
 
-            evm::raw_log(&topics, &data);
+    // Quote a buy and commit to it for the configured number of blocks.
+    // Returns (token amount, new price, commitment, expiry block).
+    pub fn commit_quote(
+        &mut self,
+        pool_id: B256,
+        weth_amount: U256,
+    ) -> Result<(U256, U256, B256, u64), PumpUpErrors> {
+        // The committer is the trader the commitment can later be executed for
+        let committer = msg::sender();
+        let (token_amount, new_price, _weth_collected, _weth_unspent) =
+            self.quote_buy_amounts(pool_id, committer, weth_amount)?;
+
+        let validity_blocks = self.quote_validity_blocks();
+        let expiry_block = block::number().saturating_add(validity_blocks);
+
+        let nonce = self.quote_nonce.get();
+        self.quote_nonce.set(nonce + U256::from(1u64));
+
+        let mut preimage = Vec::with_capacity(160);
+        preimage.extend_from_slice(pool_id.as_slice());
+        preimage.extend_from_slice(committer.as_slice());
+        preimage.extend_from_slice(&weth_amount.to_be_bytes::<32>());
+        preimage.extend_from_slice(&token_amount.to_be_bytes::<32>());
+        preimage.extend_from_slice(&expiry_block.to_be_bytes());
+        preimage.extend_from_slice(&nonce.to_be_bytes::<32>());
+        let commitment = crypto::keccak(&preimage);
+
+        let mut quote = self.quote_commitments.setter(commitment);
+        quote.pool_id.set(pool_id);
+        quote.committer.set(committer);
+        quote.amount_in.set(weth_amount);
+        quote.amount_out.set(token_amount);
+        quote.expiry_block.set(U64::from(expiry_block));
+
+        // Emit event - Quote Committed
+        evm::log(QuoteCommitted {
+            commitment,
+            poolId: pool_id,
+            baseAmount: weth_amount,
+            tokenAmount: token_amount,
+            expiryBlock: expiry_block,
+        });
+
+        Ok((token_amount, new_price, commitment, expiry_block))
+    }
 
-            return Ok((token_amount, new_price));
+    // Execute a buy for `trader` against a quote commitment they made. Reverts if the commitment
+    // expired or the token amount fell more than the configured tolerance below the quote.
+    pub fn calculate_buy_with_commitment(
+        &mut self,
+        trader: Address,
+        commitment: B256,
+    ) -> Result<(U256, U256), PumpUpErrors> {
+        let quote = self.quote_commitments.get(commitment);
+        let expiry_block = quote.expiry_block.get().to::<u64>();
+
+        if expiry_block == 0 || quote.committer.get() != trader {
+            return Err(UnknownQuoteCommitment {}.into());
         }
 
-        // Find token amount using binary search
-        let token_amount =
-            self.find_token_amount_for_weth(circulating_supply, weth_amount, &params, false);
+        if block::number() > expiry_block {
+            return Err(QuoteCommitmentExpired {}.into());
+        }
 
-        // Calculate new price after purchase
-        let new_circulating_supply = circulating_supply + token_amount;
-        let new_price = self.calculate_sigmoid_price(new_circulating_supply, &params);
+        let pool_id = quote.pool_id.get();
+        let weth_amount = quote.amount_in.get();
+        let quoted_tokens = quote.amount_out.get();
 
-        // Emit event - Tokens Purchased
-        let mut topics = Vec::new();
-        let sig = [
-            0xb5, 0x76, 0x4e, 0x7b, 0x82, 0xdd, 0x8f, 0x30, 0x19, 0x96, 0xd3, 0x71, 0x8c, 0xe0,
-            0xa3, 0x43, 0xf4, 0x74, 0xc9, 0x37, 0x93, 0xa6, 0xd3, 0x83, 0xcb, 0x65, 0x6f, 0x91,
-            0x78, 0x69, 0xaf, 0xcf,
-        ];
-        topics.push(B256::from_slice(&sig));
-        topics.push(pool_id);
+        // Commitments are single use
+        let mut quote = self.quote_commitments.setter(commitment);
+        quote.pool_id.set(B256::ZERO);
+        quote.committer.set(Address::ZERO);
+        quote.amount_in.set(U256::ZERO);
+        quote.amount_out.set(U256::ZERO);
+        quote.expiry_block.set(U64::ZERO);
 
-        let mut data = Vec::new();
-        data.extend_from_slice(&weth_amount.to_be_bytes::<32>());
-        data.extend_from_slice(&token_amount.to_be_bytes::<32>());
-        data.extend_from_slice(&new_price.to_be_bytes::<32>());
+        let (token_amount, new_price) = self.calculate_buy(pool_id, trader, weth_amount)?;
 
-        evm::raw_log(&topics, &data);
+        let tolerance = quoted_tokens * self.quote_tolerance_bps() / BPS_DENOMINATOR;
+        if token_amount < quoted_tokens.saturating_sub(tolerance) {
+            return Err(QuoteMoved {}.into());
+        }
 
         Ok((token_amount, new_price))
     }
 
-    // Calculate WETH amount to receive for a given token amount
+    // Calculate base asset amount `trader` receives for a given token amount. Only the pool's
+    // manager executes trades, on behalf of the trader.
     pub fn calculate_sell(
         &mut self,
         pool_id: B256,
+        trader: Address,
         token_amount: U256,
-    ) -> Result<(U256, U256), Vec<u8>> {
-        // Get pool info
+    ) -> Result<(U256, U256), PumpUpErrors> {
+        self.check_pool_manager(pool_id)?;
+        self.check_not_blacklisted(pool_id, trader)?;
+        self.check_compliance(pool_id, trader, TRADE_DIRECTION_SELL, token_amount)?;
+
+        let (weth_to_return, new_price, shortfall, circulating_supply, weth_collected) =
+            self.quote_sell_amounts(pool_id, token_amount)?;
+
+        // Draw the sell floor top-up from the insurance reserve
+        if !shortfall.is_zero() {
+            let reserve = self.insurance_reserves.get(pool_id);
+            let mut reserve_setter = self.insurance_reserves.setter(pool_id);
+            reserve_setter.set(reserve - shortfall);
+
+            // Emit event - Sell Floor Applied
+            evm::log(SellFloorApplied {
+                poolId: pool_id,
+                shortfall,
+                remainingReserve: reserve - shortfall,
+            });
+        }
+
+        let new_circulating_supply = circulating_supply - self.token_to_wad(pool_id, token_amount);
+
+        if self.compact_events.get() {
+            self.emit_compact_trade(pool_id, false, token_amount, weth_to_return);
+        } else {
+            // Emit event - Tokens Sold
+            evm::log(TokensSold {
+                poolId: pool_id,
+                tokenAmount: token_amount,
+                baseAmount: weth_to_return,
+                newPrice: new_price,
+            });
+        }
+
+        self.record_trade(
+            pool_id,
+            trader,
+            TRADE_DIRECTION_SELL,
+            token_amount,
+            weth_to_return,
+            new_price,
+        );
+
+        self.update_pool_cache(
+            pool_id,
+            new_circulating_supply,
+            weth_collected.saturating_sub(weth_to_return - shortfall),
+            false,
+        );
+
+        // Fees come out of the base asset returned
+        let (protocol_fee, creator_fee) = self.trade_fees(pool_id, trader, weth_to_return);
+        self.accrue_fees(pool_id, trader, Address::ZERO, protocol_fee, creator_fee);
+
+        Ok((weth_to_return - protocol_fee - creator_fee, new_price))
+    }
+
+    // Calculate a sell that reverts with SlippageExceeded if it returns less than `min_weth_out`,
+    // and with Expired once the block timestamp passes `deadline` (zero for no deadline)
+    pub fn calculate_sell_with_slippage(
+        &mut self,
+        pool_id: B256,
+        trader: Address,
+        token_amount: U256,
+        min_weth_out: U256,
+        deadline: u64,
+    ) -> Result<(U256, U256), PumpUpErrors> {
+        check_deadline(deadline)?;
+
+        let (weth_amount, new_price) = self.calculate_sell(pool_id, trader, token_amount)?;
+
+        if weth_amount < min_weth_out {
+            return Err(SlippageExceeded {
+                limit: min_weth_out,
+                actual: weth_amount,
+            }
+            .into());
+        }
+
+        Ok((weth_amount, new_price))
+    }
+
+    // Apply a sequence of hypothetical trades by `trader`, given as (is_buy, amount in), to a
+    // scratch copy of the pool state. Returns (amount out, price after) for each trade.
+    pub fn simulate_sequence(
+        &self,
+        pool_id: B256,
+        trader: Address,
+        trades: Vec<(bool, U256)>,
+    ) -> Result<Vec<(U256, U256)>, PumpUpErrors> {
+        self.check_not_paused(pool_id)?;
+
         let (
             token_address,
             _creator,
@@ -258,60 +1709,327 @@ impl SigmoidBondingCurve {
         ) = self.get_pool_info(pool_id)?;
 
         if is_transitioned {
-            return Err(Vec::<u8>::from("Pool has transitioned"));
-        }
-
-        if token_amount.is_zero() {
-            return Err(Vec::<u8>::from("Invalid Amount"));
+            return Err(PoolTransitioned {}.into());
         }
 
-        // Get curve parameters
         let params = self.get_curve_params(pool_id)?;
 
-        // Get current circulating supply
-        let total_token_supply = self.call_total_supply(&token_address)?;
-        let held_by_manager = self.call_balance_of(&token_address, *self.pool_state_manager)?;
-        let circulating_supply = total_token_supply.saturating_sub(held_by_manager);
-
-        if token_amount > circulating_supply {
-            return Err(Vec::<u8>::from("Invalid Amount"));
+        // Scratch state
+        let mut circulating_supply = self.circulating_supply(pool_id, &token_address, &params)?;
+        let mut weth_collected = weth_collected;
+        let mut insurance_reserve = self.insurance_reserves.get(pool_id);
+        let mut transition_pending = self.transition_pending.get(pool_id);
+        let weth_target = self.graduation_weth_targets.get(pool_id);
+
+        // Each trade is quoted and booked the way its execution would be, fees included
+        let mut results = Vec::with_capacity(trades.len());
+        for (is_buy, amount_in) in trades {
+            if is_buy {
+                if transition_pending {
+                    return Err(TransitionPending {}.into());
+                }
+
+                let (token_amount, new_price, _weth_unspent) = self.quote_buy_from_state(
+                    pool_id,
+                    trader,
+                    circulating_supply,
+                    weth_collected,
+                    false,
+                    amount_in,
+                )?;
+                let (protocol_fee, creator_fee) = self.trade_fees(pool_id, trader, amount_in);
+
+                circulating_supply += self.token_to_wad(pool_id, token_amount);
+                weth_collected =
+                    weth_collected.saturating_add(amount_in - protocol_fee - creator_fee);
+                if !weth_target.is_zero() && weth_collected >= weth_target {
+                    transition_pending = true;
+                }
+
+                results.push((token_amount, new_price));
+            } else {
+                let (weth_to_return, new_price, shortfall) = self.quote_sell_from_state(
+                    pool_id,
+                    circulating_supply,
+                    weth_collected,
+                    insurance_reserve,
+                    amount_in,
+                )?;
+                let (protocol_fee, creator_fee) = self.trade_fees(pool_id, trader, weth_to_return);
+
+                insurance_reserve -= shortfall;
+                circulating_supply -= self.token_to_wad(pool_id, amount_in);
+                weth_collected = weth_collected.saturating_sub(weth_to_return - shortfall);
+
+                results.push((weth_to_return - protocol_fee - creator_fee, new_price));
+            }
         }
 
-        // Calculate WETH to return based on area under the curve
-        let weth_to_return =
-            self.calculate_weth_for_token_amount(circulating_supply, token_amount, &params, true);
+        Ok(results)
+    }
 
-        // Check against available liquidity
-        if weth_to_return > weth_collected {
-            return Err(Vec::<u8>::from("Insufficient Liquidity"));
+    // Quote a buy against a caller-supplied circulating supply, without any external calls.
+    // Returns (token amount, new price).
+    pub fn quote_buy_at_supply(
+        &self,
+        pool_id: B256,
+        circulating_supply: U256,
+        weth_amount: U256,
+    ) -> Result<(U256, U256), PumpUpErrors> {
+        self.check_not_paused(pool_id)?;
+
+        if weth_amount.is_zero() {
+            return Err(InvalidAmount {}.into());
         }
 
-        // Calculate the new price after selling
-        let new_circulating_supply = circulating_supply - token_amount;
-        let new_price = self.calculate_sigmoid_price(new_circulating_supply, &params);
-
-        // Emit event - Tokens Sold
-        let mut topics = Vec::new();
-        let sig = [
-            0x6d, 0xfb, 0xff, 0xa4, 0x12, 0x55, 0xd2, 0x61, 0x0f, 0x46, 0xd2, 0x8a, 0x68, 0xf7,
-            0xbb, 0xf0, 0xd3, 0xd0, 0x6a, 0xba, 0x0c, 0x73, 0x2c, 0x9a, 0xdb, 0x02, 0xa9, 0x1f,
-            0x1b, 0xa5, 0xb7, 0x35,
-        ];
-        topics.push(B256::from_slice(&sig));
-        topics.push(pool_id);
-
-        let mut data = Vec::new();
-        data.extend_from_slice(&token_amount.to_be_bytes::<32>());
-        data.extend_from_slice(&weth_to_return.to_be_bytes::<32>());
-        data.extend_from_slice(&new_price.to_be_bytes::<32>());
-
-        evm::raw_log(&topics, &data);
+        let params = self.get_curve_params(pool_id)?;
+
+        let (token_amount, new_price) = self.buy_at_supply(
+            pool_id,
+            self.token_to_wad(pool_id, circulating_supply),
+            weth_amount,
+            &params,
+        );
+
+        Ok((
+            self.wad_to_token(pool_id, token_amount),
+            self.quantize_price(pool_id, new_price, true),
+        ))
+    }
+
+    // Quote a sell against a caller-supplied circulating supply, without any external calls or
+    // liquidity checks. Returns (base asset amount, new price).
+    pub fn quote_sell_at_supply(
+        &self,
+        pool_id: B256,
+        circulating_supply: U256,
+        token_amount: U256,
+    ) -> Result<(U256, U256), PumpUpErrors> {
+        self.check_not_paused(pool_id)?;
+
+        let circulating_supply = self.token_to_wad(pool_id, circulating_supply);
+        let token_amount = self.token_to_wad(pool_id, token_amount);
+        if token_amount.is_zero() || token_amount > circulating_supply {
+            return Err(InvalidAmount {}.into());
+        }
+
+        let params = self.get_curve_params(pool_id)?;
+
+        let weth_to_return =
+            self.base_cost(pool_id, circulating_supply, token_amount, &params, true);
+        let new_price = self.quantize_price(
+            pool_id,
+            self.calculate_sigmoid_price(circulating_supply - token_amount, &params),
+            false,
+        );
 
         Ok((weth_to_return, new_price))
     }
 
+    // Spot price at a caller-supplied circulating supply, without any external calls
+    pub fn get_price_at_supply(
+        &self,
+        pool_id: B256,
+        circulating_supply: U256,
+    ) -> Result<U256, PumpUpErrors> {
+        let params = self.get_curve_params(pool_id)?;
+
+        Ok(self.calculate_sigmoid_price(self.token_to_wad(pool_id, circulating_supply), &params))
+    }
+
+    // Marginal price change per whole token at the current supply (dP/dS, scaled like prices),
+    // zero once the pool has transitioned
+    pub fn get_price_slope(&self, pool_id: B256) -> Result<U256, PumpUpErrors> {
+        let (token_address, _, _, _, is_transitioned, _) = self.get_pool_info(pool_id)?;
+        if is_transitioned {
+            return Ok(U256::ZERO);
+        }
+
+        let params = self.get_curve_params(pool_id)?;
+        let circulating_supply = self.circulating_supply(pool_id, &token_address, &params)?;
+
+        Ok(self.calculate_price_slope(circulating_supply, &params))
+    }
+
+    // Circulating supply at which the curve reaches `price` (0 below the initial price, total
+    // supply at or above the max price), without any external calls
+    pub fn get_supply_at_price(&self, pool_id: B256, price: U256) -> Result<U256, PumpUpErrors> {
+        let params = self.get_curve_params(pool_id)?;
+
+        Ok(self.wad_to_token(pool_id, self.calculate_supply_at_price(price, &params)))
+    }
+
+    // Base asset returned if the whole circulating supply were sold back through the curve, and its
+    // ratio to base asset collected (1e18 = exactly backed, above 1e18 = under-backed)
+    pub fn simulate_sell_all(&self, pool_id: B256) -> Result<(U256, U256), PumpUpErrors> {
+        self.check_not_paused(pool_id)?;
+
+        let (token_address, _, weth_collected, _, _, _) = self.get_pool_info(pool_id)?;
+        let params = self.get_curve_params(pool_id)?;
+
+        let circulating_supply = self.circulating_supply(pool_id, &token_address, &params)?;
+        if circulating_supply.is_zero() {
+            return Ok((U256::ZERO, U256::ZERO));
+        }
+
+        let weth_to_return = self.base_cost(
+            pool_id,
+            circulating_supply,
+            circulating_supply,
+            &params,
+            true,
+        );
+
+        let ratio = if weth_collected.is_zero() {
+            U256::MAX
+        } else {
+            divide_fixed_point(weth_to_return, weth_collected)
+        };
+
+        Ok((weth_to_return, ratio))
+    }
+
+    // Quote a buy for `trader` as (token amount, new price, unspent base asset, stale). In degraded mode a
+    // failed token or manager read falls back to the state cached at the pool's last trade and sets
+    // `stale`.
+    pub fn quote_buy_with_fallback(
+        &self,
+        pool_id: B256,
+        trader: Address,
+        weth_amount: U256,
+    ) -> Result<(U256, U256, U256, bool), PumpUpErrors> {
+        let (circulating_supply, weth_collected, is_transitioned, stale) =
+            self.pool_state(pool_id, true)?;
+
+        let (token_amount, new_price, weth_unspent) = self.quote_buy_from_state(
+            pool_id,
+            trader,
+            circulating_supply,
+            weth_collected,
+            is_transitioned,
+            weth_amount,
+        )?;
+
+        Ok((token_amount, new_price, weth_unspent, stale))
+    }
+
+    // Current spot price as (price, stale), falling back like `quote_buy_with_fallback`
+    pub fn get_current_price_with_fallback(
+        &self,
+        pool_id: B256,
+    ) -> Result<(U256, bool), PumpUpErrors> {
+        let (circulating_supply, _, is_transitioned, stale) = self.pool_state(pool_id, true)?;
+        if !stale {
+            return Ok((self.get_current_price(pool_id)?, false));
+        }
+
+        let params = self.get_curve_params(pool_id)?;
+        let price = if is_transitioned || !circulating_supply.is_zero() {
+            self.calculate_sigmoid_price(circulating_supply, &params)
+        } else {
+            params.initial_price
+        };
+
+        Ok((price, true))
+    }
+
+    // Cache a pool's live state for degraded-mode quotes, e.g. for pools that have not traded
+    // recently
+    pub fn refresh_pool_cache(&mut self, pool_id: B256) -> Result<(), PumpUpErrors> {
+        let (circulating_supply, weth_collected, is_transitioned, _stale) =
+            self.pool_state(pool_id, false)?;
+        self.update_pool_cache(pool_id, circulating_supply, weth_collected, is_transitioned);
+
+        Ok(())
+    }
+
+    // Circulating supply this contract tracks for a pool and whether it is synced (unsynced
+    // pools are priced from the token's supply and the manager's balance)
+    pub fn get_tracked_supply(&self, pool_id: B256) -> (U256, bool) {
+        let account = self.supply_accounts.get(pool_id);
+        (
+            self.wad_to_token(pool_id, account.circulating_supply.get()),
+            account.synced.get(),
+        )
+    }
+
+    // Decimals of a pool's token, 18 until read from the token. Token amounts and supplies are
+    // taken and returned in these decimals and scaled to 18 for pricing, so prices are base asset
+    // per whole token whatever its decimals.
+    pub fn token_decimals(&self, pool_id: B256) -> u8 {
+        let decimals = self.token_decimals.get(pool_id);
+        if decimals.recorded.get() {
+            decimals.decimals.get().to::<u8>()
+        } else {
+            WAD_DECIMALS
+        }
+    }
+
+    // Record tokens the curve sold (only the pool's manager). Returns the new circulating supply.
+    pub fn notify_buy(&mut self, pool_id: B256, token_amount: U256) -> Result<U256, PumpUpErrors> {
+        let circulating_supply = self.tracked_supply_for_update(pool_id)?;
+        let params = self.get_curve_params(pool_id)?;
+
+        let new_circulating_supply = circulating_supply
+            .checked_add(self.token_to_wad(pool_id, token_amount))
+            .filter(|supply| *supply <= params.total_supply)
+            .ok_or(InvalidAmount {})?;
+        self.set_tracked_supply(pool_id, circulating_supply, new_circulating_supply);
+
+        Ok(self.wad_to_token(pool_id, new_circulating_supply))
+    }
+
+    // Record tokens sold back to the curve (only the pool's manager). Returns the new circulating
+    // supply.
+    pub fn notify_sell(&mut self, pool_id: B256, token_amount: U256) -> Result<U256, PumpUpErrors> {
+        let circulating_supply = self.tracked_supply_for_update(pool_id)?;
+
+        let new_circulating_supply = circulating_supply
+            .checked_sub(self.token_to_wad(pool_id, token_amount))
+            .ok_or(InvalidAmount {})?;
+        self.set_tracked_supply(pool_id, circulating_supply, new_circulating_supply);
+
+        Ok(self.wad_to_token(pool_id, new_circulating_supply))
+    }
+
+    // Reset the tracked circulating supply to the token's total supply minus the manager's
+    // balance and mark the pool synced (only the pool's manager). Starts tracking for pools
+    // initialized before it existed and corrects drift. Returns the new circulating supply.
+    pub fn sync_supply(&mut self, pool_id: B256) -> Result<U256, PumpUpErrors> {
+        self.check_pool_manager(pool_id)?;
+
+        let params = self.get_curve_params(pool_id)?;
+        let (token_address, _, _, _, _, _) = self.get_pool_info(pool_id)?;
+        self.record_token_decimals(pool_id, token_address);
+        let new_circulating_supply =
+            self.token_circulating_supply(pool_id, &token_address, &params)?;
+
+        let previous_circulating_supply =
+            self.supply_accounts.get(pool_id).circulating_supply.get();
+        self.set_tracked_supply(pool_id, previous_circulating_supply, new_circulating_supply);
+
+        Ok(self.wad_to_token(pool_id, new_circulating_supply))
+    }
+
+    // Reserves shaped like UniswapV2's getReserves: (tokens remaining on the curve, base asset
+    // backing sells per the manager, timestamp of the last trade)
+    pub fn get_reserves(&self, pool_id: B256) -> Result<(U256, U256, u32), PumpUpErrors> {
+        let (token_address, _, weth_collected, _, _, _) = self.get_pool_info(pool_id)?;
+        let params = self.get_curve_params(pool_id)?;
+
+        let circulating_supply = self.circulating_supply(pool_id, &token_address, &params)?;
+        let token_reserve = params.total_supply - circulating_supply;
+        let last_trade_timestamp = self.last_trades.get(pool_id).timestamp.get().to::<u64>();
+
+        Ok((
+            self.wad_to_token(pool_id, token_reserve),
+            weth_collected,
+            last_trade_timestamp as u32,
+        ))
+    }
+
     // Get current token price
-    pub fn get_current_price(&self, pool_id: B256) -> Result<U256, Vec<u8>> {
+    pub fn get_current_price(&self, pool_id: B256) -> Result<U256, PumpUpErrors> {
         let (
             token_address,
             _creator,
@@ -328,385 +2046,4768 @@ impl SigmoidBondingCurve {
         let params = self.get_curve_params(pool_id)?;
 
         // Get current circulating supply
-        let total_token_supply = self.call_total_supply(&token_address)?;
-        let held_by_manager = self.call_balance_of(&token_address, *self.pool_state_manager)?;
-        let circulating_supply = total_token_supply.saturating_sub(held_by_manager);
+        let circulating_supply = self.circulating_supply(pool_id, &token_address, &params)?;
 
         // If no tokens have been sold yet, return the initial price
         if circulating_supply.is_zero() {
             return Ok(params.initial_price);
         }
 
-        Ok(self.calculate_sigmoid_price(circulating_supply, &params))
+        let price = self.calculate_sigmoid_price(circulating_supply, &params);
+        self.check_price_bounds(price, &params)?;
+
+        Ok(price)
     }
 
-    // Calculate WETH needed for exact token amount
-    pub fn calculate_weth_for_exact_tokens(
-        &mut self,
-        pool_id: B256,
-        exact_token_amount: U256,
-    ) -> Result<(U256, U256), Vec<u8>> {
-        let (
-            token_address,
-            _creator,
-            _weth_collected,
-            _last_price,
-            is_transitioned,
-            _bonding_curve_strategy,
-        ) = self.get_pool_info(pool_id)?;
+    // Current prices of many pools in one call, zero for pools that are unknown or whose state
+    // can't be read. Repeated pool ids reuse the first read of the pool's info.
+    pub fn get_current_prices(&self, pool_ids: Vec<B256>) -> Vec<U256> {
+        pool_ids
+            .into_iter()
+            .map(|pool_id| {
+                if !self.pool_exists(pool_id) {
+                    return U256::ZERO;
+                }
+                self.get_current_price(pool_id).unwrap_or_default()
+            })
+            .collect()
+    }
 
-        if is_transitioned {
-            return Err(Vec::<u8>::from("Pool has transitioned"));
+    // Market cap in the base asset: circulating supply times the current price
+    pub fn get_market_cap(&self, pool_id: B256) -> Result<U256, PumpUpErrors> {
+        let (token_address, _, _, _, _, _) = self.get_pool_info(pool_id)?;
+        let params = self.get_curve_params(pool_id)?;
+        let circulating_supply = self.circulating_supply(pool_id, &token_address, &params)?;
+
+        Ok(self.wad_to_base(
+            pool_id,
+            multiply_fixed_point(circulating_supply, self.get_current_price(pool_id)?),
+            Rounding::Down,
+        ))
+    }
+
+    // Fully diluted valuation in the base asset: the pool's total supply times the current price
+    pub fn get_fdv(&self, pool_id: B256) -> Result<U256, PumpUpErrors> {
+        let params = self.get_curve_params(pool_id)?;
+
+        Ok(self.wad_to_base(
+            pool_id,
+            multiply_fixed_point(params.total_supply, self.get_current_price(pool_id)?),
+            Rounding::Down,
+        ))
+    }
+
+    // Tokens still for sale on the curve (zero once the pool has transitioned)
+    pub fn get_remaining_supply(&self, pool_id: B256) -> Result<U256, PumpUpErrors> {
+        let (_, remaining_supply, _) = self.remaining_on_curve(pool_id)?;
+
+        Ok(self.wad_to_token(pool_id, remaining_supply))
+    }
+
+    // Base asset the curve charges, before fees, to buy every token still for sale
+    pub fn get_weth_to_sell_out(&self, pool_id: B256) -> Result<U256, PumpUpErrors> {
+        let (circulating_supply, remaining_supply, params) = self.remaining_on_curve(pool_id)?;
+        if remaining_supply.is_zero() {
+            return Ok(U256::ZERO);
         }
 
+        Ok(self.base_cost(
+            pool_id,
+            circulating_supply,
+            remaining_supply,
+            &params,
+            false,
+        ))
+    }
+
+    // Calculate base asset `trader` needs, fees included, to buy an exact token amount. The amount
+    // is checked and priced like the buy that would execute it.
+    pub fn calculate_weth_for_exact_tokens(
+        &self,
+        pool_id: B256,
+        trader: Address,
+        exact_token_amount: U256,
+    ) -> Result<(U256, U256), PumpUpErrors> {
         if exact_token_amount.is_zero() {
-            return Err(Vec::<u8>::from("Invalid Amount"));
+            return Err(InvalidAmount {}.into());
         }
 
+        let (circulating_supply, weth_collected, is_transitioned, _stale) =
+            self.pool_state(pool_id, false)?;
+
         // Get curve parameters
         let params = self.get_curve_params(pool_id)?;
 
-        // Get current circulating supply
-        let total_token_supply = self.call_total_supply(&token_address)?;
-        let held_by_manager = self.call_balance_of(&token_address, *self.pool_state_manager)?;
-        let circulating_supply = total_token_supply.saturating_sub(held_by_manager);
-
-        // Calculate WETH needed
-        let weth_needed = self.calculate_weth_for_token_amount(
+        // Curve cost of the tokens, grossed up so what is left after fees still covers it
+        let weth_cost = self.base_cost(
+            pool_id,
             circulating_supply,
-            exact_token_amount,
+            self.token_to_wad(pool_id, exact_token_amount),
             &params,
             false, // buying tokens
         );
+        let weth_needed = self.amount_before_fees(pool_id, trader, weth_cost);
+
+        let (_token_amount, new_price, _weth_unspent) = self.quote_buy_from_state(
+            pool_id,
+            trader,
+            circulating_supply,
+            weth_collected,
+            is_transitioned,
+            weth_needed,
+        )?;
+
+        Ok((weth_needed, new_price))
+    }
 
-        // Calculate new price
-        let new_circulating_supply = circulating_supply + exact_token_amount;
-        let new_price = self.calculate_sigmoid_price(new_circulating_supply, &params);
+    // Calculate base asset needed for an exact token amount, reverting with SlippageExceeded if it
+    // exceeds `max_weth_in`
+    pub fn calculate_weth_for_exact_tokens_with_slippage(
+        &self,
+        pool_id: B256,
+        trader: Address,
+        exact_token_amount: U256,
+        max_weth_in: U256,
+    ) -> Result<(U256, U256), PumpUpErrors> {
+        let (weth_needed, new_price) =
+            self.calculate_weth_for_exact_tokens(pool_id, trader, exact_token_amount)?;
+
+        if weth_needed > max_weth_in {
+            return Err(SlippageExceeded {
+                limit: max_weth_in,
+                actual: weth_needed,
+            }
+            .into());
+        }
 
         Ok((weth_needed, new_price))
     }
 
-    // Calculate tokens needed for exact WETH amount
+    // Calculate tokens `trader` receives for an exact base asset amount, fees included
     pub fn calculate_tokens_for_exact_weth(
-        &mut self,
+        &self,
         pool_id: B256,
+        trader: Address,
         exact_weth_amount: U256,
-    ) -> Result<(U256, U256), Vec<u8>> {
-        let (
-            token_address,
-            _creator,
-            _weth_collected,
-            _last_price,
-            is_transitioned,
-            _bonding_curve_strategy,
-        ) = self.get_pool_info(pool_id)?;
+    ) -> Result<(U256, U256), PumpUpErrors> {
+        let (token_amount, new_price, _weth_unspent) =
+            self.quote_buy(pool_id, trader, exact_weth_amount)?;
 
-        if is_transitioned {
-            return Err(Vec::<u8>::from("Pool has transitioned"));
+        Ok((token_amount, new_price))
+    }
+
+    // Uniswap v4 pool id of a pool key: keccak256(abi.encode(key))
+    pub fn get_pool_id(&self, key: PoolKey) -> B256 {
+        pool_id_from_key(&key)
+    }
+
+    // Initialize the strategy for a new pool, identifying the pool by its Uniswap v4 key
+    #[selector(name = "initialize")]
+    pub fn initialize_by_key(&mut self, key: PoolKey, params: Bytes) -> Result<(), PumpUpErrors> {
+        self.initialize(pool_id_from_key(&key), params)
+    }
+
+    // Calculate token amount to receive for a given base asset amount, identifying the pool by its
+    // Uniswap v4 key
+    #[selector(name = "calculateBuy")]
+    pub fn calculate_buy_by_key(
+        &mut self,
+        key: PoolKey,
+        trader: Address,
+        weth_amount: U256,
+    ) -> Result<(U256, U256), PumpUpErrors> {
+        self.calculate_buy(pool_id_from_key(&key), trader, weth_amount)
+    }
+
+    // Calculate base asset amount to receive for a given token amount, identifying the pool by its
+    // Uniswap v4 key
+    #[selector(name = "calculateSell")]
+    pub fn calculate_sell_by_key(
+        &mut self,
+        key: PoolKey,
+        trader: Address,
+        token_amount: U256,
+    ) -> Result<(U256, U256), PumpUpErrors> {
+        self.calculate_sell(pool_id_from_key(&key), trader, token_amount)
+    }
+
+    // Get current token price, identifying the pool by its Uniswap v4 key
+    #[selector(name = "getCurrentPrice")]
+    pub fn get_current_price_by_key(&self, key: PoolKey) -> Result<U256, PumpUpErrors> {
+        self.get_current_price(pool_id_from_key(&key))
+    }
+
+    // Calculate base asset needed for exact token amount, identifying the pool by its Uniswap v4
+    // key
+    #[selector(name = "calculateWethForExactTokens")]
+    pub fn calculate_weth_for_exact_tokens_by_key(
+        &self,
+        key: PoolKey,
+        trader: Address,
+        exact_token_amount: U256,
+    ) -> Result<(U256, U256), PumpUpErrors> {
+        self.calculate_weth_for_exact_tokens(pool_id_from_key(&key), trader, exact_token_amount)
+    }
+
+    // Calculate tokens needed for exact base asset amount, identifying the pool by its Uniswap v4
+    // key
+    #[selector(name = "calculateTokensForExactWeth")]
+    pub fn calculate_tokens_for_exact_weth_by_key(
+        &self,
+        key: PoolKey,
+        trader: Address,
+        exact_weth_amount: U256,
+    ) -> Result<(U256, U256), PumpUpErrors> {
+        self.calculate_tokens_for_exact_weth(pool_id_from_key(&key), trader, exact_weth_amount)
+    }
+
+    // Run several calls to this contract in one transaction, e.g. price reads for many pools or a
+    // batch of initializations, and return each call's ABI-encoded result. Calls run in order as
+    // the caller; the first one to fail reverts the batch with its own revert data.
+    pub fn multicall(&mut self, data: Vec<Bytes>) -> Result<Vec<Bytes>, Vec<u8>> {
+        let mut results = Vec::with_capacity(data.len());
+        for call in data {
+            if call.len() < 4 {
+                return Err(PumpUpErrors::from(InsufficientDataLength {}).into());
+            }
+
+            let selector = u32::from_be_bytes([call[0], call[1], call[2], call[3]]);
+            let result = <Self as Router<Self>>::route(self, selector, &call[4..]).ok_or_else(
+                || -> Vec<u8> {
+                    PumpUpErrors::from(UnknownSelector {
+                        selector: FixedBytes(selector.to_be_bytes()),
+                    })
+                    .into()
+                },
+            )?;
+            results.push(Bytes::from(result?));
+        }
+
+        Ok(results)
+    }
+
+    // List every error the contract can revert with as (selector, name, message).
+    // The selector is that of the custom error's full signature.
+    pub fn error_catalog(&self) -> Vec<(FixedBytes<4>, String, String)> {
+        ERROR_CATALOG
+            .iter()
+            .map(|(signature, message)| {
+                let hash = crypto::keccak(signature);
+                let name = signature.split('(').next().unwrap_or(signature);
+                (
+                    FixedBytes::<4>::from_slice(&hash[0..4]),
+                    String::from(name),
+                    String::from(*message),
+                )
+            })
+            .collect()
+    }
+
+    // Get the contract owner
+    pub fn owner(&self) -> Address {
+        *self.owner
+    }
+
+    // Set pool state manager (only owner)
+    pub fn set_pool_state_manager(
+        &mut self,
+        new_pool_state_manager: Address,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_owner()?;
+        self.update_pool_state_manager(new_pool_state_manager)
+    }
+
+    // Time-weighted average price of a pool over the last `seconds_ago` seconds, from the
+    // observations its trades write. Between trades the price is the one the last trade left.
+    // A zero window returns that price.
+    pub fn consult(&self, pool_id: B256, seconds_ago: u32) -> Result<U256, PumpUpErrors> {
+        let count = self.observation_counts.get(pool_id);
+        if count.is_zero() {
+            return Err(InsufficientObservations {}.into());
+        }
+        if seconds_ago == 0 {
+            return Ok(self.last_trades.get(pool_id).price.get());
         }
 
-        if exact_weth_amount.is_zero() {
-            return Err(Vec::<u8>::from("Invalid Amount"));
+        self.time_weighted_price(pool_id, count, seconds_ago, block::timestamp())
+    }
+
+    // Latest price of a pool shaped like Chainlink's AggregatorV3 latestRoundData: (round id,
+    // answer, started at, updated at, answered in round). Each second with trades is a round, so
+    // the round id counts them and both timestamps are the pool's last trade. The answer is the
+    // current curve price, or the TWAP over the pool's price feed window when one is set, with
+    // PRICE_FEED_DECIMALS decimals. Round ids stay below 2^80, so the return data decodes as
+    // AggregatorV3's uint80 rounds.
+    pub fn latest_round_data(
+        &self,
+        pool_id: B256,
+    ) -> Result<(u128, I256, U256, U256, u128), PumpUpErrors> {
+        let count = self.observation_counts.get(pool_id);
+        if count.is_zero() {
+            return Err(InsufficientObservations {}.into());
         }
 
-        // Get curve parameters
+        let window = self.price_feed_twap_windows.get(pool_id).to::<u32>();
+        let price = if window == 0 {
+            self.get_current_price(pool_id)?
+        } else {
+            self.consult(pool_id, window)?
+        };
+
+        let round_id = count.saturating_to::<u128>();
+        let answer = I256::try_from(price).unwrap_or(I256::MAX);
+        let (updated_at, _) = self.latest_observation(pool_id, count);
+
+        Ok((
+            round_id,
+            answer,
+            U256::from(updated_at),
+            U256::from(updated_at),
+            round_id,
+        ))
+    }
+
+    // Decimals of the price feed answers
+    pub fn price_feed_decimals(&self) -> u8 {
+        PRICE_FEED_DECIMALS
+    }
+
+    // TWAP window in seconds of a pool's price feed answers (0 answers the spot price)
+    pub fn price_feed_twap_window(&self, pool_id: B256) -> u32 {
+        self.price_feed_twap_windows.get(pool_id).to::<u32>()
+    }
+
+    // Set the TWAP window of a pool's price feed answers, 0 for the spot price (owner or param
+    // admin)
+    pub fn set_price_feed_twap_window(
+        &mut self,
+        pool_id: B256,
+        window: u32,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_role(PARAM_ADMIN_ROLE)?;
+        self.get_curve_params(pool_id)?;
+
+        let previous_window = self.price_feed_twap_window(pool_id);
+        self.price_feed_twap_windows
+            .setter(pool_id)
+            .set(U32::from(window));
+
+        // Emit event - Price Feed TWAP Window Set
+        evm::log(PriceFeedTwapWindowSet {
+            poolId: pool_id,
+            previousWindow: previous_window,
+            window,
+        });
+
+        Ok(())
+    }
+
+    // Most recent trade of a pool as (timestamp, direction, token amount, base asset amount,
+    // resulting price). Direction is 0 when the pool has not traded yet, 1 for buys and 2 for
+    // sells.
+    pub fn last_trade(&self, pool_id: B256) -> (u64, u8, U256, U256, U256) {
+        let trade = self.last_trades.get(pool_id);
+        let direction = trade.direction.get().to::<u8>();
+        if direction == TRADE_DIRECTION_NONE {
+            return (0, TRADE_DIRECTION_NONE, U256::ZERO, U256::ZERO, U256::ZERO);
+        }
+
+        (
+            trade.timestamp.get().to::<u64>(),
+            direction,
+            trade.token_amount.get(),
+            trade.weth_amount.get(),
+            trade.price.get(),
+        )
+    }
+
+    // Current price and circulating supply of a pool with the EIP-712 digest of
+    // PriceAttestation(pool_id, price, supply, block) for the owner or attester to sign.
+    // Returns (price, supply, block number, digest).
+    pub fn attest_price(&self, pool_id: B256) -> Result<(U256, U256, u64, B256), PumpUpErrors> {
+        let (token_address, _, _, _, _, _) = self.get_pool_info(pool_id)?;
         let params = self.get_curve_params(pool_id)?;
 
-        // Get current circulating supply
-        let total_token_supply = self.call_total_supply(&token_address)?;
-        let held_by_manager = self.call_balance_of(&token_address, *self.pool_state_manager)?;
-        let circulating_supply = total_token_supply.saturating_sub(held_by_manager);
+        let circulating_supply = self.wad_to_token(
+            pool_id,
+            self.circulating_supply(pool_id, &token_address, &params)?,
+        );
 
-        // Calculate tokens needed using binary search
-        let tokens_needed = self.find_token_amount_for_weth(
-            circulating_supply,
-            exact_weth_amount,
+        let price = self.get_current_price(pool_id)?;
+        let block_number = block::number();
+        let digest =
+            self.price_attestation_digest(pool_id, price, circulating_supply, block_number);
+
+        Ok((price, circulating_supply, block_number, digest))
+    }
+
+    // Whether (v, r, s) is a signature by the owner or the price attester over the
+    // attestation of the given pool, price, supply and block
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_price_attestation(
+        &self,
+        pool_id: B256,
+        price: U256,
+        supply: U256,
+        block_number: u64,
+        v: u8,
+        r: B256,
+        s: B256,
+    ) -> bool {
+        let digest = self.price_attestation_digest(pool_id, price, supply, block_number);
+
+        // ecrecover precompile
+        let mut input = Vec::with_capacity(128);
+        input.extend_from_slice(digest.as_slice());
+        input.extend_from_slice(&U256::from(v).to_be_bytes::<32>());
+        input.extend_from_slice(r.as_slice());
+        input.extend_from_slice(s.as_slice());
+
+        let result = match RawCall::new_static().call(Address::with_last_byte(1), &input) {
+            Ok(result) if result.len() >= 32 => result,
+            _ => return false,
+        };
+        let signer = Address::from_slice(&result[12..32]);
+        if signer.is_zero() {
+            return false;
+        }
+
+        signer == self.owner.get() || signer == self.price_attester.get()
+    }
+
+    // Oracle allowed to sign price attestations besides the owner
+    pub fn price_attester(&self) -> Address {
+        self.price_attester.get()
+    }
+
+    // Set the oracle allowed to sign price attestations, zero to allow only the owner (only owner)
+    pub fn set_price_attester(&mut self, attester: Address) -> Result<(), PumpUpErrors> {
+        self.only_owner()?;
+
+        let previous_attester = self.price_attester.get();
+        self.price_attester.set(attester);
+
+        // Emit event - Price Attester Set
+        evm::log(PriceAttesterSet {
+            previousAttester: previous_attester,
+            attester,
+        });
+
+        Ok(())
+    }
+
+    // Recent trades of a pool, newest first, as (trader, direction, token amount, base asset
+    // amount, resulting price, block number). Only the last `trade_log_capacity` trades are kept.
+    pub fn trades(
+        &self,
+        pool_id: B256,
+        offset: U256,
+        limit: U256,
+    ) -> Vec<(Address, u8, U256, U256, U256, u64)> {
+        let count = self.trade_log_counts.get(pool_id);
+        let available = count.min(U256::from(self.trade_log_capacity.get()));
+        let start = offset.min(available);
+        let end = start.saturating_add(limit).min(available);
+
+        let log = self.trade_logs.get(pool_id);
+        let mut trades = Vec::new();
+        let mut i = start;
+        while i < end {
+            let trade = log.get(count - U256::from(1) - i);
+            // Entries dropped before a capacity increase are gone
+            if trade.trader.get().is_zero() {
+                break;
+            }
+            trades.push((
+                trade.trader.get(),
+                trade.direction.get().to::<u8>(),
+                trade.token_amount.get(),
+                trade.weth_amount.get(),
+                trade.price.get(),
+                trade.block_number.get().to::<u64>(),
+            ));
+            i += U256::from(1);
+        }
+
+        trades
+    }
+
+    // Number of trades kept per pool in the trade log
+    pub fn trade_log_capacity(&self) -> u64 {
+        self.trade_log_capacity.get().to::<u64>()
+    }
+
+    // Set how many recent trades are kept per pool, 0 disables the trade log (owner or param admin)
+    pub fn set_trade_log_capacity(&mut self, capacity: u64) -> Result<(), PumpUpErrors> {
+        self.only_role(PARAM_ADMIN_ROLE)?;
+
+        if capacity > MAX_TRADE_LOG_CAPACITY {
+            return Err(InvalidTradeLogCapacity {}.into());
+        }
+
+        let previous_capacity = self.trade_log_capacity();
+        self.trade_log_capacity.set(U64::from(capacity));
+
+        // Emit event - Trade Log Capacity Set
+        evm::log(TradeLogCapacitySet {
+            previousCapacity: previous_capacity,
+            capacity,
+        });
+
+        Ok(())
+    }
+
+    // Trading activity of a pool as (base asset bought with, base asset sold for, number of trades)
+    pub fn get_pool_stats(&self, pool_id: B256) -> (U256, U256, u64) {
+        let stats = self.pool_stats.get(pool_id);
+        (
+            stats.buy_volume.get(),
+            stats.sell_volume.get(),
+            stats.trade_count.get().to::<u64>(),
+        )
+    }
+
+    // Whether a pool's trades run the curve math overflow-checked, reverting with MathOverflow
+    // where saturating math would clamp. Always on when built with the checked-math feature.
+    pub fn checked_math(&self, pool_id: B256) -> bool {
+        cfg!(feature = "checked-math") || self.checked_math_pools.get(pool_id)
+    }
+
+    // Enable or disable overflow-checked curve math for a pool (owner or param admin)
+    pub fn set_checked_math(&mut self, pool_id: B256, enabled: bool) -> Result<(), PumpUpErrors> {
+        self.only_role(PARAM_ADMIN_ROLE)?;
+        self.get_curve_params(pool_id)?;
+
+        let previous_enabled = self.checked_math_pools.get(pool_id);
+        self.checked_math_pools.setter(pool_id).set(enabled);
+
+        // Emit event - Checked Math Set
+        evm::log(CheckedMathSet {
+            poolId: pool_id,
+            previousEnabled: previous_enabled,
+            enabled,
+        });
+
+        Ok(())
+    }
+
+    // Asset a pool is priced and settled in as (address, decimals): the quote token for pools
+    // without a configured base asset, and the zero address for the native currency. Prices are
+    // 18-decimal amounts of the base asset per whole token whatever its decimals.
+    pub fn base_asset(&self, pool_id: B256) -> (Address, u8) {
+        let base_asset = self.base_assets.get(pool_id);
+        if base_asset.configured.get() {
+            (base_asset.asset.get(), base_asset.decimals.get().to::<u8>())
+        } else {
+            (*self.quote_token, QUOTE_TOKEN_DECIMALS)
+        }
+    }
+
+    // Price and settle a pool in another asset, such as a stablecoin or the native currency (the
+    // zero address, with 18 decimals). Tokens reporting decimals() must match `decimals`. Only
+    // before the pool's first trade (only param admin).
+    pub fn set_base_asset(
+        &mut self,
+        pool_id: B256,
+        asset: Address,
+        decimals: u8,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_role(PARAM_ADMIN_ROLE)?;
+        self.get_curve_params(pool_id)?;
+
+        if !self.pool_stats.get(pool_id).trade_count.get().is_zero()
+            || !self.creator_fees_accrued.get(pool_id).is_zero()
+        {
+            return Err(BaseAssetLocked {}.into());
+        }
+
+        let decimals_match = if asset == NATIVE_BASE_ASSET {
+            decimals == WAD_DECIMALS
+        } else {
+            host()
+                .decimals(asset)
+                .map_or(true, |reported| reported == decimals)
+        };
+        if decimals > MAX_TOKEN_DECIMALS || !decimals_match {
+            return Err(InvalidBaseAsset {}.into());
+        }
+
+        let (previous_asset, previous_decimals) = self.base_asset(pool_id);
+        let mut base_asset = self.base_assets.setter(pool_id);
+        base_asset.configured.set(true);
+        base_asset.asset.set(asset);
+        base_asset.decimals.set(U8::from(decimals));
+
+        // Emit event - Base Asset Set
+        evm::log(BaseAssetSet {
+            poolId: pool_id,
+            previousAsset: previous_asset,
+            previousDecimals: previous_decimals,
+            asset,
+            decimals,
+        });
+
+        Ok(())
+    }
+
+    // Estimated wash volume of a pool in the base asset: the offset part of opposite trades by the
+    // same address within the wash window
+    pub fn wash_volume(&self, pool_id: B256) -> U256 {
+        self.wash_volumes.get(pool_id)
+    }
+
+    // Whether an address is flagged for repeated offsetting trades in a pool
+    pub fn is_wash_flagged(&self, pool_id: B256, account: Address) -> bool {
+        self.wash_trackers.get(pool_id).get(account).flagged.get()
+    }
+
+    // Addresses flagged for wash trading in a pool, in flagging order
+    pub fn wash_flagged_addresses(&self, pool_id: B256, offset: U256, limit: U256) -> Vec<Address> {
+        let flagged = self.wash_flagged.get(pool_id);
+        let count = flagged.len();
+        let start = offset.min(U256::from(count)).to::<usize>();
+        let end = start
+            .saturating_add(limit.min(U256::from(count)).to::<usize>())
+            .min(count);
+
+        (start..end)
+            .map(|i| flagged.get(i).unwrap_or_default())
+            .collect()
+    }
+
+    // Deployment-wide totals as (pools initialized, pools graduated, base asset volume, fees
+    // collected)
+    pub fn global_stats(&self) -> (U256, U256, U256, U256) {
+        (
+            U256::from(self.pool_ids.len()),
+            self.total_pools_graduated.get(),
+            self.total_weth_volume.get(),
+            self.total_fees_collected.get(),
+        )
+    }
+
+    // Minimum price sells of this pool are quoted at (zero if disabled)
+    pub fn get_sell_price_floor(&self, pool_id: B256) -> U256 {
+        self.sell_price_floors.get(pool_id)
+    }
+
+    // Insurance reserve available to cover sell floor shortfalls
+    pub fn get_insurance_reserve(&self, pool_id: B256) -> U256 {
+        self.insurance_reserves.get(pool_id)
+    }
+
+    // Set the minimum price sells are quoted at, e.g. the initial price (owner or param admin).
+    // A floor of zero disables the guard.
+    pub fn set_sell_price_floor(
+        &mut self,
+        pool_id: B256,
+        floor_price: U256,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_role(PARAM_ADMIN_ROLE)?;
+        self.get_curve_params(pool_id)?;
+
+        let previous_floor_price = self.sell_price_floors.get(pool_id);
+        let mut floor_setter = self.sell_price_floors.setter(pool_id);
+        floor_setter.set(floor_price);
+
+        // Emit event - Sell Price Floor Set
+        evm::log(SellPriceFloorSet {
+            poolId: pool_id,
+            previousFloorPrice: previous_floor_price,
+            floorPrice: floor_price,
+        });
+
+        Ok(())
+    }
+
+    // Credit the insurance reserve of a pool (only owner).
+    // Mirrors the base asset the manager sets aside for covering sell floor shortfalls.
+    pub fn fund_insurance_reserve(
+        &mut self,
+        pool_id: B256,
+        amount: U256,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_owner()?;
+        self.get_curve_params(pool_id)?;
+
+        if amount.is_zero() {
+            return Err(InvalidAmount {}.into());
+        }
+
+        let new_reserve = self.insurance_reserves.get(pool_id).saturating_add(amount);
+        let mut reserve_setter = self.insurance_reserves.setter(pool_id);
+        reserve_setter.set(new_reserve);
+
+        // Emit event - Insurance Reserve Funded
+        evm::log(InsuranceReserveFunded {
+            poolId: pool_id,
+            amount,
+            newReserve: new_reserve,
+        });
+
+        Ok(())
+    }
+
+    // Base asset collected at which a pool graduates (zero if no threshold is set)
+    pub fn get_graduation_weth_target(&self, pool_id: B256) -> U256 {
+        self.graduation_weth_targets.get(pool_id)
+    }
+
+    // Whether a pool crossed its graduation threshold and buys are blocked until the manager acts
+    pub fn is_transition_pending(&self, pool_id: B256) -> bool {
+        self.transition_pending.get(pool_id)
+    }
+
+    // Set the base asset collected at which a pool graduates (owner or param admin). Zero disables
+    // the threshold.
+    pub fn set_graduation_weth_target(
+        &mut self,
+        pool_id: B256,
+        weth_target: U256,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_role(PARAM_ADMIN_ROLE)?;
+        self.get_curve_params(pool_id)?;
+
+        let previous_weth_target = self.graduation_weth_targets.get(pool_id);
+        let mut target_setter = self.graduation_weth_targets.setter(pool_id);
+        target_setter.set(weth_target);
+
+        // Emit event - Graduation Target Set
+        evm::log(GraduationTargetSet {
+            poolId: pool_id,
+            previousBaseTarget: previous_weth_target,
+            baseTarget: weth_target,
+        });
+
+        Ok(())
+    }
+
+    // Graduation progress of a pool as (base asset collected, graduation target); the target is
+    // zero when no threshold is set
+    pub fn graduation_progress(&self, pool_id: B256) -> Result<(U256, U256), PumpUpErrors> {
+        self.get_curve_params(pool_id)?;
+
+        let (_, _, weth_collected, _, _, _) = self.get_pool_info(pool_id)?;
+
+        Ok((weth_collected, self.graduation_weth_targets.get(pool_id)))
+    }
+
+    // Final curve price as the (sqrtPriceX96, tick) to initialize the pool's Uniswap v4 pool at
+    pub fn get_graduation_price_x96(&self, pool_id: B256) -> Result<(U256, i32), PumpUpErrors> {
+        let (sqrt_price_x96, _) = self.graduation_sqrt_price(pool_id)?;
+
+        Ok((sqrt_price_x96, get_tick_at_sqrt_price(sqrt_price_x96)))
+    }
+
+    // Full-range v4 position reproducing the final curve price from the pool's remaining token
+    // inventory and collected base asset, as (token amount, base asset amount, lower tick, upper
+    // tick, liquidity). Whatever one side cannot pair with at that price is left over.
+    pub fn calculate_graduation_liquidity(
+        &self,
+        pool_id: B256,
+    ) -> Result<(U256, U256, i32, i32, U256), PumpUpErrors> {
+        let (token_address, _, weth_collected, _, _, _) = self.get_pool_info(pool_id)?;
+        let params = self.get_curve_params(pool_id)?;
+        let circulating_supply = self.circulating_supply(pool_id, &token_address, &params)?;
+        let token_inventory = params.total_supply - circulating_supply;
+
+        let (tick_lower, tick_upper) = usable_tick_range(GRADUATION_TICK_SPACING);
+        let sqrt_price_lower = get_sqrt_price_at_tick(tick_lower);
+        let sqrt_price_upper = get_sqrt_price_at_tick(tick_upper);
+
+        // Keep the price strictly inside the range so both sides of the position are priced
+        let (sqrt_price_x96, token_is_currency0) = self.graduation_sqrt_price(pool_id)?;
+        let sqrt_price_x96 = sqrt_price_x96.clamp(
+            sqrt_price_lower + U256::from(1u8),
+            sqrt_price_upper - U256::from(1u8),
+        );
+
+        let (amount0, amount1) = if token_is_currency0 {
+            (token_inventory, weth_collected)
+        } else {
+            (weth_collected, token_inventory)
+        };
+        let liquidity = liquidity_for_amounts(
+            sqrt_price_x96,
+            sqrt_price_lower,
+            sqrt_price_upper,
+            amount0,
+            amount1,
+        );
+        let (amount0, amount1) = amounts_for_liquidity(
+            sqrt_price_x96,
+            sqrt_price_lower,
+            sqrt_price_upper,
+            liquidity,
+        );
+
+        let (token_amount, weth_amount) = if token_is_currency0 {
+            (amount0, amount1)
+        } else {
+            (amount1, amount0)
+        };
+
+        Ok((token_amount, weth_amount, tick_lower, tick_upper, liquidity))
+    }
+
+    // Whether the pool's manager is called back when a buy reaches the graduation target
+    pub fn graduation_callback_enabled(&self) -> bool {
+        self.graduation_callback_enabled.get()
+    }
+
+    // Enable or disable calling onGraduationThresholdReached(bytes32,uint256) on the pool's
+    // manager when a buy reaches the graduation target (only owner)
+    pub fn set_graduation_callback(&mut self, enabled: bool) -> Result<(), PumpUpErrors> {
+        self.only_owner()?;
+
+        let previous_enabled = self.graduation_callback_enabled.get();
+        self.graduation_callback_enabled.set(enabled);
+
+        // Emit event - Graduation Callback Set
+        evm::log(GraduationCallbackSet {
+            previousEnabled: previous_enabled,
+            enabled,
+        });
+
+        Ok(())
+    }
+
+    // Hard cap on the cumulative base asset a pool may raise
+    pub fn get_raise_cap(&self, pool_id: B256) -> U256 {
+        self.raise_caps.get(pool_id)
+    }
+
+    // Set the hard cap on cumulative base asset raised (owner or param admin). Buys that would take
+    // base asset collected past it revert. Zero disables the cap.
+    pub fn set_raise_cap(&mut self, pool_id: B256, raise_cap: U256) -> Result<(), PumpUpErrors> {
+        self.only_role(PARAM_ADMIN_ROLE)?;
+        self.get_curve_params(pool_id)?;
+
+        let previous_raise_cap = self.raise_caps.get(pool_id);
+        let mut cap_setter = self.raise_caps.setter(pool_id);
+        cap_setter.set(raise_cap);
+
+        // Emit event - Raise Cap Set
+        evm::log(RaiseCapSet {
+            poolId: pool_id,
+            previousRaiseCap: previous_raise_cap,
+            raiseCap: raise_cap,
+        });
+
+        Ok(())
+    }
+
+    // Projected base asset and blocks remaining until a pool reaches its graduation target, from
+    // the net base asset inflow over the trades kept in the trade log. Blocks remaining is the
+    // maximum uint256 when there is no positive inflow to extrapolate from.
+    pub fn estimate_graduation(&self, pool_id: B256) -> Result<(U256, U256), PumpUpErrors> {
+        let (_, _, weth_collected, _, _, _) = self.get_pool_info(pool_id)?;
+
+        let weth_target = self.graduation_weth_targets.get(pool_id);
+        if weth_target.is_zero() {
+            return Err(NoGraduationTarget {}.into());
+        }
+
+        let weth_remaining = weth_target.saturating_sub(weth_collected);
+        if weth_remaining.is_zero() {
+            return Ok((U256::ZERO, U256::ZERO));
+        }
+
+        let trades = self.trades(
+            pool_id,
+            U256::ZERO,
+            U256::from(self.trade_log_capacity.get()),
+        );
+        let oldest_block = match trades.last() {
+            Some(&(_, _, _, _, _, block_number)) => block_number,
+            None => return Ok((weth_remaining, U256::MAX)),
+        };
+
+        let mut weth_in = U256::ZERO;
+        let mut weth_out = U256::ZERO;
+        for (_, direction, _, weth_amount, _, _) in trades {
+            if direction == TRADE_DIRECTION_BUY {
+                weth_in = weth_in.saturating_add(weth_amount);
+            } else {
+                weth_out = weth_out.saturating_add(weth_amount);
+            }
+        }
+
+        let net_inflow = weth_in.saturating_sub(weth_out);
+        if net_inflow.is_zero() {
+            return Ok((weth_remaining, U256::MAX));
+        }
+
+        let elapsed_blocks = U256::from(block::number().saturating_sub(oldest_block).max(1));
+        let blocks_remaining = weth_remaining
+            .saturating_mul(elapsed_blocks)
+            .div_ceil(net_inflow);
+
+        Ok((weth_remaining, blocks_remaining))
+    }
+
+    // Timestamp after which a pool that has not graduated winds down (0 if none)
+    pub fn get_pool_expiry(&self, pool_id: B256) -> u64 {
+        self.pool_expiries.get(pool_id).to::<u64>()
+    }
+
+    // Let a pool expire `duration` seconds from now unless it graduates first, zero to remove
+    // the expiry (owner or param admin). Expired pools wind down: buys are disabled and holders are
+    // refunded pro rata.
+    pub fn set_pool_expiry(&mut self, pool_id: B256, duration: u64) -> Result<(), PumpUpErrors> {
+        self.only_role(PARAM_ADMIN_ROLE)?;
+        self.get_curve_params(pool_id)?;
+
+        let previous_expiry = self.get_pool_expiry(pool_id);
+        let expiry = if duration == 0 {
+            0
+        } else {
+            block::timestamp().saturating_add(duration)
+        };
+        self.pool_expiries.setter(pool_id).set(U64::from(expiry));
+
+        // Emit event - Pool Expiry Set
+        evm::log(PoolExpirySet {
+            poolId: pool_id,
+            previousExpiry: previous_expiry,
+            expiry,
+        });
+
+        Ok(())
+    }
+
+    // Whether a pool expired without graduating and is winding down
+    pub fn is_winding_down(&self, pool_id: B256) -> Result<bool, PumpUpErrors> {
+        let (_, _, weth_collected, _, is_transitioned, _) = self.get_pool_info(pool_id)?;
+
+        Ok(!is_transitioned && self.winding_down(pool_id, weth_collected))
+    }
+
+    // Base asset the manager refunds for `token_amount` of a winding-down pool: its pro-rata share
+    // of the base asset collected
+    pub fn calculate_refund(
+        &self,
+        pool_id: B256,
+        token_amount: U256,
+    ) -> Result<U256, PumpUpErrors> {
+        self.check_not_paused(pool_id)?;
+
+        let (token_address, _, weth_collected, _, is_transitioned, _) =
+            self.get_pool_info(pool_id)?;
+
+        if is_transitioned || !self.winding_down(pool_id, weth_collected) {
+            return Err(PoolNotWindingDown {}.into());
+        }
+
+        let params = self.get_curve_params(pool_id)?;
+        let circulating_supply = self.circulating_supply(pool_id, &token_address, &params)?;
+        let token_amount = self.token_to_wad(pool_id, token_amount);
+
+        if token_amount.is_zero() || token_amount > circulating_supply {
+            return Err(InvalidAmount {}.into());
+        }
+
+        Ok(weth_collected * token_amount / circulating_supply)
+    }
+
+    // Clear the pending flag once the manager completed the transition (only the pool's manager)
+    pub fn finalize_transition(&mut self, pool_id: B256) -> Result<(), PumpUpErrors> {
+        self.clear_transition_pending(pool_id)?;
+
+        let total_pools_graduated = self.total_pools_graduated.get();
+        self.total_pools_graduated
+            .set(total_pools_graduated + U256::from(1u64));
+
+        // Emit event - Transition Finalized
+        evm::log(TransitionFinalized { poolId: pool_id });
+
+        Ok(())
+    }
+
+    // Clear the pending flag without transitioning, re-enabling buys (only the pool's manager)
+    pub fn abort_transition(&mut self, pool_id: B256) -> Result<(), PumpUpErrors> {
+        self.clear_transition_pending(pool_id)?;
+
+        // Emit event - Transition Aborted
+        evm::log(TransitionAborted { poolId: pool_id });
+
+        Ok(())
+    }
+
+    // Early graduation settings as (minimum base asset raised, timelock in seconds)
+    pub fn get_early_graduation_config(&self) -> (U256, u64) {
+        (
+            self.early_graduation_min_weth.get(),
+            self.early_graduation_timelock.get().to::<u64>(),
+        )
+    }
+
+    // Configure the minimum base asset raised and the timelock for creator-initiated early
+    // graduation (owner or param admin)
+    pub fn set_early_graduation_config(
+        &mut self,
+        min_weth: U256,
+        timelock: u64,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_role(PARAM_ADMIN_ROLE)?;
+
+        let (previous_min_weth, previous_timelock) = self.get_early_graduation_config();
+        self.early_graduation_min_weth.set(min_weth);
+        self.early_graduation_timelock.set(U64::from(timelock));
+
+        // Emit event - Early Graduation Config Set
+        evm::log(EarlyGraduationConfigSet {
+            previousMinBase: previous_min_weth,
+            previousTimelock: previous_timelock,
+            minBase: min_weth,
+            timelock,
+        });
+
+        Ok(())
+    }
+
+    // Timestamp from which a pool's early graduation request can be executed (0 if none)
+    pub fn early_graduation_request(&self, pool_id: B256) -> u64 {
+        self.early_graduation_requests.get(pool_id).to::<u64>()
+    }
+
+    // Request graduation ahead of the automatic threshold (only the pool creator).
+    // Returns the timestamp from which it can be executed.
+    pub fn request_early_graduation(&mut self, pool_id: B256) -> Result<u64, PumpUpErrors> {
+        self.only_creator(pool_id)?;
+        self.check_early_graduation_allowed(pool_id)?;
+
+        let (_, timelock) = self.get_early_graduation_config();
+        let executable_at = block::timestamp().saturating_add(timelock);
+        self.early_graduation_requests
+            .setter(pool_id)
+            .set(U64::from(executable_at));
+
+        // Emit event - Early Graduation Requested
+        evm::log(EarlyGraduationRequested {
+            poolId: pool_id,
+            executableAt: executable_at,
+        });
+
+        Ok(executable_at)
+    }
+
+    // Execute a matured early graduation request, graduating the pool like the automatic
+    // threshold does (only the pool creator)
+    pub fn execute_early_graduation(&mut self, pool_id: B256) -> Result<(), PumpUpErrors> {
+        self.only_creator(pool_id)?;
+
+        let executable_at = self.early_graduation_request(pool_id);
+        if executable_at == 0 {
+            return Err(NoEarlyGraduationRequest {}.into());
+        }
+        if block::timestamp() < executable_at {
+            return Err(EarlyGraduationTimelocked {}.into());
+        }
+
+        let weth_collected = self.check_early_graduation_allowed(pool_id)?;
+        self.early_graduation_requests
+            .setter(pool_id)
+            .set(U64::ZERO);
+
+        let final_price = self.get_current_price(pool_id)?;
+        self.graduate(pool_id, weth_collected, final_price)
+    }
+
+    // Quote commitment settings as (validity in blocks, tolerance in bps)
+    pub fn get_quote_commitment_config(&self) -> (u64, U256) {
+        (self.quote_validity_blocks(), self.quote_tolerance_bps())
+    }
+
+    // Configure how long quote commitments stay valid and how far execution may deviate from them
+    // (owner or param admin). Zero values restore the defaults.
+    pub fn set_quote_commitment_config(
+        &mut self,
+        validity_blocks: u64,
+        tolerance_bps: U256,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_role(PARAM_ADMIN_ROLE)?;
+
+        if tolerance_bps > BPS_DENOMINATOR {
+            return Err(InvalidTolerance {}.into());
+        }
+
+        let (previous_validity_blocks, previous_tolerance_bps) = self.get_quote_commitment_config();
+        self.quote_validity_blocks.set(U64::from(validity_blocks));
+        self.quote_tolerance_bps.set(tolerance_bps);
+        let (validity_blocks, tolerance_bps) = self.get_quote_commitment_config();
+
+        // Emit event - Quote Commitment Config Set
+        evm::log(QuoteCommitmentConfigSet {
+            previousValidityBlocks: previous_validity_blocks,
+            previousToleranceBps: previous_tolerance_bps,
+            validityBlocks: validity_blocks,
+            toleranceBps: tolerance_bps,
+        });
+
+        Ok(())
+    }
+
+    // Largest spot price move in bps a curve parameter update may cause (0 means unbounded)
+    pub fn get_curve_update_bound(&self) -> U256 {
+        self.curve_update_max_price_change_bps.get()
+    }
+
+    // Bound the spot price move of curve parameter updates, 0 to disable (owner or param admin)
+    pub fn set_curve_update_bound(
+        &mut self,
+        max_price_change_bps: U256,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_role(PARAM_ADMIN_ROLE)?;
+
+        let previous_max_price_change_bps = self.curve_update_max_price_change_bps.get();
+        self.curve_update_max_price_change_bps
+            .set(max_price_change_bps);
+
+        // Emit event - Curve Update Bound Set
+        evm::log(CurveUpdateBoundSet {
+            previousMaxPriceChangeBps: previous_max_price_change_bps,
+            maxPriceChangeBps: max_price_change_bps,
+        });
+
+        Ok(())
+    }
+
+    // Defaults substituted for zero parameters at initialization as
+    // (max price factor, steepness, midpoint)
+    pub fn get_default_curve_params(&self) -> (U256, U256, U256) {
+        let max_price_factor = self.default_max_price_factor.get();
+        let steepness = self.default_steepness.get();
+        let midpoint = self.default_midpoint.get();
+
+        (
+            if max_price_factor.is_zero() {
+                DEFAULT_MAX_PRICE_FACTOR
+            } else {
+                max_price_factor
+            },
+            if steepness.is_zero() {
+                DEFAULT_STEEPNESS
+            } else {
+                steepness
+            },
+            if midpoint.is_zero() {
+                DEFAULT_MIDPOINT
+            } else {
+                midpoint
+            },
+        )
+    }
+
+    // Set the defaults substituted for zero parameters at initialization (owner or param admin).
+    // Requires 1.0 <= max price factor <= 1000.0, 0 < steepness <= 50.0 and 0 < midpoint < 1.0.
+    pub fn set_default_curve_params(
+        &mut self,
+        max_price_factor: U256,
+        steepness: U256,
+        midpoint: U256,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_role(PARAM_ADMIN_ROLE)?;
+
+        if max_price_factor < MIN_DEFAULT_MAX_PRICE_FACTOR
+            || max_price_factor > MAX_DEFAULT_MAX_PRICE_FACTOR
+            || steepness.is_zero()
+            || steepness > MAX_DEFAULT_STEEPNESS
+            || midpoint.is_zero()
+            || midpoint >= SCALE_FACTOR
+        {
+            return Err(InvalidDefaultParameters {}.into());
+        }
+
+        let (previous_max_price_factor, previous_steepness, previous_midpoint) =
+            self.get_default_curve_params();
+        self.default_max_price_factor.set(max_price_factor);
+        self.default_steepness.set(steepness);
+        self.default_midpoint.set(midpoint);
+
+        // Emit event - Default Curve Params Set
+        evm::log(DefaultCurveParamsSet {
+            previousMaxPriceFactor: previous_max_price_factor,
+            previousSteepness: previous_steepness,
+            previousMidpoint: previous_midpoint,
+            maxPriceFactor: max_price_factor,
+            steepness,
+            midpoint,
+        });
+
+        Ok(())
+    }
+
+    // Pricing model of a pool, one of `registered_strategies`
+    pub fn get_curve_kind(&self, pool_id: B256) -> Result<u8, PumpUpErrors> {
+        Ok(self.get_curve_params(pool_id)?.curve_kind)
+    }
+
+    // Switch a pool's pricing model before any tokens are sold (owner or param admin). Virtual
+    // reserves and linear curves are sized from the pool's initial price, max price factor and
+    // total supply; exponential curves grow at the pool's steepness. Piecewise curves are
+    // selected through set_curve_segments.
+    pub fn set_curve_kind(&mut self, pool_id: B256, curve_kind: u8) -> Result<(), PumpUpErrors> {
+        self.only_role(PARAM_ADMIN_ROLE)?;
+
+        if !is_selectable_curve_kind(curve_kind) {
+            return Err(InvalidCurveKind {}.into());
+        }
+
+        let mut params = self.get_curve_params(pool_id)?;
+        self.check_curve_kind_unlocked(pool_id, &params)?;
+
+        let previous_curve_kind = params.curve_kind;
+        params.curve_kind = curve_kind;
+        self.store_curve_params(pool_id, &params);
+
+        // Emit event - Curve Kind Set
+        evm::log(CurveKindSet {
+            poolId: pool_id,
+            previousCurveKind: previous_curve_kind,
+            curveKind: curve_kind,
+        });
+
+        Ok(())
+    }
+
+    // Segments of a piecewise pool's curve as an ABI-encoded CurveSegmentParams[], empty for
+    // other pricing models
+    pub fn get_curve_segments(&self, pool_id: B256) -> Result<Bytes, PumpUpErrors> {
+        let params = self.get_curve_params(pool_id)?;
+        let segments: Vec<CurveSegmentParams> = params.segments[..params.segment_count]
+            .iter()
+            .map(|segment| CurveSegmentParams {
+                shape: segment.shape,
+                endSupply: segment.end_supply,
+                endPrice: segment.end_price,
+                steepness: segment.steepness,
+                midpoint: segment.midpoint,
+            })
+            .collect();
+
+        Ok(Bytes(<Array<CurveSegmentParams> as SolType>::abi_encode(
+            &segments,
+        )))
+    }
+
+    // Switch a pool to a piecewise curve of abi.encode'd CurveSegmentParams[] before any tokens
+    // are sold (owner or param admin). Segments start where the previous one ends, the first at
+    // the initial price, and the last must end at the total supply with prices never falling.
+    pub fn set_curve_segments(
+        &mut self,
+        pool_id: B256,
+        segments: Bytes,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_role(PARAM_ADMIN_ROLE)?;
+
+        let requested = <Array<CurveSegmentParams> as SolType>::abi_decode(&segments.0, true)
+            .map_err(|_| -> PumpUpErrors { InvalidCurveSegments {}.into() })?;
+        if requested.is_empty() || requested.len() > MAX_CURVE_SEGMENTS {
+            return Err(InvalidCurveSegments {}.into());
+        }
+
+        let mut params = self.get_curve_params(pool_id)?;
+        self.check_curve_kind_unlocked(pool_id, &params)?;
+
+        let (previous_curve_kind, previous_segment_count) =
+            (params.curve_kind, params.segment_count);
+        params.curve_kind = CURVE_KIND_PIECEWISE;
+        params.segment_count = requested.len();
+        for (segment, requested) in params.segments.iter_mut().zip(requested.iter()) {
+            *segment = CurveSegment {
+                shape: requested.shape,
+                end_supply: requested.endSupply,
+                end_price: requested.endPrice,
+                steepness: requested.steepness,
+                midpoint: requested.midpoint,
+            };
+        }
+        piecewise::check_segments(&params)?;
+
+        self.store_curve_params(pool_id, &params);
+
+        // Emit event - Curve Segments Set
+        evm::log(CurveSegmentsSet {
+            poolId: pool_id,
+            previousSegmentCount: U256::from(previous_segment_count),
+            segmentCount: U256::from(params.segment_count),
+        });
+
+        // Emit event - Curve Kind Set
+        if previous_curve_kind != CURVE_KIND_PIECEWISE {
+            evm::log(CurveKindSet {
+                poolId: pool_id,
+                previousCurveKind: previous_curve_kind,
+                curveKind: CURVE_KIND_PIECEWISE,
+            });
+        }
+
+        Ok(())
+    }
+
+    // Pricing model assigned to newly initialized pools
+    pub fn get_default_curve_kind(&self) -> u8 {
+        self.default_curve_kind.get().to::<u8>()
+    }
+
+    // Set the pricing model assigned to newly initialized pools (owner or param admin)
+    pub fn set_default_curve_kind(&mut self, curve_kind: u8) -> Result<(), PumpUpErrors> {
+        self.only_role(PARAM_ADMIN_ROLE)?;
+
+        if !is_selectable_curve_kind(curve_kind) {
+            return Err(InvalidCurveKind {}.into());
+        }
+
+        let previous_curve_kind = self.get_default_curve_kind();
+        self.default_curve_kind.set(U8::from(curve_kind));
+
+        // Emit event - Default Curve Kind Set
+        evm::log(DefaultCurveKindSet {
+            previousCurveKind: previous_curve_kind,
+            curveKind: curve_kind,
+        });
+
+        Ok(())
+    }
+
+    // Effective (min, max) bounds of a curve parameter: 0 initial price, 1 max price factor,
+    // 2 steepness, 3 midpoint, 4 total supply
+    pub fn get_param_bounds(&self, parameter: u8) -> Result<(U256, U256), PumpUpErrors> {
+        self.param_bounds_of(parameter)
+    }
+
+    // Set the bounds pools' curve parameters must fall within at initialization and on update,
+    // zero to use the compile-time bound (owner or param admin)
+    pub fn set_param_bounds(
+        &mut self,
+        parameter: u8,
+        min: U256,
+        max: U256,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_role(PARAM_ADMIN_ROLE)?;
+
+        if parameter > CURVE_PARAM_TOTAL_SUPPLY || (!max.is_zero() && min > max) {
+            return Err(InvalidParamBounds {}.into());
+        }
+
+        let mut bounds = self.param_bounds.setter(U8::from(parameter));
+        let (previous_min, previous_max) = (bounds.min.get(), bounds.max.get());
+        bounds.min.set(min);
+        bounds.max.set(max);
+
+        // Emit event - Param Bounds Set
+        evm::log(ParamBoundsSet {
+            parameter,
+            previousMin: previous_min,
+            previousMax: previous_max,
+            min,
+            max,
+        });
+
+        Ok(())
+    }
+
+    // Whether a pool has been initialized with this strategy
+    pub fn pool_exists(&self, pool_id: B256) -> bool {
+        !self.curve_params.get(pool_id).initial_price.get().is_zero()
+    }
+
+    // Whether initialize has stored curve parameters for a pool (same as pool_exists)
+    pub fn is_initialized(&self, pool_id: B256) -> bool {
+        self.pool_exists(pool_id)
+    }
+
+    // Lifecycle state of a pool: 0 uninitialized, 1 active, 2 paused, 3 transition pending,
+    // 4 transitioned, 5 cleaned, 6 winding down
+    pub fn pool_status(&self, pool_id: B256) -> Result<u8, PumpUpErrors> {
+        if !self.pool_exists(pool_id) {
+            return Ok(POOL_STATUS_UNINITIALIZED);
+        }
+
+        if self.transition_pending.get(pool_id) {
+            return Ok(POOL_STATUS_TRANSITION_PENDING);
+        }
+
+        let (
+            _token_address,
+            _creator,
+            weth_collected,
+            _last_price,
+            is_transitioned,
+            _bonding_curve_strategy,
+        ) = self.get_pool_info(pool_id)?;
+
+        if is_transitioned {
+            return Ok(POOL_STATUS_TRANSITIONED);
+        }
+
+        if self.is_paused(pool_id) {
+            return Ok(POOL_STATUS_PAUSED);
+        }
+
+        if self.winding_down(pool_id, weth_collected) {
+            return Ok(POOL_STATUS_WINDING_DOWN);
+        }
+
+        Ok(POOL_STATUS_ACTIVE)
+    }
+
+    // Number of pools ever initialized with this strategy
+    pub fn pool_count(&self) -> U256 {
+        U256::from(self.pool_ids.len())
+    }
+
+    // Page of initialized pool ids, in initialization order
+    pub fn pools(&self, offset: U256, limit: U256) -> Vec<B256> {
+        let count = self.pool_ids.len();
+        let start = offset.min(U256::from(count)).to::<usize>();
+        let end = start
+            .saturating_add(limit.min(U256::from(count)).to::<usize>())
+            .min(count);
+
+        (start..end)
+            .map(|i| self.pool_ids.get(i).unwrap_or_default())
+            .collect()
+    }
+
+    // ABI-encoded PoolSnapshot[] of the pools in initialization order, paged like `pools`
+    pub fn export_snapshot(&self, offset: U256, limit: U256) -> Bytes {
+        let snapshots: Vec<PoolSnapshot> = self
+            .pools(offset, limit)
+            .into_iter()
+            .map(|pool_id| {
+                let params = self.get_curve_params(pool_id).unwrap_or_default();
+                PoolSnapshot {
+                    poolId: pool_id,
+                    manager: self.pool_manager(pool_id),
+                    initialPrice: params.initial_price,
+                    maxPriceFactor: params.max_price_factor,
+                    steepness: params.steepness,
+                    midpoint: params.midpoint,
+                    totalSupply: params.total_supply,
+                    sellPriceFloor: self.sell_price_floors.get(pool_id),
+                    insuranceReserve: self.insurance_reserves.get(pool_id),
+                    graduationBaseTarget: self.graduation_weth_targets.get(pool_id),
+                    raiseCap: self.raise_caps.get(pool_id),
+                    transitionPending: self.transition_pending.get(pool_id),
+                    minBuyBase: self.min_buy_weth.get(pool_id),
+                    minSellTokens: self.min_sell_tokens.get(pool_id),
+                    priceTick: self.price_ticks.get(pool_id),
+                    tradeLogCount: self.trade_log_counts.get(pool_id),
+                    washVolume: self.wash_volumes.get(pool_id),
+                }
+            })
+            .collect();
+
+        Bytes(<Array<PoolSnapshot> as SolType>::abi_encode(&snapshots))
+    }
+
+    // Curve parameters of a pool as (initial price, max price factor, steepness, midpoint,
+    // total supply)
+    pub fn get_curve_parameters(
+        &self,
+        pool_id: B256,
+    ) -> Result<(U256, U256, U256, U256, U256), PumpUpErrors> {
+        let params = self.get_curve_params(pool_id)?;
+        Ok((
+            params.initial_price,
+            params.max_price_factor,
+            params.steepness,
+            params.midpoint,
+            params.total_supply,
+        ))
+    }
+
+    // Curve parameters of many pools as (initial price, max price factor, steepness, midpoint,
+    // total supply). Unknown pools yield all zeros.
+    pub fn get_curve_params_many(
+        &self,
+        pool_ids: Vec<B256>,
+    ) -> Vec<(U256, U256, U256, U256, U256)> {
+        pool_ids
+            .into_iter()
+            .map(|pool_id| {
+                let params = self.get_curve_params(pool_id).unwrap_or_default();
+                (
+                    params.initial_price,
+                    params.max_price_factor,
+                    params.steepness,
+                    params.midpoint,
+                    params.total_supply,
+                )
+            })
+            .collect()
+    }
+
+    // One-call deployment smoke test returning a bitmask of failures (zero when healthy):
+    // 1 manager unreachable, 2 quote token unset, 4 quote token not 18 decimals, 8 math sanity
+    pub fn health_check(&self) -> u32 {
+        let mut failures = 0u32;
+
+        // The manager must be a contract and answer getPoolInfo for a known pool
+        let manager = *self.pool_state_manager;
+        let manager_reachable = manager.has_code()
+            && (self.pool_ids.is_empty()
+                || self
+                    .get_pool_info(self.pool_ids.get(0).unwrap_or_default())
+                    .is_ok());
+        if !manager_reachable {
+            failures |= HEALTH_MANAGER_UNREACHABLE;
+        }
+
+        let quote_token = *self.quote_token;
+        if quote_token == Address::ZERO {
+            failures |= HEALTH_QUOTE_TOKEN_UNSET;
+        } else if host().decimals(quote_token).ok() != Some(QUOTE_TOKEN_DECIMALS) {
+            failures |= HEALTH_QUOTE_TOKEN_DECIMALS;
+        }
+
+        if !self.math_sanity_holds() {
+            failures |= HEALTH_MATH_SANITY;
+        }
+
+        failures
+    }
+
+    // Launch phase boundaries of a pool as
+    // (start block, allowlist end block, capped end block, max buy per trade in capped phases)
+    pub fn get_launch_phases(&self, pool_id: B256) -> (u64, u64, u64, U256) {
+        let schedule = self.launch_schedules.get(pool_id);
+        (
+            schedule.start_block.get().to::<u64>(),
+            schedule.allowlist_end_block.get().to::<u64>(),
+            schedule.capped_end_block.get().to::<u64>(),
+            schedule.max_buy_weth.get(),
+        )
+    }
+
+    // Current launch phase of a pool: 0 open, 1 pre-launch, 2 allowlist, 3 capped
+    pub fn launch_phase(&self, pool_id: B256) -> u8 {
+        let (start_block, allowlist_end_block, capped_end_block, _max_buy_weth) =
+            self.get_launch_phases(pool_id);
+        let current_block = block::number();
+
+        if current_block < start_block {
+            LAUNCH_PHASE_PRE_LAUNCH
+        } else if current_block < allowlist_end_block {
+            LAUNCH_PHASE_ALLOWLIST
+        } else if current_block < capped_end_block {
+            LAUNCH_PHASE_CAPPED
+        } else {
+            LAUNCH_PHASE_OPEN
+        }
+    }
+
+    // Whether an account may buy during a pool's allowlist phase
+    pub fn is_launch_allowlisted(&self, pool_id: B256, account: Address) -> bool {
+        self.launch_allowlists.get(pool_id).get(account)
+    }
+
+    // Define a pool's launch phases in block numbers (owner or param admin). Boundaries must be
+    // non-decreasing; equal boundaries skip a phase and all zeros leaves the pool open.
+    pub fn set_launch_phases(
+        &mut self,
+        pool_id: B256,
+        start_block: u64,
+        allowlist_end_block: u64,
+        capped_end_block: u64,
+        max_buy_weth: U256,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_role(PARAM_ADMIN_ROLE)?;
+        self.get_curve_params(pool_id)?;
+
+        if start_block > allowlist_end_block || allowlist_end_block > capped_end_block {
+            return Err(InvalidLaunchPhases {}.into());
+        }
+
+        let (
+            previous_start_block,
+            previous_allowlist_end_block,
+            previous_capped_end_block,
+            previous_max_buy_weth,
+        ) = self.get_launch_phases(pool_id);
+
+        let mut schedule = self.launch_schedules.setter(pool_id);
+        schedule.start_block.set(U64::from(start_block));
+        schedule
+            .allowlist_end_block
+            .set(U64::from(allowlist_end_block));
+        schedule.capped_end_block.set(U64::from(capped_end_block));
+        schedule.max_buy_weth.set(max_buy_weth);
+
+        // Emit event - Launch Phases Set
+        evm::log(LaunchPhasesSet {
+            poolId: pool_id,
+            previousStartBlock: previous_start_block,
+            previousAllowlistEndBlock: previous_allowlist_end_block,
+            previousCappedEndBlock: previous_capped_end_block,
+            previousMaxBuyBase: previous_max_buy_weth,
+            startBlock: start_block,
+            allowlistEndBlock: allowlist_end_block,
+            cappedEndBlock: capped_end_block,
+            maxBuyBase: max_buy_weth,
+        });
+
+        Ok(())
+    }
+
+    // Add or remove accounts from a pool's launch allowlist (owner or param admin)
+    pub fn set_launch_allowlist(
+        &mut self,
+        pool_id: B256,
+        accounts: Vec<Address>,
+        allowed: bool,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_role(PARAM_ADMIN_ROLE)?;
+        self.get_curve_params(pool_id)?;
+
+        for account in accounts {
+            let mut allowlist = self.launch_allowlists.setter(pool_id);
+            let mut allowed_setter = allowlist.setter(account);
+            allowed_setter.set(allowed);
+
+            // Emit event - Launch Allowlist Updated
+            evm::log(LaunchAllowlistUpdated {
+                poolId: pool_id,
+                account,
+                allowed,
+            });
+        }
+
+        Ok(())
+    }
+
+    // Whether an account is exempt from a pool's launch allowlist and buy caps
+    pub fn is_market_maker_exempt(&self, pool_id: B256, account: Address) -> bool {
+        self.market_maker_exemptions.get(pool_id).get(account)
+    }
+
+    // Exempt or un-exempt a market maker from a pool's launch allowlist and buy caps
+    // (only the pool creator or owner)
+    pub fn set_market_maker_exemption(
+        &mut self,
+        pool_id: B256,
+        account: Address,
+        exempt: bool,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_creator_or_owner(pool_id)?;
+
+        let previous_exempt = self.is_market_maker_exempt(pool_id, account);
+        let mut exemptions = self.market_maker_exemptions.setter(pool_id);
+        let mut exempt_setter = exemptions.setter(account);
+        exempt_setter.set(exempt);
+
+        // Emit event - Market Maker Exemption Set
+        evm::log(MarketMakerExemptionSet {
+            poolId: pool_id,
+            account,
+            previousExempt: previous_exempt,
+            exempt,
+        });
+
+        Ok(())
+    }
+
+    // Minimum trade size of a pool as (base asset in per buy, tokens in per sell)
+    pub fn get_min_trade_size(&self, pool_id: B256) -> (U256, U256) {
+        (
+            self.min_buy_weth.get(pool_id),
+            self.min_sell_tokens.get(pool_id),
+        )
+    }
+
+    // Set the minimum base asset in per buy and tokens in per sell, zero disables either
+    // (only the pool creator or owner)
+    pub fn set_min_trade_size(
+        &mut self,
+        pool_id: B256,
+        min_buy_weth: U256,
+        min_sell_tokens: U256,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_creator_or_owner(pool_id)?;
+
+        let (previous_min_buy_weth, previous_min_sell_tokens) = self.get_min_trade_size(pool_id);
+        self.min_buy_weth.setter(pool_id).set(min_buy_weth);
+        self.min_sell_tokens.setter(pool_id).set(min_sell_tokens);
+
+        // Emit event - Min Trade Size Set
+        evm::log(MinTradeSizeSet {
+            poolId: pool_id,
+            previousMinBuyBase: previous_min_buy_weth,
+            previousMinSellTokens: previous_min_sell_tokens,
+            minBuyBase: min_buy_weth,
+            minSellTokens: min_sell_tokens,
+        });
+
+        Ok(())
+    }
+
+    // Tick size a pool's quoted prices are rounded to
+    pub fn get_price_tick(&self, pool_id: B256) -> U256 {
+        self.price_ticks.get(pool_id)
+    }
+
+    // Set the tick size quoted buy and sell prices are rounded to, zero disables rounding
+    // (only the pool creator or owner)
+    pub fn set_price_tick(&mut self, pool_id: B256, tick: U256) -> Result<(), PumpUpErrors> {
+        self.only_creator_or_owner(pool_id)?;
+
+        let previous_tick = self.price_ticks.get(pool_id);
+        self.price_ticks.setter(pool_id).set(tick);
+
+        // Emit event - Price Tick Set
+        evm::log(PriceTickSet {
+            poolId: pool_id,
+            previousTick: previous_tick,
+            tick,
+        });
+
+        Ok(())
+    }
+
+    // Compliance module consulted before a pool's trades
+    pub fn get_compliance_module(&self, pool_id: B256) -> Address {
+        self.compliance_modules.get(pool_id)
+    }
+
+    // Set the module asked `isAllowed(pool, trader, direction, amount)` before each trade, zero
+    // to remove it (only the pool creator or owner)
+    pub fn set_compliance_module(
+        &mut self,
+        pool_id: B256,
+        module: Address,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_creator_or_owner(pool_id)?;
+
+        let previous_module = self.compliance_modules.get(pool_id);
+        self.compliance_modules.setter(pool_id).set(module);
+
+        // Emit event - Compliance Module Set
+        evm::log(ComplianceModuleSet {
+            poolId: pool_id,
+            previousModule: previous_module,
+            module,
+        });
+
+        Ok(())
+    }
+
+    // Buy cashback of a pool as (cashback in bps, incentive reserve left, total cashback paid)
+    pub fn get_cashback(&self, pool_id: B256) -> (U256, U256, U256) {
+        (
+            self.cashback_bps.get(pool_id),
+            self.incentive_reserves.get(pool_id),
+            self.cashback_paid.get(pool_id),
+        )
+    }
+
+    // Set the share of each buy, in bps, returned to the buyer as extra tokens from the incentive
+    // reserve. Zero disables cashback (only the pool creator or owner).
+    pub fn set_cashback_bps(
+        &mut self,
+        pool_id: B256,
+        cashback_bps: U256,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_creator_or_owner(pool_id)?;
+
+        if cashback_bps > BPS_DENOMINATOR {
+            return Err(InvalidCashback {}.into());
+        }
+
+        let previous_cashback_bps = self.cashback_bps.get(pool_id);
+        self.cashback_bps.setter(pool_id).set(cashback_bps);
+
+        // Emit event - Cashback Set
+        evm::log(CashbackSet {
+            poolId: pool_id,
+            previousCashbackBps: previous_cashback_bps,
+            cashbackBps: cashback_bps,
+        });
+
+        Ok(())
+    }
+
+    // Deposit pool tokens with the manager for buy cashback, pulled from the caller, who must have
+    // approved this contract (only the pool creator or owner)
+    pub fn fund_incentive_reserve(
+        &mut self,
+        pool_id: B256,
+        amount: U256,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_creator_or_owner(pool_id)?;
+
+        if amount.is_zero() {
+            return Err(InvalidAmount {}.into());
+        }
+
+        let (token_address, _, _, _, _, _) = self.get_pool_info(pool_id)?;
+        self.safe_transfer_from(
+            &token_address,
+            msg::sender(),
+            self.pool_manager(pool_id),
+            amount,
+        )?;
+
+        let new_reserve = self.incentive_reserves.get(pool_id).saturating_add(amount);
+        self.incentive_reserves.setter(pool_id).set(new_reserve);
+
+        // Emit event - Incentive Reserve Funded
+        evm::log(IncentiveReserveFunded {
+            poolId: pool_id,
+            amount,
+            newReserve: new_reserve,
+        });
+
+        Ok(())
+    }
+
+    // Manager a pool is currently bound to
+    pub fn get_pool_manager(&self, pool_id: B256) -> Address {
+        self.pool_manager(pool_id)
+    }
+
+    // Migrate pools from one manager to another (only owner).
+    // An empty `pool_ids` migrates every pool bound to `from` and, if `from` is the
+    // global manager, re-points the global manager too. Returns the number of pools migrated.
+    pub fn migrate_pool_manager(
+        &mut self,
+        from: Address,
+        to: Address,
+        pool_ids: Vec<B256>,
+    ) -> Result<U256, PumpUpErrors> {
+        self.only_owner()?;
+
+        if to == Address::ZERO {
+            return Err(ZeroAddressManager {}.into());
+        }
+
+        let mut migrated = 0u64;
+
+        if pool_ids.is_empty() {
+            for i in 0..self.pool_ids.len() {
+                let pool_id = self.pool_ids.get(i).unwrap_or_default();
+                if self.pool_manager(pool_id) == from {
+                    self.rebind_pool_manager(pool_id, from, to)?;
+                    migrated += 1;
+                }
+            }
+
+            if *self.pool_state_manager == from {
+                self.update_pool_state_manager(to)?;
+            }
+        } else {
+            for pool_id in pool_ids {
+                if !self.pool_exists(pool_id) {
+                    return Err(InvalidPoolId {}.into());
+                }
+                if self.pool_manager(pool_id) != from {
+                    return Err(PoolNotBoundToManager {}.into());
+                }
+                self.rebind_pool_manager(pool_id, from, to)?;
+                migrated += 1;
+            }
+        }
+
+        Ok(U256::from(migrated))
+    }
+
+    // Protocol fee discount (in bps) granted to an integrator
+    pub fn get_integrator_discount(&self, integrator: Address) -> U256 {
+        self.integrator_discounts.get(integrator)
+    }
+
+    // Register, update or remove (discount of zero) a partner integrator (owner or param admin)
+    pub fn set_integrator_discount(
+        &mut self,
+        integrator: Address,
+        discount_bps: U256,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_role(PARAM_ADMIN_ROLE)?;
+
+        if discount_bps > BPS_DENOMINATOR {
+            return Err(InvalidDiscount {}.into());
+        }
+
+        let previous_discount_bps = self.integrator_discounts.get(integrator);
+        let mut discount_setter = self.integrator_discounts.setter(integrator);
+        discount_setter.set(discount_bps);
+
+        // Emit event - Integrator Discount Set
+        evm::log(IntegratorDiscountSet {
+            integrator,
+            previousDiscountBps: previous_discount_bps,
+            discountBps: discount_bps,
+        });
+
+        Ok(())
+    }
+
+    // Quote token (WETH or its equivalent) pools of this deployment are priced in
+    pub fn quote_token(&self) -> Address {
+        *self.quote_token
+    }
+
+    // Set the deployment's quote token (only owner)
+    pub fn set_quote_token(&mut self, new_quote_token: Address) -> Result<(), PumpUpErrors> {
+        self.only_owner()?;
+
+        if new_quote_token == Address::ZERO {
+            return Err(ZeroAddressQuoteToken {}.into());
+        }
+
+        let previous_quote_token = *self.quote_token;
+        self.quote_token.set(new_quote_token);
+
+        // Emit event - Quote Token Set
+        evm::log(QuoteTokenSet {
+            previousQuoteToken: previous_quote_token,
+            newQuoteToken: new_quote_token,
+        });
+
+        Ok(())
+    }
+
+    // Whether the strategy is deprecated (new pools rejected, existing pools unaffected)
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated.get()
+    }
+
+    // Deprecate or un-deprecate the strategy (only owner)
+    pub fn set_deprecated(&mut self, deprecated: bool) -> Result<(), PumpUpErrors> {
+        self.only_owner()?;
+
+        let previous_deprecated = self.deprecated.get();
+        self.deprecated.set(deprecated);
+
+        // Emit event - Deprecation Set
+        evm::log(DeprecationSet {
+            previousDeprecated: previous_deprecated,
+            deprecated,
+        });
+
+        Ok(())
+    }
+
+    // Whether trades emit compact instead of verbose events
+    pub fn compact_events(&self) -> bool {
+        self.compact_events.get()
+    }
+
+    // Switch between compact and verbose (default) trade events (owner or param admin)
+    pub fn set_compact_events(&mut self, compact: bool) -> Result<(), PumpUpErrors> {
+        self.only_role(PARAM_ADMIN_ROLE)?;
+
+        let previous_compact = self.compact_events.get();
+        self.compact_events.set(compact);
+
+        // Emit event - Compact Events Set
+        evm::log(CompactEventsSet {
+            previousCompact: previous_compact,
+            compact,
+        });
+
+        Ok(())
+    }
+
+    // Send tokens held by this contract, e.g. sent by mistake, to a recipient (only owner)
+    pub fn rescue_tokens(
+        &mut self,
+        token: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_owner()?;
+
+        if amount.is_zero() {
+            return Err(InvalidAmount {}.into());
+        }
+
+        self.safe_transfer(&token, to, amount)?;
+
+        // Emit event - Tokens Rescued
+        evm::log(TokensRescued { token, to, amount });
+
+        Ok(())
+    }
+
+    // Protocol fee split as (payee, share in bps)
+    pub fn get_fee_split(&self) -> Vec<(Address, U256)> {
+        (0..self.fee_payees.len())
+            .map(|i| {
+                (
+                    self.fee_payees.get(i).unwrap_or_default(),
+                    self.fee_shares.get(i).unwrap_or_default(),
+                )
+            })
+            .collect()
+    }
+
+    // Amount each payee receives out of a protocol fee. The last payee receives the rounding
+    // remainder.
+    pub fn quote_fee_split(&self, fee_amount: U256) -> Vec<(Address, U256)> {
+        let split = self.get_fee_split();
+        let mut remaining = fee_amount;
+
+        split
+            .iter()
+            .enumerate()
+            .map(|(i, &(payee, share))| {
+                let amount = if i + 1 == split.len() {
+                    remaining
+                } else {
+                    fee_amount * share / BPS_DENOMINATOR
+                };
+                remaining -= amount;
+                (payee, amount)
+            })
+            .collect()
+    }
+
+    // Replace the protocol fee split (only owner). Shares are in bps and must sum to 100%;
+    // an empty list clears the split.
+    pub fn set_fee_split(&mut self, split: Vec<(Address, U256)>) -> Result<(), PumpUpErrors> {
+        self.only_owner()?;
+
+        if split.len() > MAX_FEE_PAYEES {
+            return Err(InvalidFeeSplit {}.into());
+        }
+
+        let mut total_shares = U256::ZERO;
+        for &(payee, share) in &split {
+            if payee.is_zero() || share.is_zero() {
+                return Err(InvalidFeeSplit {}.into());
+            }
+            total_shares = total_shares.saturating_add(share);
+        }
+        if !split.is_empty() && total_shares != BPS_DENOMINATOR {
+            return Err(InvalidFeeSplit {}.into());
+        }
+
+        let previous_split = self.get_fee_split();
+        self.fee_payees.erase();
+        self.fee_shares.erase();
+        for &(payee, share) in &split {
+            self.fee_payees.push(payee);
+            self.fee_shares.push(share);
+        }
+
+        // Emit event - Fee Split Set
+        let (previous_payees, previous_shares): (Vec<Address>, Vec<U256>) =
+            previous_split.into_iter().unzip();
+        let (payees, shares): (Vec<Address>, Vec<U256>) = split.into_iter().unzip();
+        evm::log(FeeSplitSet {
+            previousPayees: previous_payees,
+            previousShares: previous_shares,
+            payees,
+            shares,
+        });
+
+        Ok(())
+    }
+
+    // Deployment-wide trade fees as (protocol fee bps, creator fee bps)
+    pub fn get_default_fees(&self) -> (U256, U256) {
+        (self.protocol_fee_bps.get(), self.creator_fee_bps.get())
+    }
+
+    // Set the deployment-wide trade fees (only owner). The combined fee is capped at 10%.
+    pub fn set_default_fees(
+        &mut self,
+        protocol_fee_bps: U256,
+        creator_fee_bps: U256,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_owner()?;
+
+        if protocol_fee_bps.saturating_add(creator_fee_bps) > MAX_TOTAL_FEE_BPS {
+            return Err(InvalidFee {}.into());
+        }
+
+        let (previous_protocol_fee_bps, previous_creator_fee_bps) = self.get_default_fees();
+        self.protocol_fee_bps.set(protocol_fee_bps);
+        self.creator_fee_bps.set(creator_fee_bps);
+
+        // Emit event - Default Fees Set
+        evm::log(DefaultFeesSet {
+            previousProtocolFeeBps: previous_protocol_fee_bps,
+            previousCreatorFeeBps: previous_creator_fee_bps,
+            protocolFeeBps: protocol_fee_bps,
+            creatorFeeBps: creator_fee_bps,
+        });
+
+        Ok(())
+    }
+
+    // Trade fees of a pool as (protocol fee bps, creator fee bps), before integrator discounts
+    pub fn get_fees(&self, pool_id: B256) -> (U256, U256) {
+        let fee_override = self.fee_overrides.get(pool_id);
+        if fee_override.enabled.get() {
+            (
+                fee_override.protocol_fee_bps.get(),
+                fee_override.creator_fee_bps.get(),
+            )
+        } else {
+            self.get_default_fees()
+        }
+    }
+
+    // Override the trade fees of a pool (only owner). The combined fee is capped at 10%.
+    pub fn set_pool_fees(
+        &mut self,
+        pool_id: B256,
+        protocol_fee_bps: U256,
+        creator_fee_bps: U256,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_owner()?;
+        self.get_curve_params(pool_id)?;
+
+        if protocol_fee_bps.saturating_add(creator_fee_bps) > MAX_TOTAL_FEE_BPS {
+            return Err(InvalidFee {}.into());
+        }
+
+        self.set_fee_override(pool_id, true, protocol_fee_bps, creator_fee_bps);
+
+        Ok(())
+    }
+
+    // Return a pool to the deployment-wide trade fees (only owner)
+    pub fn clear_pool_fees(&mut self, pool_id: B256) -> Result<(), PumpUpErrors> {
+        self.only_owner()?;
+
+        self.set_fee_override(pool_id, false, U256::ZERO, U256::ZERO);
+
+        Ok(())
+    }
+
+    // Unclaimed fees as (protocol fees across all pools, creator fees of the pool)
+    pub fn get_accrued_fees(&self, pool_id: B256) -> (U256, U256) {
+        (
+            self.protocol_fees_accrued.get(),
+            self.creator_fees_accrued.get(pool_id),
+        )
+    }
+
+    // Pay the accrued protocol fees in the quote token to the fee split payees, or to the owner
+    // while no split is set (only owner). The manager forwards fees to this contract; what it has
+    // not forwarded yet stays accrued.
+    pub fn claim_protocol_fees(&mut self) -> Result<U256, PumpUpErrors> {
+        self.only_owner()?;
+
+        let quote_token = self.claim_token()?;
+        let accrued = self.protocol_fees_accrued.get();
+        let amount = self.claimable(quote_token, accrued)?;
+        if amount.is_zero() {
+            return Err(NothingToClaim {}.into());
+        }
+        self.protocol_fees_accrued.set(accrued - amount);
+
+        self.pay_protocol_fees(quote_token, amount)?;
+
+        // Emit event - Protocol Fees Claimed
+        evm::log(ProtocolFeesClaimed { amount });
+
+        Ok(amount)
+    }
+
+    // Unclaimed protocol fees in a base asset, accrued by the pools configured with it
+    pub fn get_base_asset_fees(&self, asset: Address) -> U256 {
+        self.base_asset_fees_accrued.get(asset)
+    }
+
+    // Pay the protocol fees accrued in a base asset like claim_protocol_fees does for the quote
+    // token (only owner)
+    pub fn claim_base_asset_fees(&mut self, asset: Address) -> Result<U256, PumpUpErrors> {
+        self.only_owner()?;
+
+        let accrued = self.base_asset_fees_accrued.get(asset);
+        let amount = self.claimable(asset, accrued)?;
+        if amount.is_zero() {
+            return Err(NothingToClaim {}.into());
+        }
+        self.base_asset_fees_accrued
+            .setter(asset)
+            .set(accrued - amount);
+
+        self.pay_protocol_fees(asset, amount)?;
+
+        // Emit event - Base Asset Fees Claimed
+        evm::log(BaseAssetFeesClaimed { asset, amount });
+
+        Ok(amount)
+    }
+
+    // Pay a pool's accrued creator fees in its base asset to its creator (only creator)
+    pub fn claim_creator_fees(&mut self, pool_id: B256) -> Result<U256, PumpUpErrors> {
+        self.only_creator(pool_id)?;
+
+        let asset = self.fee_asset(pool_id)?;
+        let accrued = self.creator_fees_accrued.get(pool_id);
+        let amount = self.claimable(asset, accrued)?;
+        if amount.is_zero() {
+            return Err(NothingToClaim {}.into());
+        }
+        let mut accrued_setter = self.creator_fees_accrued.setter(pool_id);
+        accrued_setter.set(accrued - amount);
+
+        self.transfer_base_asset(asset, msg::sender(), amount)?;
+
+        // Emit event - Creator Fees Claimed
+        evm::log(CreatorFeesClaimed {
+            poolId: pool_id,
+            creator: msg::sender(),
+            amount,
+        });
+
+        Ok(amount)
+    }
+
+    // Share of the protocol fee in bps accrued to the referrer of a buy
+    pub fn get_referral_share(&self) -> U256 {
+        self.referral_share_bps.get()
+    }
+
+    // Set the share of the protocol fee referrers accrue, at most 100% (only owner)
+    pub fn set_referral_share(&mut self, share_bps: U256) -> Result<(), PumpUpErrors> {
+        self.only_owner()?;
+
+        if share_bps > BPS_DENOMINATOR {
+            return Err(InvalidReferralShare {}.into());
+        }
+
+        let previous_share_bps = self.referral_share_bps.get();
+        self.referral_share_bps.set(share_bps);
+
+        // Emit event - Referral Share Set
+        evm::log(ReferralShareSet {
+            previousShareBps: previous_share_bps,
+            shareBps: share_bps,
+        });
+
+        Ok(())
+    }
+
+    // Unclaimed referral fees of a referrer
+    pub fn get_referral_fees(&self, referrer: Address) -> U256 {
+        self.referral_fees_accrued.get(referrer)
+    }
+
+    // Pay the caller's accrued referral fees in the quote token
+    pub fn claim_referral_fees(&mut self) -> Result<U256, PumpUpErrors> {
+        let referrer = msg::sender();
+        let quote_token = self.claim_token()?;
+        let accrued = self.referral_fees_accrued.get(referrer);
+        let amount = self.claimable(quote_token, accrued)?;
+        if amount.is_zero() {
+            return Err(NothingToClaim {}.into());
+        }
+        let mut accrued_setter = self.referral_fees_accrued.setter(referrer);
+        accrued_setter.set(accrued - amount);
+
+        self.safe_transfer(&quote_token, referrer, amount)?;
+
+        // Emit event - Referral Fees Claimed
+        evm::log(ReferralFeesClaimed { referrer, amount });
+
+        Ok(amount)
+    }
+
+    // Guardian allowed to blacklist addresses from trading
+    pub fn guardian(&self) -> Address {
+        self.guardian.get()
+    }
+
+    // Set the guardian, zero to leave blacklisting to the owner (only owner)
+    pub fn set_guardian(&mut self, new_guardian: Address) -> Result<(), PumpUpErrors> {
+        self.only_owner()?;
+
+        let previous_guardian = self.guardian.get();
+        self.guardian.set(new_guardian);
+
+        // Emit event - Guardian Set
+        evm::log(GuardianSet {
+            previousGuardian: previous_guardian,
+            newGuardian: new_guardian,
+        });
+
+        Ok(())
+    }
+
+    // Whether an address is blocked from trading a pool
+    pub fn is_blacklisted(&self, pool_id: B256, account: Address) -> bool {
+        self.blacklists.get(pool_id).get(account)
+    }
+
+    // Block or unblock an address from trading a pool (only guardian or owner, or the pool
+    // creator until the launch phases end)
+    pub fn set_blacklisted(
+        &mut self,
+        pool_id: B256,
+        account: Address,
+        blocked: bool,
+    ) -> Result<(), PumpUpErrors> {
+        self.only_blacklist_admin(pool_id)?;
+
+        let previous_blocked = self.is_blacklisted(pool_id, account);
+        let mut blacklist = self.blacklists.setter(pool_id);
+        let mut blocked_setter = blacklist.setter(account);
+        blocked_setter.set(blocked);
+
+        // Emit event - Blacklist Set
+        evm::log(BlacklistSet {
+            poolId: pool_id,
+            account,
+            previousBlocked: previous_blocked,
+            blocked,
+        });
+
+        Ok(())
+    }
+
+    // Whether fallback quotes may serve cached pool state
+    pub fn degraded_mode(&self) -> bool {
+        self.degraded_mode.get()
+    }
+
+    // Enable or disable serving cached pool state from fallback quotes (owner or pauser)
+    pub fn set_degraded_mode(&mut self, enabled: bool) -> Result<(), PumpUpErrors> {
+        self.only_role(PAUSER_ROLE)?;
+
+        let previous_enabled = self.degraded_mode.get();
+        self.degraded_mode.set(enabled);
+
+        // Emit event - Degraded Mode Set
+        evm::log(DegradedModeSet {
+            previousEnabled: previous_enabled,
+            enabled,
+        });
+
+        Ok(())
+    }
+
+    // Whether trading is paused for every pool
+    pub fn paused(&self) -> bool {
+        self.paused.get()
+    }
+
+    // Whether trading and quoting a pool is paused, by itself or globally
+    pub fn is_paused(&self, pool_id: B256) -> bool {
+        self.paused.get() || self.paused_pools.get(pool_id)
+    }
+
+    // Pause trading and quoting a pool (owner or pauser)
+    pub fn pause(&mut self, pool_id: B256) -> Result<(), PumpUpErrors> {
+        self.set_pool_paused(pool_id, true)
+    }
+
+    // Resume trading and quoting a pool (owner or pauser)
+    pub fn unpause(&mut self, pool_id: B256) -> Result<(), PumpUpErrors> {
+        self.set_pool_paused(pool_id, false)
+    }
+
+    // Pause trading and quoting every pool (owner or pauser)
+    pub fn pause_all(&mut self) -> Result<(), PumpUpErrors> {
+        self.set_global_pause(true)
+    }
+
+    // Lift the global pause; individually paused pools stay paused (owner or pauser)
+    pub fn unpause_all(&mut self) -> Result<(), PumpUpErrors> {
+        self.set_global_pause(false)
+    }
+
+    // Whether an account holds a role; the owner holds every role
+    pub fn has_role(&self, role: B256, account: Address) -> bool {
+        self.holds_role(role, account)
+    }
+
+    // Grant a role to an account (only owner)
+    pub fn grant_role(&mut self, role: B256, account: Address) -> Result<(), PumpUpErrors> {
+        self.only_owner()?;
+
+        if !is_known_role(role) {
+            return Err(UnknownRole { role }.into());
+        }
+
+        let mut members = self.role_members.setter(role);
+        if !members.get(account) {
+            let mut member_setter = members.setter(account);
+            member_setter.set(true);
+
+            // Emit event - Role Granted
+            evm::log(RoleGranted {
+                role,
+                account,
+                sender: msg::sender(),
+            });
+        }
+
+        Ok(())
+    }
+
+    // Revoke a role from an account (only owner)
+    pub fn revoke_role(&mut self, role: B256, account: Address) -> Result<(), PumpUpErrors> {
+        self.only_owner()?;
+
+        if !is_known_role(role) {
+            return Err(UnknownRole { role }.into());
+        }
+
+        let mut members = self.role_members.setter(role);
+        if members.get(account) {
+            let mut member_setter = members.setter(account);
+            member_setter.set(false);
+
+            // Emit event - Role Revoked
+            evm::log(RoleRevoked {
+                role,
+                account,
+                sender: msg::sender(),
+            });
+        }
+
+        Ok(())
+    }
+
+    // Transfer ownership of the contract (only owner)
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), PumpUpErrors> {
+        self.only_owner()?;
+
+        if new_owner == Address::ZERO {
+            return Err(ZeroAddressOwner {}.into());
+        }
+
+        let previous_owner = *self.owner;
+        self.owner.set(new_owner);
+
+        // Emit event - Ownership Transferred
+        evm::log(OwnershipTransferred {
+            previousOwner: previous_owner,
+            newOwner: new_owner,
+        });
+
+        Ok(())
+    }
+}
+
+// Shared strategy interface, backed by the public quote entrypoints
+impl BondingCurveStrategy for SigmoidBondingCurve {
+    type Error = PumpUpErrors;
+
+    fn initialize(&mut self, pool_id: B256, params: &[u8]) -> Result<(), PumpUpErrors> {
+        SigmoidBondingCurve::initialize(self, pool_id, Bytes(params.to_vec()))
+    }
+
+    fn quote_buy(&self, pool_id: B256, weth_amount: U256) -> Result<(U256, U256), PumpUpErrors> {
+        let (token_amount, new_price, _weth_unspent) =
+            SigmoidBondingCurve::quote_buy(self, pool_id, Address::ZERO, weth_amount)?;
+        Ok((token_amount, new_price))
+    }
+
+    fn quote_sell(&self, pool_id: B256, token_amount: U256) -> Result<(U256, U256), PumpUpErrors> {
+        SigmoidBondingCurve::quote_sell(self, pool_id, Address::ZERO, token_amount)
+    }
+
+    fn spot_price(&self, pool_id: B256) -> Result<U256, PumpUpErrors> {
+        self.get_current_price(pool_id)
+    }
+}
+
+// Internal functions
+impl SigmoidBondingCurve {
+    // Helper function to get curve parameters from storage
+    fn get_curve_params(&self, pool_id: B256) -> Result<CurveParameters, PumpUpErrors> {
+        let curve_params = self.curve_params.get(pool_id);
+        let initial_price = curve_params.initial_price.get();
+
+        if initial_price.is_zero() {
+            return Err(InvalidPoolId {}.into());
+        }
+
+        let mut params = CurveParameters {
+            initial_price,
+            max_price_factor: curve_params.max_price_factor.get(),
+            steepness: U256::from(curve_params.steepness.get()),
+            midpoint: U256::from(curve_params.midpoint.get()),
+            total_supply: curve_params.total_supply.get(),
+            curve_kind: curve_params.curve_kind.get().to::<u8>(),
+            ..Default::default()
+        };
+
+        if params.curve_kind == CURVE_KIND_PIECEWISE {
+            let segments = self.curve_segments.get(pool_id);
+            params.segment_count = self
+                .curve_segment_counts
+                .get(pool_id)
+                .saturating_to::<usize>()
+                .min(MAX_CURVE_SEGMENTS);
+            for (i, segment) in params.segments[..params.segment_count]
+                .iter_mut()
+                .enumerate()
+            {
+                let stored = segments.get(U256::from(i));
+                *segment = CurveSegment {
+                    shape: stored.shape.get().to::<u8>(),
+                    end_supply: stored.end_supply.get(),
+                    end_price: stored.end_price.get(),
+                    steepness: U256::from(stored.steepness.get()),
+                    midpoint: U256::from(stored.midpoint.get()),
+                };
+            }
+        }
+
+        Ok(params)
+    }
+
+    // Circulating supply pricing uses: the tracked supply once the pool is synced, token reads
+    // before
+    fn circulating_supply(
+        &self,
+        pool_id: B256,
+        token_address: &Address,
+        params: &CurveParameters,
+    ) -> Result<U256, PumpUpErrors> {
+        let account = self.supply_accounts.get(pool_id);
+        if account.synced.get() {
+            return Ok(account.circulating_supply.get());
+        }
+
+        self.token_circulating_supply(pool_id, token_address, params)
+    }
+
+    // Circulating supply read from the token (total supply minus the manager's balance),
+    // rejecting reads that contradict the pool's stored total supply
+    fn token_circulating_supply(
+        &self,
+        pool_id: B256,
+        token_address: &Address,
+        params: &CurveParameters,
+    ) -> Result<U256, PumpUpErrors> {
+        let total_token_supply = host().total_supply(*token_address)?;
+        let held_by_manager = host().balance_of(*token_address, self.pool_manager(pool_id))?;
+
+        if total_token_supply.is_zero() || held_by_manager > total_token_supply {
+            return Err(InconsistentTokenState {}.into());
+        }
+
+        let circulating_supply = self.token_to_wad(pool_id, total_token_supply - held_by_manager);
+        if circulating_supply > params.total_supply {
+            return Err(InconsistentTokenState {}.into());
+        }
+
+        Ok(circulating_supply)
+    }
+
+    // Store a pool token's decimals the first time the token answers decimals() with a value
+    // the curve can scale; until then its amounts are taken as 18-decimal
+    fn record_token_decimals(&mut self, pool_id: B256, token_address: Address) {
+        if self.token_decimals.get(pool_id).recorded.get() {
+            return;
+        }
+
+        if let Ok(decimals) = host().decimals(token_address) {
+            if decimals <= MAX_TOKEN_DECIMALS {
+                let mut setter = self.token_decimals.setter(pool_id);
+                setter.recorded.set(true);
+                setter.decimals.set(U8::from(decimals));
+            }
+        }
+    }
+
+    // Token amount in the pool token's decimals as the 18-decimal amount the curve prices
+    fn token_to_wad(&self, pool_id: B256, amount: U256) -> U256 {
+        scale_decimals(
+            amount,
+            self.token_decimals(pool_id),
+            WAD_DECIMALS,
+            Rounding::Down,
+        )
+    }
+
+    // 18-decimal curve amount in the pool token's decimals, rounded down
+    fn wad_to_token(&self, pool_id: B256, amount: U256) -> U256 {
+        scale_decimals(
+            amount,
+            WAD_DECIMALS,
+            self.token_decimals(pool_id),
+            Rounding::Down,
+        )
+    }
+
+    // 18-decimal curve amount rounded down to a whole number of the pool token's units
+    fn round_to_token_units(&self, pool_id: B256, amount: U256) -> U256 {
+        self.token_to_wad(pool_id, self.wad_to_token(pool_id, amount))
+    }
+
+    // Amount in the pool's base asset decimals as the 18-decimal amount the curve prices
+    fn base_to_wad(&self, pool_id: B256, amount: U256) -> U256 {
+        let (_, decimals) = self.base_asset(pool_id);
+        scale_decimals(amount, decimals, WAD_DECIMALS, Rounding::Down)
+    }
+
+    // 18-decimal curve amount in the pool's base asset decimals, rounded in the given direction
+    fn wad_to_base(&self, pool_id: B256, amount: U256, rounding: Rounding) -> U256 {
+        let (_, decimals) = self.base_asset(pool_id);
+        scale_decimals(amount, WAD_DECIMALS, decimals, rounding)
+    }
+
+    // Curve cost of buying or selling `token_amount` in the pool's base asset, rounded against
+    // the trader: up for buys, down for sells
+    fn base_cost(
+        &self,
+        pool_id: B256,
+        current_supply: U256,
+        token_amount: U256,
+        params: &CurveParameters,
+        is_selling: bool,
+    ) -> U256 {
+        let rounding = if is_selling {
+            Rounding::Down
+        } else {
+            Rounding::Up
+        };
+        self.wad_to_base(
+            pool_id,
+            self.calculate_weth_for_token_amount(current_supply, token_amount, params, is_selling),
+            rounding,
+        )
+    }
+
+    // ERC20 transfer that also accepts tokens returning no data (USDT-style)
+    fn safe_transfer(
+        &mut self,
+        token: &Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), PumpUpErrors> {
+        let mut call_data = Vec::with_capacity(68);
+        call_data.extend_from_slice(&[0xa9, 0x05, 0x9c, 0xbb]); // keccak256("transfer(address,uint256)")
+        call_data.extend_from_slice(&[0; 12]);
+        call_data.extend_from_slice(to.as_slice());
+        call_data.extend_from_slice(&amount.to_be_bytes::<32>());
+
+        self.call_erc20(token, &call_data)
+    }
+
+    // Transfer ERC20 tokens from `from`, who must have approved this contract
+    fn safe_transfer_from(
+        &mut self,
+        token: &Address,
+        from: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), PumpUpErrors> {
+        let mut call_data = Vec::with_capacity(100);
+        call_data.extend_from_slice(&[0x23, 0xb8, 0x72, 0xdd]); // keccak256("transferFrom(address,address,uint256)")
+        call_data.extend_from_slice(&[0; 12]);
+        call_data.extend_from_slice(from.as_slice());
+        call_data.extend_from_slice(&[0; 12]);
+        call_data.extend_from_slice(to.as_slice());
+        call_data.extend_from_slice(&amount.to_be_bytes::<32>());
+
+        self.call_erc20(token, &call_data)
+    }
+
+    // Call an ERC20 transfer function. Empty return data counts as success only when the token
+    // has code; otherwise the result must decode to true.
+    fn call_erc20(&mut self, token: &Address, call_data: &[u8]) -> Result<(), PumpUpErrors> {
+        let result = RawCall::new()
+            .call(*token, call_data)
+            .map_err(|_| -> PumpUpErrors { Erc20CallFailed {}.into() })?;
+
+        if result.is_empty() {
+            if !token.has_code() {
+                return Err(Erc20CallFailed {}.into());
+            }
+            return Ok(());
+        }
+
+        // Parse bool from the result
+        if result.len() < 32 || result[0..31].iter().any(|b| *b != 0) || result[31] > 1 {
+            return Err(Erc20InvalidResult {}.into());
+        }
+        if result[31] == 0 {
+            return Err(Erc20TransferFailed {}.into());
+        }
+
+        Ok(())
+    }
+
+    // Replace the global pool state manager and emit the change
+    fn update_pool_state_manager(
+        &mut self,
+        new_pool_state_manager: Address,
+    ) -> Result<(), PumpUpErrors> {
+        let previous_pool_state_manager = *self.pool_state_manager;
+        self.pool_state_manager.set(new_pool_state_manager);
+
+        // Emit event - Pool State Manager Set
+        evm::log(PoolStateManagerSet {
+            previousPoolStateManager: previous_pool_state_manager,
+            newPoolStateManager: new_pool_state_manager,
+        });
+
+        Ok(())
+    }
+
+    // Emit the previous and new curve parameters of a pool whose parameters were overwritten
+    fn emit_curve_params_changed(
+        &self,
+        pool_id: B256,
+        previous: &CurveParameters,
+        current: &CurveParameters,
+    ) {
+        // Emit event - Curve Params Changed
+        evm::log(CurveParamsChanged {
+            poolId: pool_id,
+            previousInitialPrice: previous.initial_price,
+            previousMaxPriceFactor: previous.max_price_factor,
+            previousSteepness: previous.steepness,
+            previousMidpoint: previous.midpoint,
+            previousTotalSupply: previous.total_supply,
+            initialPrice: current.initial_price,
+            maxPriceFactor: current.max_price_factor,
+            steepness: current.steepness,
+            midpoint: current.midpoint,
+            totalSupply: current.total_supply,
+        });
+    }
+
+    // Tracked circulating supply of a synced pool, for the manager's notify hooks
+    fn tracked_supply_for_update(&self, pool_id: B256) -> Result<U256, PumpUpErrors> {
+        self.check_pool_manager(pool_id)?;
+
+        let account = self.supply_accounts.get(pool_id);
+        if !account.synced.get() {
+            return Err(SupplyNotSynced {}.into());
+        }
+
+        Ok(account.circulating_supply.get())
+    }
+
+    // Store a pool's tracked circulating supply, marking it synced
+    fn set_tracked_supply(
+        &mut self,
+        pool_id: B256,
+        previous_circulating_supply: U256,
+        circulating_supply: U256,
+    ) {
+        let mut account = self.supply_accounts.setter(pool_id);
+        account.circulating_supply.set(circulating_supply);
+        account.synced.set(true);
+
+        // The manager reports supply after updating its own state
+        pool_info_cache::clear(self.pool_manager(pool_id), pool_id);
+
+        // Emit event - Circulating Supply Updated
+        evm::log(CirculatingSupplyUpdated {
+            poolId: pool_id,
+            previousCirculatingSupply: self.wad_to_token(pool_id, previous_circulating_supply),
+            circulatingSupply: self.wad_to_token(pool_id, circulating_supply),
+        });
+    }
+
+    // Manager a pool is bound to, falling back to the global manager for unbound pools
+    fn pool_manager(&self, pool_id: B256) -> Address {
+        let manager = self.pool_managers.get(pool_id);
+        if manager == Address::ZERO {
+            *self.pool_state_manager
+        } else {
+            manager
+        }
+    }
+
+    // Reject calls from anyone but the manager a pool is bound to
+    fn check_pool_manager(&self, pool_id: B256) -> Result<(), PumpUpErrors> {
+        if msg::sender() != self.pool_manager(pool_id) {
+            return Err(NotPoolStateManager {}.into());
+        }
+        Ok(())
+    }
+
+    // Re-point a single pool to a new manager and emit the migration event
+    fn rebind_pool_manager(
+        &mut self,
+        pool_id: B256,
+        from: Address,
+        to: Address,
+    ) -> Result<(), PumpUpErrors> {
+        let mut pool_manager_setter = self.pool_managers.setter(pool_id);
+        pool_manager_setter.set(to);
+
+        // Emit event - Pool Manager Migrated
+        evm::log(PoolManagerMigrated {
+            poolId: pool_id,
+            from,
+            to,
+        });
+
+        Ok(())
+    }
+
+    // Get pool info from the pool's manager contract, at most once per transaction
+    fn get_pool_info(&self, pool_id: B256) -> Result<PoolInfo, PumpUpErrors> {
+        let manager = self.pool_manager(pool_id);
+        if let Some(info) = pool_info_cache::get(manager, pool_id) {
+            return Ok(info);
+        }
+
+        let info = host().pool_info(manager, pool_id)?;
+        pool_info_cache::put(manager, pool_id, &info);
+        Ok(info)
+    }
+
+    // Emit a compact trade event whose single data word packs amount in (high 128 bits) and
+    // amount out (low 128 bits), each saturated to 128 bits
+    fn emit_compact_trade(&self, pool_id: B256, is_buy: bool, amount_in: U256, amount_out: U256) {
+        let max_half = U256::from(u128::MAX);
+        let packed: U256 = (amount_in.min(max_half) << 128) | amount_out.min(max_half);
+
+        if is_buy {
+            // Emit event - Tokens Purchased Compact
+            evm::log(TokensPurchasedCompact {
+                poolId: pool_id,
+                packedAmounts: packed,
+            });
+        } else {
+            // Emit event - Tokens Sold Compact
+            evm::log(TokensSoldCompact {
+                poolId: pool_id,
+                packedAmounts: packed,
+            });
+        }
+    }
+
+    // EIP-712 digest of a price attestation bound to this chain and contract
+    fn price_attestation_digest(
+        &self,
+        pool_id: B256,
+        price: U256,
+        supply: U256,
+        block_number: u64,
+    ) -> B256 {
+        let mut contract_bytes = [0u8; 32];
+        contract_bytes[12..32].copy_from_slice(contract::address().as_slice());
+
+        let mut domain = Vec::with_capacity(160);
+        domain.extend_from_slice(crypto::keccak(EIP712_DOMAIN_TYPE).as_slice());
+        domain.extend_from_slice(crypto::keccak(EIP712_NAME).as_slice());
+        domain.extend_from_slice(crypto::keccak(EIP712_VERSION).as_slice());
+        domain.extend_from_slice(&U256::from(block::chainid()).to_be_bytes::<32>());
+        domain.extend_from_slice(&contract_bytes);
+        let domain_separator = crypto::keccak(&domain);
+
+        let mut attestation = Vec::with_capacity(160);
+        attestation.extend_from_slice(crypto::keccak(PRICE_ATTESTATION_TYPE).as_slice());
+        attestation.extend_from_slice(pool_id.as_slice());
+        attestation.extend_from_slice(&price.to_be_bytes::<32>());
+        attestation.extend_from_slice(&supply.to_be_bytes::<32>());
+        attestation.extend_from_slice(&U256::from(block_number).to_be_bytes::<32>());
+        let struct_hash = crypto::keccak(&attestation);
+
+        let mut preimage = Vec::with_capacity(66);
+        preimage.extend_from_slice(&[0x19, 0x01]);
+        preimage.extend_from_slice(domain_separator.as_slice());
+        preimage.extend_from_slice(struct_hash.as_slice());
+        crypto::keccak(&preimage)
+    }
+
+    // Record hook run after every executed trade
+    fn record_trade(
+        &mut self,
+        pool_id: B256,
+        trader: Address,
+        direction: u8,
+        token_amount: U256,
+        weth_amount: U256,
+        price: U256,
+    ) {
+        // The manager's view of the pool changes with the trade
+        pool_info_cache::clear(self.pool_manager(pool_id), pool_id);
+
+        self.write_observation(pool_id, block::timestamp());
+
+        let mut trade = self.last_trades.setter(pool_id);
+        trade.timestamp.set(U64::from(block::timestamp()));
+        trade.direction.set(U8::from(direction));
+        trade.token_amount.set(token_amount);
+        trade.weth_amount.set(weth_amount);
+        trade.price.set(price);
+
+        let total_weth_volume = self.total_weth_volume.get();
+        self.total_weth_volume
+            .set(total_weth_volume.saturating_add(weth_amount));
+
+        let mut stats = self.pool_stats.setter(pool_id);
+        let volume = if direction == TRADE_DIRECTION_BUY {
+            &mut stats.buy_volume
+        } else {
+            &mut stats.sell_volume
+        };
+        let previous_volume = volume.get();
+        volume.set(previous_volume.saturating_add(weth_amount));
+        let trade_count = stats.trade_count.get();
+        stats.trade_count.set(trade_count + U64::from(1));
+
+        self.track_wash_trading(pool_id, trader, direction, weth_amount);
+
+        let capacity = U256::from(self.trade_log_capacity.get());
+        if capacity.is_zero() {
+            return;
+        }
+
+        // Append to the pool's trade log and drop the entry falling out of the window
+        let count = self.trade_log_counts.get(pool_id);
+        let mut log = self.trade_logs.setter(pool_id);
+        let mut entry = log.setter(count);
+        entry.trader.set(trader);
+        entry.block_number.set(U64::from(block::number()));
+        entry.direction.set(U8::from(direction));
+        entry.token_amount.set(token_amount);
+        entry.weth_amount.set(weth_amount);
+        entry.price.set(price);
+
+        if count >= capacity {
+            let mut dropped = log.setter(count - capacity);
+            dropped.trader.set(Address::ZERO);
+            dropped.block_number.set(U64::ZERO);
+            dropped.direction.set(U8::ZERO);
+            dropped.token_amount.set(U256::ZERO);
+            dropped.weth_amount.set(U256::ZERO);
+            dropped.price.set(U256::ZERO);
+        }
+
+        self.trade_log_counts
+            .setter(pool_id)
+            .set(count + U256::from(1));
+    }
+
+    // Accumulate the price in effect since the pool's last trade up to `now`. Trades within the
+    // same second share one observation.
+    fn write_observation(&mut self, pool_id: B256, now: u64) {
+        let count = self.observation_counts.get(pool_id);
+        if !count.is_zero() && self.latest_observation(pool_id, count).0 >= now {
+            return;
+        }
+
+        let price_cumulative = self.extrapolated_price_cumulative(pool_id, count, now);
+        let mut observations = self.observations.setter(pool_id);
+        let mut observation = observations.setter(count % U256::from(OBSERVATION_CAPACITY));
+        observation.timestamp.set(U64::from(now));
+        observation.price_cumulative.set(price_cumulative);
+
+        self.observation_counts
+            .setter(pool_id)
+            .set(count + U256::from(1));
+    }
+
+    // Average price of a pool over the `seconds_ago` seconds before `now`, from its `count`
+    // observations
+    fn time_weighted_price(
+        &self,
+        pool_id: B256,
+        count: U256,
+        seconds_ago: u32,
+        now: u64,
+    ) -> Result<U256, PumpUpErrors> {
+        let start = now
+            .checked_sub(u64::from(seconds_ago))
+            .ok_or(InvalidAmount {})?;
+
+        let end_cumulative = self.extrapolated_price_cumulative(pool_id, count, now);
+        let start_cumulative = self.price_cumulative_at(pool_id, count, start)?;
+
+        Ok((end_cumulative - start_cumulative) / U256::from(seconds_ago))
+    }
+
+    // Observation number `index` of a pool, as (timestamp, cumulative price)
+    fn observation(&self, pool_id: B256, index: U256) -> (u64, U256) {
+        let observations = self.observations.get(pool_id);
+        let observation = observations.get(index % U256::from(OBSERVATION_CAPACITY));
+        (
+            observation.timestamp.get().to::<u64>(),
+            observation.price_cumulative.get(),
+        )
+    }
+
+    // Newest of a pool's `count` observations
+    fn latest_observation(&self, pool_id: B256, count: U256) -> (u64, U256) {
+        self.observation(pool_id, count - U256::from(1))
+    }
+
+    // Cumulative price of a pool at a time no older than its newest observation: the last trade's
+    // price has been in effect since then
+    fn extrapolated_price_cumulative(&self, pool_id: B256, count: U256, timestamp: u64) -> U256 {
+        if count.is_zero() {
+            return U256::ZERO;
+        }
+
+        let (observed_at, price_cumulative) = self.latest_observation(pool_id, count);
+        let price = self.last_trades.get(pool_id).price.get();
+        price_cumulative
+            .saturating_add(price.saturating_mul(U256::from(timestamp.saturating_sub(observed_at))))
+    }
+
+    // Cumulative price of a pool at `timestamp`, interpolating between the observations around it
+    fn price_cumulative_at(
+        &self,
+        pool_id: B256,
+        count: U256,
+        timestamp: u64,
+    ) -> Result<U256, PumpUpErrors> {
+        let capacity = U256::from(OBSERVATION_CAPACITY);
+        let oldest = count.saturating_sub(capacity);
+        let (oldest_timestamp, _) = self.observation(pool_id, oldest);
+        if timestamp < oldest_timestamp {
+            return Err(ObservationTooOld {
+                oldestTimestamp: oldest_timestamp,
+            }
+            .into());
+        }
+
+        let (latest_timestamp, _) = self.latest_observation(pool_id, count);
+        if timestamp >= latest_timestamp {
+            return Ok(self.extrapolated_price_cumulative(pool_id, count, timestamp));
+        }
+
+        // Last observation at or before `timestamp`; the one after it is newer than `timestamp`
+        let (mut low, mut high) = (oldest, count - U256::from(1));
+        while high - low > U256::from(1) {
+            let mid = low + (high - low) / U256::from(2);
+            if self.observation(pool_id, mid).0 <= timestamp {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+
+        let (before_timestamp, before_cumulative) = self.observation(pool_id, low);
+        let (after_timestamp, after_cumulative) = self.observation(pool_id, high);
+        let elapsed = U256::from(timestamp - before_timestamp);
+        let interval = U256::from(after_timestamp - before_timestamp);
+
+        Ok(before_cumulative + (after_cumulative - before_cumulative) * elapsed / interval)
+    }
+
+    // Count a trade that offsets the same address's opposite trade within the wash window as wash
+    // volume, and flag addresses that keep doing so
+    fn track_wash_trading(
+        &mut self,
+        pool_id: B256,
+        trader: Address,
+        direction: u8,
+        weth_amount: U256,
+    ) {
+        let current_block = block::number();
+
+        let mut trackers = self.wash_trackers.setter(pool_id);
+        let mut tracker = trackers.setter(trader);
+
+        let (opposite_block, opposite_weth) = if direction == TRADE_DIRECTION_BUY {
+            tracker.last_buy_block.set(U64::from(current_block));
+            tracker.last_buy_weth.set(weth_amount);
+            (tracker.last_sell_block.get(), tracker.last_sell_weth.get())
+        } else {
+            tracker.last_sell_block.set(U64::from(current_block));
+            tracker.last_sell_weth.set(weth_amount);
+            (tracker.last_buy_block.get(), tracker.last_buy_weth.get())
+        };
+
+        let opposite_block = opposite_block.to::<u64>();
+        if opposite_block == 0
+            || current_block.saturating_sub(opposite_block) > WASH_TRADE_WINDOW_BLOCKS
+        {
+            return;
+        }
+
+        let offsetting_trades = tracker.offsetting_trades.get().to::<u64>() + 1;
+        tracker.offsetting_trades.set(U64::from(offsetting_trades));
+
+        let newly_flagged = offsetting_trades >= WASH_FLAG_THRESHOLD && !tracker.flagged.get();
+        if newly_flagged {
+            tracker.flagged.set(true);
+        }
+
+        let wash_volume = self.wash_volumes.get(pool_id);
+        self.wash_volumes
+            .setter(pool_id)
+            .set(wash_volume.saturating_add(weth_amount.min(opposite_weth)));
+
+        if newly_flagged {
+            self.wash_flagged.setter(pool_id).push(trader);
+        }
+    }
+
+    // Flag the pool as transition pending and announce it ready once a buy pushes base asset
+    // collected past its target
+    fn check_graduation_threshold(
+        &mut self,
+        pool_id: B256,
+        weth_collected: U256,
+        final_price: U256,
+    ) -> Result<(), PumpUpErrors> {
+        let weth_target = self.graduation_weth_targets.get(pool_id);
+        if weth_target.is_zero() || weth_collected < weth_target {
+            return Ok(());
+        }
+
+        self.graduate(pool_id, weth_collected, final_price)
+    }
+
+    // Flag the pool as transition pending, announce it ready and notify its manager if the
+    // graduation callback is on
+    fn graduate(
+        &mut self,
+        pool_id: B256,
+        weth_collected: U256,
+        final_price: U256,
+    ) -> Result<(), PumpUpErrors> {
+        let weth_target = self.graduation_weth_targets.get(pool_id);
+        self.mark_transition_pending(pool_id, weth_collected)?;
+
+        // Emit event - Graduation Threshold Reached
+        evm::log(GraduationThresholdReached {
+            poolId: pool_id,
+            baseCollected: weth_collected,
+            baseTarget: weth_target,
+        });
+
+        if self.graduation_callback_enabled.get() {
+            self.notify_graduation(pool_id, weth_collected)?;
+        }
+
+        // Emit event - Transition Ready
+        evm::log(TransitionReady {
+            poolId: pool_id,
+            finalPrice: final_price,
+            baseCollected: weth_collected,
+        });
+
+        Ok(())
+    }
+
+    // Tell the pool's manager that a buy reached the graduation target
+    fn notify_graduation(
+        &mut self,
+        pool_id: B256,
+        weth_collected: U256,
+    ) -> Result<(), PumpUpErrors> {
+        let mut call_data = Vec::with_capacity(68);
+        call_data.extend_from_slice(&[0xed, 0x29, 0x5f, 0xfe]); // keccak256("onGraduationThresholdReached(bytes32,uint256)")
+        call_data.extend_from_slice(pool_id.as_slice());
+        call_data.extend_from_slice(&weth_collected.to_be_bytes::<32>());
+
+        RawCall::new()
+            .call(self.pool_manager(pool_id), &call_data)
+            .map_err(|_| -> PumpUpErrors { ManagerCallFailed {}.into() })?;
+
+        Ok(())
+    }
+
+    // Flag the pool as transition pending so the manager runs the transition
+    fn mark_transition_pending(
+        &mut self,
+        pool_id: B256,
+        weth_collected: U256,
+    ) -> Result<(), PumpUpErrors> {
+        let mut pending_setter = self.transition_pending.setter(pool_id);
+        pending_setter.set(true);
+
+        // Emit event - Transition Pending
+        evm::log(TransitionMarkedPending {
+            poolId: pool_id,
+            baseCollected: weth_collected,
+        });
+
+        Ok(())
+    }
+
+    // Clear a pool's pending transition flag (only the pool's manager)
+    fn clear_transition_pending(&mut self, pool_id: B256) -> Result<(), PumpUpErrors> {
+        self.check_pool_manager(pool_id)?;
+
+        if !self.transition_pending.get(pool_id) {
+            return Err(NoTransitionPending {}.into());
+        }
+
+        let mut pending_setter = self.transition_pending.setter(pool_id);
+        pending_setter.set(false);
+
+        Ok(())
+    }
+
+    // Execute a buy for `trader`: enforce launch limits, emit the purchase and record the trade.
+    // Unless `refund_unspent` is set, base asset the curve could not use is absorbed into the pool.
+    fn execute_buy(
+        &mut self,
+        pool_id: B256,
+        trader: Address,
+        weth_amount: U256,
+        refund_unspent: bool,
+        referrer: Address,
+    ) -> Result<(U256, U256, U256), PumpUpErrors> {
+        self.check_pool_manager(pool_id)?;
+        self.check_not_blacklisted(pool_id, trader)?;
+        self.check_compliance(pool_id, trader, TRADE_DIRECTION_BUY, weth_amount)?;
+        self.check_launch_phase(pool_id, trader, weth_amount)?;
+
+        let (circulating_supply, weth_collected, is_transitioned, _stale) =
+            self.pool_state(pool_id, false)?;
+        let (token_amount, new_price, weth_unspent) = self.quote_buy_from_state(
+            pool_id,
+            trader,
+            circulating_supply,
+            weth_collected,
+            is_transitioned,
+            weth_amount,
+        )?;
+        let (weth_spent, weth_unspent) = if refund_unspent {
+            (weth_amount - weth_unspent, weth_unspent)
+        } else {
+            (weth_amount, U256::ZERO)
+        };
+
+        // Refunded base asset carries no fees
+        let (protocol_fee, creator_fee) = self.trade_fees(pool_id, trader, weth_spent);
+        self.accrue_fees(pool_id, trader, referrer, protocol_fee, creator_fee);
+        let weth_to_curve = weth_spent - protocol_fee - creator_fee;
+
+        if self.compact_events.get() {
+            self.emit_compact_trade(pool_id, true, weth_spent, token_amount);
+        } else {
+            // Emit event - Tokens Purchased
+            evm::log(TokensPurchased {
+                poolId: pool_id,
+                baseAmount: weth_spent,
+                tokenAmount: token_amount,
+                newPrice: new_price,
+            });
+        }
+
+        self.check_graduation_threshold(
+            pool_id,
+            weth_collected.saturating_add(weth_to_curve),
+            new_price,
+        )?;
+
+        self.record_trade(
+            pool_id,
+            trader,
+            TRADE_DIRECTION_BUY,
+            token_amount,
+            weth_spent,
+            new_price,
+        );
+
+        let cashback = self.apply_cashback(pool_id, trader, token_amount)?;
+
+        self.update_pool_cache(
+            pool_id,
+            circulating_supply + self.token_to_wad(pool_id, token_amount + cashback),
+            weth_collected.saturating_add(weth_to_curve),
+            false,
+        );
+
+        Ok((token_amount + cashback, new_price, weth_unspent))
+    }
+
+    // Draw a buy's cashback from the pool's incentive reserve, as far as it allows. Returns the
+    // extra tokens owed to the buyer.
+    fn apply_cashback(
+        &mut self,
+        pool_id: B256,
+        buyer: Address,
+        token_amount: U256,
+    ) -> Result<U256, PumpUpErrors> {
+        let cashback_bps = self.cashback_bps.get(pool_id);
+        let reserve = self.incentive_reserves.get(pool_id);
+        if cashback_bps.is_zero() || reserve.is_zero() {
+            return Ok(U256::ZERO);
+        }
+
+        let cashback = (token_amount * cashback_bps / BPS_DENOMINATOR).min(reserve);
+        if cashback.is_zero() {
+            return Ok(U256::ZERO);
+        }
+
+        let remaining_reserve = reserve - cashback;
+        self.incentive_reserves
+            .setter(pool_id)
+            .set(remaining_reserve);
+        let cashback_paid = self.cashback_paid.get(pool_id);
+        self.cashback_paid
+            .setter(pool_id)
+            .set(cashback_paid.saturating_add(cashback));
+
+        // Emit event - Cashback Paid
+        evm::log(CashbackPaid {
+            poolId: pool_id,
+            buyer,
+            cashback,
+            remainingReserve: remaining_reserve,
+        });
+
+        if remaining_reserve.is_zero() {
+            // Emit event - Incentive Reserve Depleted
+            evm::log(IncentiveReserveDepleted { poolId: pool_id });
+        }
+
+        Ok(cashback)
+    }
+
+    // Whether a pool passed its expiry without reaching its graduation target
+    fn winding_down(&self, pool_id: B256, weth_collected: U256) -> bool {
+        let expiry = self.pool_expiries.get(pool_id).to::<u64>();
+        if expiry == 0 || block::timestamp() < expiry || self.transition_pending.get(pool_id) {
+            return false;
+        }
+
+        let weth_target = self.graduation_weth_targets.get(pool_id);
+        weth_target.is_zero() || weth_collected < weth_target
+    }
+
+    // Quote a buy for `trader` without side effects as (token amount, new price, base asset
+    // collected before the buy, unspent base asset)
+    fn quote_buy_amounts(
+        &self,
+        pool_id: B256,
+        trader: Address,
+        weth_amount: U256,
+    ) -> Result<(U256, U256, U256, U256), PumpUpErrors> {
+        let (circulating_supply, weth_collected, is_transitioned, _stale) =
+            self.pool_state(pool_id, false)?;
+
+        let (token_amount, new_price, weth_unspent) = self.quote_buy_from_state(
+            pool_id,
+            trader,
+            circulating_supply,
+            weth_collected,
+            is_transitioned,
+            weth_amount,
+        )?;
+
+        Ok((token_amount, new_price, weth_collected, weth_unspent))
+    }
+
+    // Quote a sell as (base asset returned, new price, insurance top-up included in the base asset
+    // returned, circulating supply, base asset collected)
+    fn quote_sell_amounts(
+        &self,
+        pool_id: B256,
+        token_amount: U256,
+    ) -> Result<(U256, U256, U256, U256, U256), PumpUpErrors> {
+        self.check_not_paused(pool_id)?;
+
+        // Get pool info
+        let (
+            token_address,
+            _creator,
+            weth_collected,
+            _last_price,
+            is_transitioned,
+            _bonding_curve_strategy,
+        ) = self.get_pool_info(pool_id)?;
+
+        if is_transitioned {
+            return Err(PoolTransitioned {}.into());
+        }
+
+        // Get current circulating supply
+        let params = self.get_curve_params(pool_id)?;
+        let circulating_supply = self.circulating_supply(pool_id, &token_address, &params)?;
+
+        let (weth_to_return, new_price, shortfall) = self.quote_sell_from_state(
+            pool_id,
+            circulating_supply,
+            weth_collected,
+            self.insurance_reserves.get(pool_id),
+            token_amount,
+        )?;
+
+        Ok((
+            weth_to_return,
+            new_price,
+            shortfall,
+            circulating_supply,
+            weth_collected,
+        ))
+    }
+
+    // Quote a sell against the given pool state and insurance reserve as (base asset returned
+    // before fees, new price, sell floor shortfall drawn from the reserve)
+    fn quote_sell_from_state(
+        &self,
+        pool_id: B256,
+        circulating_supply: U256,
+        weth_collected: U256,
+        insurance_reserve: U256,
+        token_amount: U256,
+    ) -> Result<(U256, U256, U256), PumpUpErrors> {
+        if token_amount.is_zero() {
+            return Err(InvalidAmount {}.into());
+        }
+
+        if token_amount < self.min_sell_tokens.get(pool_id) {
+            return Err(BelowMinimumTrade {}.into());
+        }
+
+        // Amounts smaller than the curve's 18-decimal resolution sell nothing
+        let curve_amount = self.token_to_wad(pool_id, token_amount);
+        if curve_amount.is_zero() {
+            return Err(InvalidAmount {}.into());
+        }
+
+        // Get curve parameters
+        let params = self.get_curve_params(pool_id)?;
+
+        if curve_amount > circulating_supply {
+            return Err(InvalidAmount {}.into());
+        }
+
+        // Calculate base asset to return based on area under the curve
+        self.check_curve_math(pool_id, circulating_supply, curve_amount, &params, true)?;
+        let curve_weth =
+            self.calculate_weth_for_token_amount(circulating_supply, curve_amount, &params, true);
+        let weth_to_return = self.wad_to_base(pool_id, curve_weth, Rounding::Down);
+
+        // Check against available liquidity
+        if weth_to_return > weth_collected {
+            return Err(InsufficientLiquidity {
+                available: weth_collected,
+                requested: weth_to_return,
+            }
+            .into());
+        }
+
+        // Top up to the sell floor from the insurance reserve, as far as it allows
+        let shortfall =
+            self.sell_floor_shortfall(pool_id, curve_amount, weth_to_return, insurance_reserve);
+
+        // Calculate the new price after selling
+        let new_price = self.calculate_sigmoid_price(circulating_supply - curve_amount, &params);
+        self.check_quote_invariants(
+            circulating_supply,
+            curve_amount,
+            curve_weth,
+            new_price,
+            &params,
+            true,
+        )?;
+        let new_price = self.quantize_price(pool_id, new_price, false);
+
+        Ok((weth_to_return + shortfall, new_price, shortfall))
+    }
+
+    // Circulating supply, tokens still for sale and curve parameters of a pool
+    fn remaining_on_curve(
+        &self,
+        pool_id: B256,
+    ) -> Result<(U256, U256, CurveParameters), PumpUpErrors> {
+        let (token_address, _, _, _, is_transitioned, _) = self.get_pool_info(pool_id)?;
+        let params = self.get_curve_params(pool_id)?;
+        let circulating_supply = self.circulating_supply(pool_id, &token_address, &params)?;
+
+        let remaining_supply = if is_transitioned {
+            U256::ZERO
+        } else {
+            params.total_supply.saturating_sub(circulating_supply)
+        };
+
+        Ok((circulating_supply, remaining_supply, params))
+    }
+
+    // Spot price at a circulating supply, the initial price before the first sale
+    fn spot_price(&self, circulating_supply: U256, params: &CurveParameters) -> U256 {
+        if circulating_supply.is_zero() {
+            return params.initial_price;
+        }
+        self.calculate_sigmoid_price(circulating_supply, params)
+    }
+
+    // Quote a buy by `trader` against the given pool state as (token amount, new price, unspent
+    // base asset)
+    fn quote_buy_from_state(
+        &self,
+        pool_id: B256,
+        trader: Address,
+        circulating_supply: U256,
+        weth_collected: U256,
+        is_transitioned: bool,
+        weth_amount: U256,
+    ) -> Result<(U256, U256, U256), PumpUpErrors> {
+        self.check_not_paused(pool_id)?;
+
+        if is_transitioned {
+            return Err(PoolTransitioned {}.into());
+        }
+
+        if self.transition_pending.get(pool_id) {
+            return Err(TransitionPending {}.into());
+        }
+
+        if self.winding_down(pool_id, weth_collected) {
+            return Err(PoolWindingDown {}.into());
+        }
+
+        if weth_amount.is_zero() {
+            return Err(InvalidAmount {}.into());
+        }
+
+        if weth_amount < self.min_buy_weth.get(pool_id) {
+            return Err(BelowMinimumTrade {}.into());
+        }
+
+        let raise_cap = self.raise_caps.get(pool_id);
+        if !raise_cap.is_zero() && weth_collected.saturating_add(weth_amount) > raise_cap {
+            return Err(RaiseCapExceeded {}.into());
+        }
+
+        // Fees come out of the base asset paid in; the rest buys on the curve
+        let (protocol_fee, creator_fee) = self.trade_fees(pool_id, trader, weth_amount);
+        let weth_to_curve = weth_amount - protocol_fee - creator_fee;
+
+        // Get curve parameters
+        let params = self.get_curve_params(pool_id)?;
+
+        let (token_amount, new_price) =
+            self.buy_at_supply(pool_id, circulating_supply, weth_to_curve, &params);
+        self.check_curve_math(pool_id, circulating_supply, token_amount, &params, false)?;
+
+        // Base asset the curve cannot use at the solver's granularity, along with the fees a buy of
+        // only what it uses would save
+        let weth_cost =
+            self.calculate_weth_for_token_amount(circulating_supply, token_amount, &params, false);
+        self.check_quote_invariants(
+            circulating_supply,
+            token_amount,
+            weth_cost,
+            new_price,
             &params,
-            false, // buying tokens
+            false,
+        )?;
+        let new_price = self.quantize_price(pool_id, new_price, true);
+        let weth_spent = self.amount_before_fees(
+            pool_id,
+            trader,
+            self.wad_to_base(pool_id, weth_cost, Rounding::Up),
+        );
+        let weth_unspent = weth_amount.saturating_sub(weth_spent);
+
+        Ok((
+            self.wad_to_token(pool_id, token_amount),
+            new_price,
+            weth_unspent,
+        ))
+    }
+
+    // Circulating supply, base asset collected and transitioned flag of a pool, read live from the
+    // token and manager. With `allow_cached` and degraded mode on, a failed read falls back to the
+    // state cached at the last trade. Returns (supply, base asset collected, transitioned, stale).
+    fn pool_state(
+        &self,
+        pool_id: B256,
+        allow_cached: bool,
+    ) -> Result<(U256, U256, bool, bool), PumpUpErrors> {
+        let params = self.get_curve_params(pool_id)?;
+
+        let live_state = self.get_pool_info(pool_id).and_then(
+            |(token_address, _, weth_collected, _, is_transitioned, _)| {
+                let circulating_supply =
+                    self.circulating_supply(pool_id, &token_address, &params)?;
+                Ok((circulating_supply, weth_collected, is_transitioned))
+            },
+        );
+
+        match live_state {
+            Ok((circulating_supply, weth_collected, is_transitioned)) => {
+                Ok((circulating_supply, weth_collected, is_transitioned, false))
+            }
+            Err(error) => {
+                let cache = self.pool_caches.get(pool_id);
+                if !allow_cached || !self.degraded_mode.get() || cache.block_number.get().is_zero()
+                {
+                    return Err(error);
+                }
+                Ok((
+                    cache.circulating_supply.get(),
+                    cache.weth_collected.get(),
+                    cache.is_transitioned.get(),
+                    true,
+                ))
+            }
+        }
+    }
+
+    // Remember a pool's state for degraded-mode quotes
+    fn update_pool_cache(
+        &mut self,
+        pool_id: B256,
+        circulating_supply: U256,
+        weth_collected: U256,
+        is_transitioned: bool,
+    ) {
+        let mut cache = self.pool_caches.setter(pool_id);
+        cache.circulating_supply.set(circulating_supply);
+        cache.weth_collected.set(weth_collected);
+        cache.is_transitioned.set(is_transitioned);
+        cache.block_number.set(U64::from(block::number()));
+    }
+
+    // Round a quoted price to the pool's tick against the trader: up for buys, down for sells
+    fn quantize_price(&self, pool_id: B256, price: U256, round_up: bool) -> U256 {
+        let tick = self.price_ticks.get(pool_id);
+        if tick.is_zero() {
+            return price;
+        }
+
+        let remainder = price % tick;
+        if remainder.is_zero() {
+            price
+        } else if round_up {
+            (price - remainder).saturating_add(tick)
+        } else {
+            price - remainder
+        }
+    }
+
+    // Tokens received and resulting price for spending `weth_amount` at a given circulating supply
+    fn buy_at_supply(
+        &self,
+        pool_id: B256,
+        circulating_supply: U256,
+        weth_amount: U256,
+        params: &CurveParameters,
+    ) -> (U256, U256) {
+        // Solve the cost integral for the token amount, from zero supply too, keeping only whole
+        // units of the pool token
+        let token_amount = self.round_to_token_units(
+            pool_id,
+            self.find_buy_token_amount(pool_id, circulating_supply, weth_amount, params),
+        );
+
+        // Calculate new price after purchase
+        let new_circulating_supply = circulating_supply + token_amount;
+        let new_price = self.calculate_sigmoid_price(new_circulating_supply, params);
+
+        (token_amount, new_price)
+    }
+
+    // Number of blocks a quote commitment stays valid
+    fn quote_validity_blocks(&self) -> u64 {
+        let blocks = self.quote_validity_blocks.get().to::<u64>();
+        if blocks == 0 {
+            DEFAULT_QUOTE_VALIDITY_BLOCKS
+        } else {
+            blocks
+        }
+    }
+
+    // Tolerance (in bps) below a committed token amount that execution still accepts
+    fn quote_tolerance_bps(&self) -> U256 {
+        let tolerance = self.quote_tolerance_bps.get();
+        if tolerance.is_zero() {
+            DEFAULT_QUOTE_TOLERANCE_BPS
+        } else {
+            tolerance
+        }
+    }
+
+    // Protocol and creator fees on a trade worth `weth_amount`, with the trader's integrator
+    // discount taken off the protocol fee. Fees round up and the discount down, against the
+    // trader, without the fees exceeding the trade.
+    fn trade_fees(&self, pool_id: B256, trader: Address, weth_amount: U256) -> (U256, U256) {
+        let (protocol_fee_bps, creator_fee_bps) = self.get_fees(pool_id);
+
+        let protocol_fee =
+            mul_div_rounding_up(weth_amount, protocol_fee_bps, BPS_DENOMINATOR).min(weth_amount);
+        let discount = mul_div_rounding_down(
+            protocol_fee,
+            self.integrator_discounts.get(trader),
+            BPS_DENOMINATOR,
+        );
+        let creator_fee = mul_div_rounding_up(weth_amount, creator_fee_bps, BPS_DENOMINATOR)
+            .min(weth_amount - protocol_fee);
+
+        (protocol_fee - discount, creator_fee)
+    }
+
+    // Smallest trade worth that leaves at least `net_amount` once `trader`'s fees are taken out.
+    // Fees stay under MAX_TOTAL_FEE_BPS, so each step closes most of the remaining gap.
+    fn amount_before_fees(&self, pool_id: B256, trader: Address, net_amount: U256) -> U256 {
+        let mut amount = net_amount;
+        loop {
+            let (protocol_fee, creator_fee) = self.trade_fees(pool_id, trader, amount);
+            let net = amount - protocol_fee - creator_fee;
+            if net >= net_amount {
+                return amount;
+            }
+            amount += net_amount - net;
+        }
+    }
+
+    // Book a trade's fees for later claims and add them to the deployment-wide total. A referrer
+    // other than the trader takes the referral share of the protocol fee.
+    fn accrue_fees(
+        &mut self,
+        pool_id: B256,
+        trader: Address,
+        referrer: Address,
+        protocol_fee: U256,
+        creator_fee: U256,
+    ) {
+        if protocol_fee.is_zero() && creator_fee.is_zero() {
+            return;
+        }
+
+        // Fees of pools on their own base asset are kept apart from the quote token fees, and pay
+        // no referral share
+        let base_asset = self.base_assets.get(pool_id);
+        let own_base_asset = base_asset.configured.get().then(|| base_asset.asset.get());
+
+        if own_base_asset.is_none() {
+            let total_fees_collected = self.total_fees_collected.get();
+            self.total_fees_collected
+                .set(total_fees_collected.saturating_add(protocol_fee + creator_fee));
+        }
+
+        let referral_fee = if referrer.is_zero() || referrer == trader || own_base_asset.is_some() {
+            U256::ZERO
+        } else {
+            protocol_fee * self.referral_share_bps.get() / BPS_DENOMINATOR
+        };
+        let protocol_fee = protocol_fee - referral_fee;
+
+        if !referral_fee.is_zero() {
+            let referral_fees_accrued = self.referral_fees_accrued.get(referrer);
+            self.referral_fees_accrued
+                .setter(referrer)
+                .set(referral_fees_accrued.saturating_add(referral_fee));
+
+            // Emit event - Referral Paid
+            evm::log(ReferralPaid {
+                poolId: pool_id,
+                referrer,
+                trader,
+                amount: referral_fee,
+            });
+        }
+
+        if let Some(asset) = own_base_asset {
+            let base_asset_fees_accrued = self.base_asset_fees_accrued.get(asset);
+            self.base_asset_fees_accrued
+                .setter(asset)
+                .set(base_asset_fees_accrued.saturating_add(protocol_fee));
+        } else {
+            let protocol_fees_accrued = self.protocol_fees_accrued.get();
+            self.protocol_fees_accrued
+                .set(protocol_fees_accrued.saturating_add(protocol_fee));
+        }
+        let creator_fees_accrued = self.creator_fees_accrued.get(pool_id);
+        self.creator_fees_accrued
+            .setter(pool_id)
+            .set(creator_fees_accrued.saturating_add(creator_fee));
+
+        // Emit event - Fees Accrued
+        evm::log(FeesAccrued {
+            poolId: pool_id,
+            trader,
+            protocolFee: protocol_fee,
+            creatorFee: creator_fee,
+        });
+    }
+
+    // Write a pool's fee override and emit the change
+    fn set_fee_override(
+        &mut self,
+        pool_id: B256,
+        enabled: bool,
+        protocol_fee_bps: U256,
+        creator_fee_bps: U256,
+    ) {
+        let mut fee_override = self.fee_overrides.setter(pool_id);
+        let previous_enabled = fee_override.enabled.get();
+        let previous_protocol_fee_bps = fee_override.protocol_fee_bps.get();
+        let previous_creator_fee_bps = fee_override.creator_fee_bps.get();
+        fee_override.enabled.set(enabled);
+        fee_override.protocol_fee_bps.set(protocol_fee_bps);
+        fee_override.creator_fee_bps.set(creator_fee_bps);
+
+        // Emit event - Pool Fees Set
+        evm::log(PoolFeesSet {
+            poolId: pool_id,
+            previousEnabled: previous_enabled,
+            previousProtocolFeeBps: previous_protocol_fee_bps,
+            previousCreatorFeeBps: previous_creator_fee_bps,
+            enabled,
+            protocolFeeBps: protocol_fee_bps,
+            creatorFeeBps: creator_fee_bps,
+        });
+    }
+
+    // Final curve price as sqrtPriceX96 with whether the token is currency0, ordering currencies
+    // by address against the quote token as PoolStateManager does
+    fn graduation_sqrt_price(&self, pool_id: B256) -> Result<(U256, bool), PumpUpErrors> {
+        let (token_address, _, _, _, _, _) = self.get_pool_info(pool_id)?;
+        let quote_token = *self.quote_token;
+        if quote_token.is_zero() {
+            return Err(ZeroAddressQuoteToken {}.into());
+        }
+
+        let token_is_currency0 = token_address < quote_token;
+        let price = self.get_current_price(pool_id)?;
+
+        Ok((
+            sqrt_price_x96_from_price(price, token_is_currency0),
+            token_is_currency0,
+        ))
+    }
+
+    // Quote token fee claims are paid in
+    fn claim_token(&self) -> Result<Address, PumpUpErrors> {
+        let quote_token = *self.quote_token;
+        if quote_token.is_zero() {
+            return Err(ZeroAddressQuoteToken {}.into());
+        }
+        Ok(quote_token)
+    }
+
+    // Asset a pool's fees are paid out in: its configured base asset, else the quote token
+    fn fee_asset(&self, pool_id: B256) -> Result<Address, PumpUpErrors> {
+        let base_asset = self.base_assets.get(pool_id);
+        if base_asset.configured.get() {
+            Ok(base_asset.asset.get())
+        } else {
+            self.claim_token()
+        }
+    }
+
+    // Part of an accrued fee amount this contract holds in `asset` and can pay out now
+    fn claimable(&self, asset: Address, accrued: U256) -> Result<U256, PumpUpErrors> {
+        if accrued.is_zero() {
+            return Ok(U256::ZERO);
+        }
+
+        let balance = if asset == NATIVE_BASE_ASSET {
+            contract::balance()
+        } else {
+            host().balance_of(asset, contract::address())?
+        };
+        Ok(accrued.min(balance))
+    }
+
+    // Pay protocol fees to the fee split payees, or to the owner while no split is set
+    fn pay_protocol_fees(&mut self, asset: Address, amount: U256) -> Result<(), PumpUpErrors> {
+        let payouts = if self.fee_payees.is_empty() {
+            vec![(*self.owner, amount)]
+        } else {
+            self.quote_fee_split(amount)
+        };
+        for (payee, payout) in payouts {
+            if !payout.is_zero() {
+                self.transfer_base_asset(asset, payee, payout)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Send an amount of a base asset: the native currency for the zero address, else an ERC20
+    fn transfer_base_asset(
+        &mut self,
+        asset: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), PumpUpErrors> {
+        if asset != NATIVE_BASE_ASSET {
+            return self.safe_transfer(&asset, to, amount);
+        }
+
+        RawCall::new_with_value(amount)
+            .call(to, &[])
+            .map(|_| ())
+            .map_err(|_| NativeTransferFailed {}.into())
+    }
+
+    // Reject trades and quotes on paused pools
+    fn check_not_paused(&self, pool_id: B256) -> Result<(), PumpUpErrors> {
+        if self.is_paused(pool_id) {
+            return Err(TradingPaused {}.into());
+        }
+        Ok(())
+    }
+
+    // Set a pool's pause flag (owner or pauser)
+    fn set_pool_paused(&mut self, pool_id: B256, paused: bool) -> Result<(), PumpUpErrors> {
+        self.only_role(PAUSER_ROLE)?;
+        self.get_curve_params(pool_id)?;
+
+        let mut paused_setter = self.paused_pools.setter(pool_id);
+        paused_setter.set(paused);
+
+        // Emit event - Pool Pause Set
+        evm::log(PoolPauseSet {
+            poolId: pool_id,
+            paused,
+            account: msg::sender(),
+        });
+
+        Ok(())
+    }
+
+    // Set the global pause flag (owner or pauser)
+    fn set_global_pause(&mut self, paused: bool) -> Result<(), PumpUpErrors> {
+        self.only_role(PAUSER_ROLE)?;
+
+        self.paused.set(paused);
+
+        // Emit event - Global Pause Set
+        evm::log(GlobalPauseSet {
+            paused,
+            account: msg::sender(),
+        });
+
+        Ok(())
+    }
+
+    // Reject trades by addresses blacklisted from a pool
+    fn check_not_blacklisted(&self, pool_id: B256, trader: Address) -> Result<(), PumpUpErrors> {
+        if self.is_blacklisted(pool_id, trader) {
+            return Err(Blacklisted {}.into());
+        }
+        Ok(())
+    }
+
+    // Ask the pool's compliance module, if any, whether a trade is allowed. Fails closed when the
+    // module reverts or returns malformed data.
+    fn check_compliance(
+        &self,
+        pool_id: B256,
+        trader: Address,
+        direction: u8,
+        amount: U256,
+    ) -> Result<(), PumpUpErrors> {
+        let module = self.compliance_modules.get(pool_id);
+        if module.is_zero() {
+            return Ok(());
+        }
+
+        let mut call_data = Vec::with_capacity(132);
+        call_data.extend_from_slice(&[0x4d, 0x9c, 0xe4, 0xae]); // keccak256("isAllowed(bytes32,address,uint8,uint256)")
+        call_data.extend_from_slice(pool_id.as_slice());
+        call_data.extend_from_slice(&[0; 12]);
+        call_data.extend_from_slice(trader.as_slice());
+        call_data.extend_from_slice(&U256::from(direction).to_be_bytes::<32>());
+        call_data.extend_from_slice(&amount.to_be_bytes::<32>());
+
+        let result = RawCall::new_static()
+            .call(module, &call_data)
+            .map_err(|_| -> PumpUpErrors { ComplianceCallFailed {}.into() })?;
+
+        // Parse bool from the result
+        if result.len() < 32 || result[0..31].iter().any(|b| *b != 0) || result[31] > 1 {
+            return Err(ComplianceCallFailed {}.into());
+        }
+        if result[31] == 0 {
+            return Err(ComplianceDenied {}.into());
+        }
+
+        Ok(())
+    }
+
+    // Enforce a pool's launch phase on a buy
+    fn check_launch_phase(
+        &self,
+        pool_id: B256,
+        buyer: Address,
+        weth_amount: U256,
+    ) -> Result<(), PumpUpErrors> {
+        let phase = self.launch_phase(pool_id);
+        if phase == LAUNCH_PHASE_OPEN {
+            return Ok(());
+        }
+
+        if phase == LAUNCH_PHASE_PRE_LAUNCH {
+            return Err(LaunchNotStarted {}.into());
+        }
+
+        // Designated market makers bypass launch-window limits
+        if self.is_market_maker_exempt(pool_id, buyer) {
+            return Ok(());
+        }
+
+        if phase == LAUNCH_PHASE_ALLOWLIST && !self.is_launch_allowlisted(pool_id, buyer) {
+            return Err(NotAllowlisted {}.into());
+        }
+
+        let max_buy_weth = self.launch_schedules.get(pool_id).max_buy_weth.get();
+        if !max_buy_weth.is_zero() && weth_amount > max_buy_weth {
+            return Err(LaunchCapExceeded {}.into());
+        }
+
+        Ok(())
+    }
+
+    // Base asset the insurance reserve adds to a sell so it is not quoted below the pool's floor
+    fn sell_floor_shortfall(
+        &self,
+        pool_id: B256,
+        token_amount: U256,
+        weth_to_return: U256,
+        insurance_reserve: U256,
+    ) -> U256 {
+        let floor_price = self.sell_price_floors.get(pool_id);
+        if floor_price.is_zero() {
+            return U256::ZERO;
+        }
+
+        let floor_value = self.wad_to_base(
+            pool_id,
+            multiply_fixed_point(floor_price, token_amount),
+            Rounding::Down,
+        );
+        if floor_value <= weth_to_return {
+            return U256::ZERO;
+        }
+
+        (floor_value - weth_to_return).min(insurance_reserve)
+    }
+
+    // Calculate sigmoid price
+    fn calculate_sigmoid_price(&self, supply: U256, params: &CurveParameters) -> U256 {
+        saturated(self.try_sigmoid_price::<Saturating>(supply, params))
+    }
+
+    // Price at a supply from the pool's curve, on the given arithmetic
+    fn try_sigmoid_price<A: Arithmetic>(
+        &self,
+        supply: U256,
+        params: &CurveParameters,
+    ) -> Result<U256, MathOp> {
+        match params.curve_kind {
+            CURVE_KIND_VIRTUAL_RESERVES => {
+                return virtual_reserves::try_price_at::<A>(supply, params)
+            }
+            CURVE_KIND_PIECEWISE => return piecewise::try_price_at::<A>(supply, params),
+            CURVE_KIND_LINEAR => return linear::try_price_at::<A>(supply, &params.into()),
+            CURVE_KIND_EXPONENTIAL => {
+                return exponential::try_price_at::<A>(supply, &params.into())
+            }
+            _ => {}
+        }
+
+        sigmoid::try_price_at::<A>(supply, &params.into())
+    }
+
+    // Price change per whole token at a supply, from the pool's curve
+    fn calculate_price_slope(&self, supply: U256, params: &CurveParameters) -> U256 {
+        match params.curve_kind {
+            CURVE_KIND_VIRTUAL_RESERVES => return virtual_reserves::slope_at(supply, params),
+            CURVE_KIND_PIECEWISE => return piecewise::slope_at(supply, params),
+            CURVE_KIND_LINEAR => return linear::slope_at(&params.into()),
+            CURVE_KIND_EXPONENTIAL => return exponential::slope_at(supply, &params.into()),
+            _ => {}
+        }
+
+        sigmoid::slope_at(supply, &params.into())
+    }
+
+    // Closed-form inverse of the sigmoid: with s = (price - a) / R the fraction of the price range
+    // reached, percentage_sold = m + ln(s / (1 - s)) / k
+    fn calculate_supply_at_price(&self, price: U256, params: &CurveParameters) -> U256 {
+        match params.curve_kind {
+            CURVE_KIND_VIRTUAL_RESERVES => return virtual_reserves::supply_at_price(price, params),
+            CURVE_KIND_PIECEWISE => return piecewise::supply_at_price(price, params),
+            CURVE_KIND_LINEAR => return linear::supply_at_price(price, &params.into()),
+            CURVE_KIND_EXPONENTIAL => return exponential::supply_at_price(price, &params.into()),
+            _ => {}
+        }
+
+        let max_price = multiply_fixed_point(params.initial_price, params.max_price_factor);
+        let price_range = max_price.saturating_sub(params.initial_price);
+
+        if price <= params.initial_price || price_range.is_zero() {
+            return U256::ZERO;
+        }
+
+        let range_reached = divide_fixed_point(price - params.initial_price, price_range);
+        if range_reached >= SCALE_FACTOR {
+            return params.total_supply;
+        }
+
+        let range_left = SCALE_FACTOR - range_reached;
+        let percentage_sold = if range_reached >= range_left {
+            let log_odds = ln_fixed_point(divide_fixed_point(range_reached, range_left));
+            params
+                .midpoint
+                .saturating_add(divide_fixed_point(log_odds, params.steepness))
+        } else {
+            let log_odds = ln_fixed_point(divide_fixed_point(range_left, range_reached));
+            params
+                .midpoint
+                .saturating_sub(divide_fixed_point(log_odds, params.steepness))
+        };
+
+        multiply_fixed_point(percentage_sold.min(SCALE_FACTOR), params.total_supply)
+    }
+
+    // Calculate base asset for token amount from the pool's curve, integrating over the supply
+    // range the trade covers. Buys round the cost up and sells round the proceeds down, so trades
+    // never take more base asset out of the pool than they put in.
+    fn calculate_weth_for_token_amount(
+        &self,
+        current_supply: U256,
+        token_amount: U256,
+        params: &CurveParameters,
+        is_selling: bool,
+    ) -> U256 {
+        saturated(self.try_weth_for_token_amount::<Saturating>(
+            current_supply,
+            token_amount,
+            params,
+            is_selling,
+        ))
+    }
+
+    // Base asset for token amount from the pool's curve, on the given arithmetic
+    fn try_weth_for_token_amount<A: Arithmetic>(
+        &self,
+        current_supply: U256,
+        token_amount: U256,
+        params: &CurveParameters,
+        is_selling: bool,
+    ) -> Result<U256, MathOp> {
+        // Supply range covered by the trade
+        let (start_supply, end_supply) = if is_selling {
+            (current_supply.saturating_sub(token_amount), current_supply)
+        } else {
+            (current_supply, A::add(current_supply, token_amount)?)
+        };
+        let token_amount = end_supply - start_supply;
+        let rounding = if is_selling {
+            Rounding::Down
+        } else {
+            Rounding::Up
+        };
+
+        match params.curve_kind {
+            CURVE_KIND_VIRTUAL_RESERVES => {
+                return virtual_reserves::try_cost_between::<A>(
+                    start_supply,
+                    end_supply,
+                    params,
+                    rounding,
+                )
+            }
+            CURVE_KIND_PIECEWISE => {
+                return piecewise::try_cost_between::<A>(start_supply, end_supply, params, rounding)
+            }
+            CURVE_KIND_LINEAR => {
+                return linear::try_cost_between::<A>(
+                    start_supply,
+                    token_amount,
+                    &params.into(),
+                    rounding,
+                )
+            }
+            CURVE_KIND_EXPONENTIAL => {
+                return exponential::try_cost_between::<A>(
+                    start_supply,
+                    token_amount,
+                    &params.into(),
+                    rounding,
+                )
+            }
+            _ => {}
+        }
+
+        sigmoid::try_cost_between::<A>(start_supply, end_supply, &params.into(), rounding)
+    }
+
+    // Price a pool's curve never leaves: the initial price at zero supply up to the price at the
+    // full supply
+    fn curve_price_bounds(&self, params: &CurveParameters) -> (U256, U256) {
+        let max_price = match params.curve_kind {
+            CURVE_KIND_PIECEWISE => piecewise::max_price(params),
+            CURVE_KIND_EXPONENTIAL => exponential::max_price(&params.into()),
+            _ => multiply_fixed_point(
+                params.initial_price,
+                params.max_price_factor.max(SCALE_FACTOR),
+            ),
+        };
+
+        (params.initial_price, max_price)
+    }
+
+    // Reject prices outside the curve's bounds, which only corrupted math produces
+    fn check_price_bounds(
+        &self,
+        price: U256,
+        params: &CurveParameters,
+    ) -> Result<(), PumpUpErrors> {
+        let (min_price, max_price) = self.curve_price_bounds(params);
+        if price < min_price || price > max_price {
+            return Err(InvariantViolated {}.into());
+        }
+
+        Ok(())
+    }
+
+    // Reject a trade quote that breaks the curve's invariants: a resulting price outside the curve's
+    // bounds, or a cost that shrinks as the trade grows (checked against half the trade)
+    fn check_quote_invariants(
+        &self,
+        current_supply: U256,
+        token_amount: U256,
+        weth_amount: U256,
+        new_price: U256,
+        params: &CurveParameters,
+        is_selling: bool,
+    ) -> Result<(), PumpUpErrors> {
+        self.check_price_bounds(new_price, params)?;
+
+        let half_cost = self.calculate_weth_for_token_amount(
+            current_supply,
+            token_amount / TWO,
+            params,
+            is_selling,
         );
+        if half_cost > weth_amount {
+            return Err(InvariantViolated {}.into());
+        }
 
-        // Calculate new price
-        let new_circulating_supply = circulating_supply + tokens_needed;
-        let new_price = self.calculate_sigmoid_price(new_circulating_supply, &params);
-
-        Ok((tokens_needed, new_price))
+        Ok(())
     }
 
-    // Get the contract owner
-    pub fn owner(&self) -> Address {
-        *self.owner
-    }
+    // With checked math on for the pool, evaluate a trade's cost and resulting price on
+    // overflow-checked arithmetic, reverting with the operation that overflowed
+    fn check_curve_math(
+        &self,
+        pool_id: B256,
+        current_supply: U256,
+        token_amount: U256,
+        params: &CurveParameters,
+        is_selling: bool,
+    ) -> Result<(), PumpUpErrors> {
+        if !self.checked_math(pool_id) {
+            return Ok(());
+        }
+
+        let end_supply = if is_selling {
+            current_supply.saturating_sub(token_amount)
+        } else {
+            current_supply.saturating_add(token_amount)
+        };
+
+        self.try_weth_for_token_amount::<Checked>(current_supply, token_amount, params, is_selling)
+            .and_then(|_| self.try_sigmoid_price::<Checked>(end_supply, params))
+            .map_err(|op| MathOverflow { op: op as u8 })?;
 
-    // Set pool state manager (only owner)
-    pub fn set_pool_state_manager(
-        &mut self,
-        new_pool_state_manager: Address,
-    ) -> Result<(), Vec<u8>> {
-        self.only_owner()?;
-        self.pool_state_manager.set(new_pool_state_manager);
         Ok(())
     }
 
-    // Transfer ownership of the contract (only owner)
-    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), Vec<u8>> {
-        self.only_owner()?;
-
-        if new_owner == Address::ZERO {
-            return Err(Vec::<u8>::from("New owner cannot be the zero address"));
+    // Tokens bought for `weth_amount` of the base asset, searching only the cost table segment
+    // around the answer when the pool has a table and the full range otherwise
+    fn find_buy_token_amount(
+        &self,
+        pool_id: B256,
+        current_supply: U256,
+        weth_amount: U256,
+        params: &CurveParameters,
+    ) -> U256 {
+        let weth_amount = self.base_to_wad(pool_id, weth_amount);
+        match self.cost_table_bracket(pool_id, current_supply, weth_amount, params) {
+            Some((min_tokens, max_tokens)) => self.find_token_amount_in_range(
+                current_supply,
+                weth_amount,
+                params,
+                false,
+                min_tokens,
+                max_tokens,
+            ),
+            None => self.find_token_amount_for_weth(current_supply, weth_amount, params, false),
         }
+    }
 
-        let previous_owner = *self.owner;
-        self.owner.set(new_owner);
+    // Token range bracketing a buy of `weth_amount`, located from the cost table with one segment
+    // of margin on each side and confirmed against the exact cost. None if the pool has no
+    // table or the bracket does not hold.
+    fn cost_table_bracket(
+        &self,
+        pool_id: B256,
+        current_supply: U256,
+        weth_amount: U256,
+        params: &CurveParameters,
+    ) -> Option<(U256, U256)> {
+        let table = self.cost_tables.get(pool_id);
+        let breakpoints = table.len();
+        if breakpoints == 0 {
+            return None;
+        }
 
-        // Emit event - Ownership Transferred
-        let mut topics = Vec::new();
-        let sig = [
-            0x8b, 0xe0, 0x07, 0x9c, 0x53, 0x16, 0x59, 0x14, 0x13, 0x44, 0xcd, 0x1f, 0xd0, 0xa4,
-            0xf2, 0x84, 0x19, 0x49, 0x7f, 0x97, 0x22, 0xa3, 0xda, 0xaf, 0xe3, 0xb4, 0x18, 0x6f,
-            0x6b, 0x64, 0x57, 0xe0,
-        ];
-        topics.push(B256::from_slice(&sig));
+        let step = params.total_supply / U256::from(breakpoints);
+        let cumulative_cost = |i: usize| -> U256 {
+            if i == 0 {
+                U256::ZERO
+            } else {
+                table.get(i - 1).unwrap_or_default()
+            }
+        };
 
-        // Pad addresses to 32 bytes for topics
-        let mut prev_owner_bytes = [0u8; 32];
-        prev_owner_bytes[12..32].copy_from_slice(previous_owner.as_slice());
-        topics.push(B256::from_slice(&prev_owner_bytes));
+        // Interpolate the cumulative cost at the current supply
+        let index = (current_supply / step)
+            .min(U256::from(breakpoints - 1))
+            .to::<usize>();
+        let segment_start = step * U256::from(index);
+        let (low_cost, high_cost) = (cumulative_cost(index), cumulative_cost(index + 1));
+        let current_cost = low_cost
+            + (high_cost.saturating_sub(low_cost))
+                .saturating_mul(current_supply.saturating_sub(segment_start))
+                / step;
+        let target_cost = current_cost.saturating_add(weth_amount);
+
+        // First breakpoint whose cumulative cost reaches the target
+        let (mut low, mut high) = (index, breakpoints);
+        while low < high {
+            let mid = (low + high) / 2;
+            if cumulative_cost(mid + 1) < target_cost {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
 
-        let mut new_owner_bytes = [0u8; 32];
-        new_owner_bytes[12..32].copy_from_slice(new_owner.as_slice());
-        topics.push(B256::from_slice(&new_owner_bytes));
+        let max_tokens = params.total_supply.saturating_sub(current_supply);
+        let min_tokens = (step * U256::from(low.saturating_sub(1))).saturating_sub(current_supply);
+        let max_tokens = (step * U256::from(low + 2))
+            .saturating_sub(current_supply)
+            .min(max_tokens);
 
-        evm::raw_log(&topics, &[]);
+        let cost = |tokens: U256| -> U256 {
+            self.calculate_weth_for_token_amount(current_supply, tokens, params, false)
+        };
+        if min_tokens >= max_tokens
+            || cost(min_tokens) > weth_amount
+            || cost(max_tokens) < weth_amount
+        {
+            return None;
+        }
 
-        Ok(())
+        Some((min_tokens, max_tokens))
     }
-}
 
-// Internal functions
-impl SigmoidBondingCurve {
-    // Helper function to get curve parameters from storage
-    fn get_curve_params(&self, pool_id: B256) -> Result<CurveParameters, Vec<u8>> {
-        let initial_price = self.initial_prices.get(pool_id);
+    // Validate, default and store a pool's curve parameters for initialize and reinitialize
+    fn initialize_pool(&mut self, pool_id: B256, params: &[u8]) -> Result<(), PumpUpErrors> {
+        let CurveParameters {
+            initial_price,
+            max_price_factor,
+            steepness,
+            midpoint,
+            total_supply,
+            ..
+        } = decode_curve_params(params)?;
 
-        if initial_price.is_zero() {
-            return Err(Vec::<u8>::from("Invalid Pool ID"));
+        // Validate parameters
+        if total_supply.is_zero() || initial_price.is_zero() {
+            return Err(InvalidParametersZero {}.into());
         }
 
-        Ok(CurveParameters {
-            initial_price,
-            max_price_factor: self.max_price_factors.get(pool_id),
-            steepness: self.steepness_values.get(pool_id),
-            midpoint: self.midpoints.get(pool_id),
-            total_supply: self.total_supplies.get(pool_id),
-        })
-    }
-
-    // Helper functions for ERC20 calls using RawCall
-    fn call_total_supply(&self, token: &Address) -> Result<U256, Vec<u8>> {
-        let selector = vec![0x18, 0x16, 0x0d, 0xdd]; // keccak256("totalSupply()")
+        // Record which fields fall back to defaults
+        let mut defaults_applied = 0u8;
+        if max_price_factor.is_zero() {
+            defaults_applied |= DEFAULT_FIELD_MAX_PRICE_FACTOR;
+        }
+        if steepness.is_zero() {
+            defaults_applied |= DEFAULT_FIELD_STEEPNESS;
+        }
+        if midpoint.is_zero() {
+            defaults_applied |= DEFAULT_FIELD_MIDPOINT;
+        }
 
-        // Use call instead of static_call - just set read_only to true
-        let result = RawCall::new()
-            .call(*token, &selector)
-            .map_err(|_| -> Vec<u8> { "ERC20 call failed".into() })?;
+        // Use default values if not provided
+        let (default_max_price_factor, default_steepness, default_midpoint) =
+            self.get_default_curve_params();
+        let max_price_factor = if max_price_factor.is_zero() {
+            default_max_price_factor
+        } else {
+            max_price_factor
+        };
+        let steepness = if steepness.is_zero() {
+            default_steepness
+        } else {
+            steepness
+        };
+        let midpoint = if midpoint.is_zero() {
+            default_midpoint
+        } else {
+            midpoint
+        };
 
-        // Parse U256 from the result
-        if result.len() < 32 {
-            return Err(Vec::<u8>::from("Invalid result length from ERC20 call"));
+        self.check_param_bounds(&CurveParameters {
+            initial_price,
+            max_price_factor,
+            steepness,
+            midpoint,
+            total_supply,
+            curve_kind: CURVE_KIND_SIGMOID,
+            ..Default::default()
+        })?;
+
+        let steepness = self.clamp_steepness(pool_id, steepness, midpoint);
+
+        // Reinitialized pools keep their pricing model and segments, new ones take the default
+        let previous_params = if self.pool_exists(pool_id) {
+            Some(self.get_curve_params(pool_id)?)
+        } else {
+            None
+        };
+        let params = CurveParameters {
+            initial_price,
+            max_price_factor,
+            steepness,
+            midpoint,
+            total_supply,
+            curve_kind: previous_params.map_or(self.get_default_curve_kind(), |p| p.curve_kind),
+            ..previous_params.unwrap_or_default()
+        };
+        if params.curve_kind == CURVE_KIND_PIECEWISE {
+            piecewise::check_segments(&params)?;
         }
 
-        let mut bytes = [0u8; 32];
-        bytes.copy_from_slice(&result[0..32]);
-        Ok(U256::from_be_bytes::<32>(bytes))
-    }
+        // Track newly seen pools and bind them to the current manager
+        if previous_params.is_none() {
+            if self.deprecated.get() {
+                return Err(StrategyDeprecated {}.into());
+            }
+            self.pool_ids.push(pool_id);
 
-    fn call_balance_of(&self, token: &Address, account: Address) -> Result<U256, Vec<u8>> {
-        // Create call data
-        let mut call_data = Vec::with_capacity(36);
-        // Function selector for balanceOf(address)
-        call_data.extend_from_slice(&[0x70, 0xa0, 0x82, 0x31]); // keccak256("balanceOf(address)")
-                                                                // Pad address to 32 bytes
-        call_data.extend_from_slice(&[0; 12]);
-        call_data.extend_from_slice(account.as_slice());
+            // New pools start with nothing sold and are tracked from the outset
+            let mut account = self.supply_accounts.setter(pool_id);
+            account.circulating_supply.set(U256::ZERO);
+            account.synced.set(true);
+        }
+        let mut pool_manager_setter = self.pool_managers.setter(pool_id);
+        pool_manager_setter.set(*self.pool_state_manager);
+
+        // Read the token's decimals once the manager knows the pool; pools it does not know yet
+        // record them on their first sync
+        if previous_params.is_none() {
+            if let Ok((token_address, _, _, _, _, _)) = self.get_pool_info(pool_id) {
+                self.record_token_decimals(pool_id, token_address);
+            }
+        }
 
-        // Use call instead of static_call - just set read_only to true
-        let result = RawCall::new()
-            .call(*token, &call_data)
-            .map_err(|_| -> Vec<u8> { "ERC20 call failed".into() })?;
+        self.store_curve_params(pool_id, &params);
+
+        if let Some(previous_params) = previous_params {
+            // Emit event - Curve Reinitialized
+            evm::log(CurveReinitialized {
+                poolId: pool_id,
+                initialPrice: initial_price,
+                maxPriceFactor: max_price_factor,
+                steepness,
+                midpoint,
+                totalSupply: total_supply,
+            });
+
+            let params = self.get_curve_params(pool_id)?;
+            self.emit_curve_params_changed(pool_id, &previous_params, &params);
+        } else {
+            // Emit event - Curve Initialized
+            evm::log(CurveInitialized {
+                poolId: pool_id,
+                initialPrice: initial_price,
+                maxPriceFactor: max_price_factor,
+                steepness,
+                midpoint,
+                totalSupply: total_supply,
+            });
+        }
 
-        // Parse U256 from the result
-        if result.len() < 32 {
-            return Err(Vec::<u8>::from("Invalid result length from ERC20 call"));
+        // Emit event - Defaults Applied
+        if defaults_applied != 0 {
+            evm::log(DefaultsApplied {
+                poolId: pool_id,
+                fields: defaults_applied,
+            });
         }
 
-        let mut bytes = [0u8; 32];
-        bytes.copy_from_slice(&result[0..32]);
-        Ok(U256::from_be_bytes::<32>(bytes))
+        Ok(())
     }
 
-    // Get pool info from manager contract
-    fn get_pool_info(
+    // Reject pricing model changes once a pool has sold tokens or transitioned
+    fn check_curve_kind_unlocked(
         &self,
         pool_id: B256,
-    ) -> Result<(Address, Address, U256, U256, bool, B256), Vec<u8>> {
-        // Create call data
-        let mut call_data = Vec::with_capacity(36);
-        // Function selector for getPoolInfo(bytes32)
-        call_data.extend_from_slice(&[0x8e, 0xf3, 0xf2, 0x91]); // keccak256("getPoolInfo(bytes32)")
-                                                                // Pool ID
-        call_data.extend_from_slice(pool_id.as_slice());
-
-        // Use call instead of static_call - just set read_only to true
-        let result = RawCall::new()
-            .call(*self.pool_state_manager, &call_data)
-            .map_err(|_| -> Vec<u8> { "Pool state manager call failed".into() })?;
-
-        // Result should be at least 6 * 32 bytes
-        if result.len() < 192 {
-            return Err(Vec::<u8>::from(
-                "Invalid result length from pool state manager",
-            ));
+        params: &CurveParameters,
+    ) -> Result<(), PumpUpErrors> {
+        let (token_address, _, _, _, is_transitioned, _) = self.get_pool_info(pool_id)?;
+        if is_transitioned
+            || !self
+                .circulating_supply(pool_id, &token_address, params)?
+                .is_zero()
+        {
+            return Err(CurveKindLocked {}.into());
         }
 
-        // Parse the result
-        let token_address = Address::from_slice(&result[12..32]);
-        let creator = Address::from_slice(&result[44..64]);
-
-        let mut weth_bytes = [0u8; 32];
-        weth_bytes.copy_from_slice(&result[64..96]);
-        let weth_collected = U256::from_be_bytes::<32>(weth_bytes);
-
-        let mut price_bytes = [0u8; 32];
-        price_bytes.copy_from_slice(&result[96..128]);
-        let last_price = U256::from_be_bytes::<32>(price_bytes);
+        Ok(())
+    }
 
-        let is_transitioned = !result[127].eq(&0u8);
-        let bonding_curve_strategy = B256::from_slice(&result[128..160]);
+    // Effective bounds of a curve parameter, substituting the compile-time bound for unset ones
+    fn param_bounds_of(&self, parameter: u8) -> Result<(U256, U256), PumpUpErrors> {
+        let (default_min, default_max) = match parameter {
+            CURVE_PARAM_INITIAL_PRICE | CURVE_PARAM_TOTAL_SUPPLY => (U256::from(1u8), U256::MAX),
+            CURVE_PARAM_MAX_PRICE_FACTOR => {
+                (MIN_DEFAULT_MAX_PRICE_FACTOR, MAX_DEFAULT_MAX_PRICE_FACTOR)
+            }
+            CURVE_PARAM_STEEPNESS => (U256::from(1u8), MAX_DEFAULT_STEEPNESS),
+            CURVE_PARAM_MIDPOINT => (U256::from(1u8), SCALE_FACTOR),
+            _ => return Err(InvalidParamBounds {}.into()),
+        };
 
+        let bounds = self.param_bounds.get(U8::from(parameter));
+        let (min, max) = (bounds.min.get(), bounds.max.get());
         Ok((
-            token_address,
-            creator,
-            weth_collected,
-            last_price,
-            is_transitioned,
-            bonding_curve_strategy,
+            if min.is_zero() { default_min } else { min },
+            if max.is_zero() { default_max } else { max },
         ))
     }
 
-    // Calculate sigmoid price
-    fn calculate_sigmoid_price(&self, supply: U256, params: &CurveParameters) -> U256 {
-        if supply.is_zero() {
-            return params.initial_price;
+    // Reject curve parameters outside their bounds
+    fn check_param_bounds(&self, params: &CurveParameters) -> Result<(), PumpUpErrors> {
+        let (min, max) = self.param_bounds_of(CURVE_PARAM_INITIAL_PRICE)?;
+        if params.initial_price < min || params.initial_price > max {
+            return Err(InitialPriceOutOfBounds {
+                value: params.initial_price,
+                min,
+                max,
+            }
+            .into());
         }
 
-        // Calculate percentage sold (normalized to 0-1)
-        let percentage_sold = if params.total_supply.is_zero() {
-            SCALE_FACTOR // 100% if total supply is zero (edge case)
-        } else {
-            // Multiply by SCALE_FACTOR for fixed-point division
-            self.divide_fixed_point(supply.saturating_mul(SCALE_FACTOR), params.total_supply)
-        };
+        let (min, max) = self.param_bounds_of(CURVE_PARAM_MAX_PRICE_FACTOR)?;
+        if params.max_price_factor < min || params.max_price_factor > max {
+            return Err(MaxPriceFactorOutOfBounds {
+                value: params.max_price_factor,
+                min,
+                max,
+            }
+            .into());
+        }
 
-        // Calculate max price from initial price and factor
-        let max_price = self.multiply_fixed_point(params.initial_price, params.max_price_factor);
+        let (min, max) = self.param_bounds_of(CURVE_PARAM_STEEPNESS)?;
+        if params.steepness < min || params.steepness > max {
+            return Err(SteepnessOutOfBounds {
+                value: params.steepness,
+                min,
+                max,
+            }
+            .into());
+        }
 
-        // Calculate price range
-        let price_range = max_price.saturating_sub(params.initial_price);
+        let (min, max) = self.param_bounds_of(CURVE_PARAM_MIDPOINT)?;
+        if params.midpoint < min || params.midpoint > max {
+            return Err(MidpointOutOfBounds {
+                value: params.midpoint,
+                min,
+                max,
+            }
+            .into());
+        }
 
-        // Check if percentage_sold is less than midpoint
-        if percentage_sold < params.midpoint {
-            // Percentage_sold < midpoint case
-            let midpoint_diff = params.midpoint.saturating_sub(percentage_sold);
-            let exponent_term = self.multiply_fixed_point(params.steepness, midpoint_diff);
+        let (min, max) = self.param_bounds_of(CURVE_PARAM_TOTAL_SUPPLY)?;
+        if params.total_supply < min || params.total_supply > max {
+            return Err(TotalSupplyOutOfBounds {
+                value: params.total_supply,
+                min,
+                max,
+            }
+            .into());
+        }
 
-            // Calculate e^(exponent_term) using approximation
-            let exp_value = self.exp_approx(exponent_term);
+        Ok(())
+    }
 
-            // Calculate denominator: 1 + e^(exponent_term)
-            let denominator = SCALE_FACTOR.saturating_add(exp_value);
+    // Clamp steepness so steepness * max(midpoint, 1 - midpoint) stays within MAX_EXP_INPUT
+    fn clamp_steepness(&self, pool_id: B256, steepness: U256, midpoint: U256) -> U256 {
+        let max_midpoint_distance = midpoint.max(SCALE_FACTOR.saturating_sub(midpoint));
+        let max_steepness = divide_fixed_point(MAX_EXP_INPUT, max_midpoint_distance);
+        let clamped_steepness = steepness.min(max_steepness);
+        if clamped_steepness < steepness {
+            // Emit event - Steepness Clamped
+            evm::log(SteepnessClamped {
+                poolId: pool_id,
+                requestedSteepness: steepness,
+                steepness: clamped_steepness,
+            });
+        }
+        clamped_steepness
+    }
 
-            // Calculate final price: initialPrice + priceRange / denominator
-            params
-                .initial_price
-                .saturating_add(self.divide_fixed_point(price_range, denominator))
-        } else {
-            // Percentage_sold >= midpoint case
-            let midpoint_diff = percentage_sold.saturating_sub(params.midpoint);
-            let exponent_term = self.multiply_fixed_point(params.steepness, midpoint_diff);
+    // Write a pool's curve parameters and rebuild its cost table
+    fn store_curve_params(&mut self, pool_id: B256, params: &CurveParameters) {
+        let mut curve_params = self.curve_params.setter(pool_id);
+        curve_params.initial_price.set(params.initial_price);
+        curve_params.max_price_factor.set(params.max_price_factor);
+        curve_params.total_supply.set(params.total_supply);
+        curve_params.steepness.set(U128::from(params.steepness));
+        curve_params
+            .midpoint
+            .set(U128::saturating_from(params.midpoint));
+        curve_params.curve_kind.set(U8::from(params.curve_kind));
+
+        if params.curve_kind == CURVE_KIND_PIECEWISE {
+            let mut segments = self.curve_segments.setter(pool_id);
+            for (i, segment) in params.segments[..params.segment_count].iter().enumerate() {
+                let mut stored = segments.setter(U256::from(i));
+                stored.shape.set(U8::from(segment.shape));
+                stored.end_supply.set(segment.end_supply);
+                stored.end_price.set(segment.end_price);
+                stored
+                    .steepness
+                    .set(U128::saturating_from(segment.steepness));
+                stored.midpoint.set(U128::saturating_from(segment.midpoint));
+            }
+            self.curve_segment_counts
+                .setter(pool_id)
+                .set(U256::from(params.segment_count));
+        }
 
-            // Calculate e^(exponent_term)
-            let exp_value = self.exp_approx(exponent_term);
+        self.store_cost_table(pool_id, params);
+    }
 
-            // Calculate denominator: (exp_value + 1) / exp_value = 1 + 1/exp_value
-            // For numerical stability, use: 1 + exp_value^-1
-            let denominator = if exp_value.is_zero() {
-                // Handle divide by zero - rare case
-                SCALE_FACTOR.saturating_mul(U256::from(1000u64)) // Large number
-            } else {
-                SCALE_FACTOR.saturating_add(self.divide_fixed_point(SCALE_FACTOR, exp_value))
-            };
+    // Store a pool's cumulative buy cost at evenly spaced supply breakpoints
+    fn store_cost_table(&mut self, pool_id: B256, params: &CurveParameters) {
+        let step = params.total_supply / U256::from(COST_TABLE_BREAKPOINTS);
+        let mut cumulative_costs = Vec::new();
+        if !step.is_zero() {
+            let mut cumulative_cost = U256::ZERO;
+            for i in 0..COST_TABLE_BREAKPOINTS {
+                let segment_start = step * U256::from(i);
+                cumulative_cost = cumulative_cost.saturating_add(
+                    self.calculate_weth_for_token_amount(segment_start, step, params, false),
+                );
+                cumulative_costs.push(cumulative_cost);
+            }
+        }
 
-            // Calculate final price
-            params
-                .initial_price
-                .saturating_add(self.divide_fixed_point(price_range, denominator))
+        let mut table = self.cost_tables.setter(pool_id);
+        table.erase();
+        for cumulative_cost in cumulative_costs {
+            table.push(cumulative_cost);
         }
     }
 
-    // Calculate WETH for token amount using trapezoid rule
-    fn calculate_weth_for_token_amount(
+    // Find token amount for base asset by Newton-Raphson with a binary search fallback. The
+    // logistic integral a*x + (R/k)*ln(1 + e^(k(x - m))) used for pricing cannot be inverted in
+    // closed form while the initial price a is non-zero (it needs Lambert W), so this stays
+    // numeric.
+    fn find_token_amount_for_weth(
         &self,
         current_supply: U256,
-        token_amount: U256,
+        weth_amount: U256,
         params: &CurveParameters,
         is_selling: bool,
     ) -> U256 {
-        // Calculate new supply based on operation
-        let new_supply = if is_selling {
-            current_supply.saturating_sub(token_amount)
+        let max_tokens = if is_selling {
+            current_supply // Can't sell more than circulating supply
         } else {
-            current_supply.saturating_add(token_amount)
+            params.total_supply.saturating_sub(current_supply) // Can't buy more than remaining supply
         };
 
-        // Get prices at endpoints
-        let start_price = self.calculate_sigmoid_price(current_supply, params);
-        let end_price = self.calculate_sigmoid_price(new_supply, params);
-
-        // Use trapezoid rule: (start_price + end_price) * token_amount / 2
-        let sum_prices = start_price.saturating_add(end_price);
-        self.multiply_fixed_point(sum_prices, token_amount) / TWO
+        self.find_token_amount_in_range(
+            current_supply,
+            weth_amount,
+            params,
+            is_selling,
+            U256::ZERO,
+            max_tokens,
+        )
     }
 
-    // Find token amount for WETH using binary search
-    fn find_token_amount_for_weth(
+    // Token amount costing `weth_amount` within [min_tokens, max_tokens]. Newton-Raphson steps use
+    // the price as the derivative of the cost and tighten the bracket as they go; if a step leaves
+    // the bracket or does not converge, binary search finishes within the tightened bracket.
+    // Answers are rounded in the pool's favour: a buy never costs more than `weth_amount` and a
+    // sell never returns less, so buying and selling straight back cannot extract value.
+    fn find_token_amount_in_range(
         &self,
         current_supply: U256,
         weth_amount: U256,
         params: &CurveParameters,
         is_selling: bool,
+        mut min_tokens: U256,
+        mut max_tokens: U256,
     ) -> U256 {
-        let mut min_tokens = U256::ZERO;
-        let mut max_tokens;
-
-        if is_selling {
-            max_tokens = current_supply; // Can't sell more than circulating supply
-        } else {
-            max_tokens = params.total_supply.saturating_sub(current_supply); // Can't buy more than remaining supply
+        // Constant product inverts exactly
+        if params.curve_kind == CURVE_KIND_VIRTUAL_RESERVES {
+            return virtual_reserves::tokens_for_weth(
+                current_supply,
+                weth_amount,
+                params,
+                is_selling,
+            )
+            .clamp(min_tokens, max_tokens);
         }
 
-        // Set tolerance for comparison (0.001 * SCALE_FACTOR)
-        let tolerance = SCALE_FACTOR / THOUSAND;
+        // Close enough to `weth_amount`, on the pool's side of it
+        let acceptable = |weth_needed: U256| {
+            let favours_pool = if is_selling {
+                weth_needed >= weth_amount
+            } else {
+                weth_needed <= weth_amount
+            };
+            favours_pool && weth_needed.abs_diff(weth_amount) <= SOLVER_TOLERANCE
+        };
+
+        // Start from the amount the spot price alone would give
+        let spot_price = self.calculate_sigmoid_price(current_supply, params);
+        let mut tokens = divide_fixed_point(weth_amount, spot_price).clamp(min_tokens, max_tokens);
+
+        for _ in 0..NEWTON_MAX_ITERATIONS {
+            let weth_needed =
+                self.calculate_weth_for_token_amount(current_supply, tokens, params, is_selling);
+            if acceptable(weth_needed) {
+                return tokens;
+            }
+
+            // Tighten the bracket around the root
+            if weth_needed < weth_amount {
+                min_tokens = tokens;
+            } else {
+                max_tokens = tokens;
+            }
+
+            // Marginal price at the far end of the trade is the derivative of the cost
+            let end_supply = if is_selling {
+                current_supply.saturating_sub(tokens)
+            } else {
+                current_supply.saturating_add(tokens)
+            };
+            let price = self.calculate_sigmoid_price(end_supply, params);
+            if price.is_zero() {
+                break;
+            }
+
+            let step = divide_fixed_point(weth_needed.abs_diff(weth_amount), price);
+            let next_tokens = if weth_needed < weth_amount {
+                tokens.saturating_add(step)
+            } else {
+                tokens.saturating_sub(step)
+            };
+
+            if next_tokens <= min_tokens || next_tokens >= max_tokens {
+                break;
+            }
+            tokens = next_tokens;
+        }
 
-        // Limit iterations
+        // Fall back to binary search, limiting iterations
         for _ in 0..100 {
-            // Avoid divide by zero
-            if max_tokens == min_tokens {
-                return min_tokens;
+            if max_tokens.saturating_sub(min_tokens) <= U256::from(1u8) {
+                break;
             }
 
             let mid_tokens = min_tokens.saturating_add(max_tokens.saturating_sub(min_tokens) / TWO);
 
-            // Calculate WETH for this many tokens
+            // Calculate base asset for this many tokens
             let weth_needed = self.calculate_weth_for_token_amount(
                 current_supply,
                 mid_tokens,
@@ -714,14 +6815,7 @@ impl SigmoidBondingCurve {
                 is_selling,
             );
 
-            // Check if we're close enough
-            let diff = if weth_needed >= weth_amount {
-                weth_needed.saturating_sub(weth_amount)
-            } else {
-                weth_amount.saturating_sub(weth_needed)
-            };
-
-            if diff <= tolerance {
+            if acceptable(weth_needed) {
                 return mid_tokens;
             }
 
@@ -733,91 +6827,253 @@ impl SigmoidBondingCurve {
             }
         }
 
-        // Return best approximation
-        min_tokens
+        // Return the bracket end on the pool's side of the root
+        if is_selling {
+            max_tokens
+        } else {
+            min_tokens
+        }
     }
 
-    // Approximate exponential function using Taylor series
-    fn exp_approx(&self, x: U256) -> U256 {
-        // Handle the base case
-        if x.is_zero() {
-            return SCALE_FACTOR; // e^0 = 1
+    // Check a pool may graduate early, returning its base asset collected
+    fn check_early_graduation_allowed(&self, pool_id: B256) -> Result<U256, PumpUpErrors> {
+        let (_, _, weth_collected, _, is_transitioned, _) = self.get_pool_info(pool_id)?;
+
+        if is_transitioned {
+            return Err(PoolTransitioned {}.into());
         }
 
-        // For large values, return a large number to avoid overflow
-        // This is a simplification - in a real implementation, you'd use a better approximation
-        if x > U256::from(50u64).saturating_mul(SCALE_FACTOR) {
-            return U256::MAX / TWO; // Very large number
+        if self.transition_pending.get(pool_id) {
+            return Err(TransitionPending {}.into());
         }
 
-        let mut result = SCALE_FACTOR; // 1.0
-        let mut term = SCALE_FACTOR; // Current term in series
-        let mut factorial = U256::from(1u64);
+        if weth_collected < self.early_graduation_min_weth.get() {
+            return Err(BelowEarlyGraduationMinimum {}.into());
+        }
 
-        // Use Taylor series: 1 + x + x²/2! + x³/3! + ...
-        for i in 1..15u64 {
-            // Limit terms for performance
-            factorial = factorial.saturating_mul(U256::from(i));
+        Ok(weth_collected)
+    }
 
-            // Calculate next term: x^i / i!
-            // For numerical stability, we divide term by i at each step
-            term = self.multiply_fixed_point(term, x) / U256::from(i);
+    // Check if caller may edit a pool's blacklist: the guardian, the owner, or the pool's creator
+    // while its launch phases are running
+    fn only_blacklist_admin(&self, pool_id: B256) -> Result<(), PumpUpErrors> {
+        self.get_curve_params(pool_id)?;
 
-            // Add to result
-            result = result.saturating_add(term);
+        let sender = msg::sender();
+        if sender == *self.owner || (!sender.is_zero() && sender == self.guardian.get()) {
+            return Ok(());
+        }
 
-            // Early termination if term becomes very small
-            if term < SCALE_FACTOR / MILLION {
-                break;
+        if self.launch_phase(pool_id) != LAUNCH_PHASE_OPEN {
+            let (_, creator, _, _, _, _) = self.get_pool_info(pool_id)?;
+            if sender == creator {
+                return Ok(());
             }
         }
 
-        result
+        Err(NotGuardian {}.into())
     }
 
-    // Check if caller is the owner
-    fn only_owner(&self) -> Result<(), Vec<u8>> {
-        if msg::sender() != *self.owner {
-            return Err(Vec::<u8>::from("Ownable: caller is not the owner"));
+    // Check if caller is the pool's creator (per the manager)
+    fn only_creator(&self, pool_id: B256) -> Result<(), PumpUpErrors> {
+        self.get_curve_params(pool_id)?;
+
+        let (
+            _token_address,
+            creator,
+            _weth_collected,
+            _last_price,
+            _is_transitioned,
+            _bonding_curve_strategy,
+        ) = self.get_pool_info(pool_id)?;
+
+        if msg::sender() != creator {
+            return Err(NotPoolCreator {}.into());
         }
         Ok(())
     }
 
-    // Fixed point math helper functions
-    fn multiply_fixed_point(&self, a: U256, b: U256) -> U256 {
-        // To avoid overflow: (a * b) / SCALE_FACTOR
-        // This implementation assumes a and b are already scaled by SCALE_FACTOR
-        if a.is_zero() || b.is_zero() {
-            return U256::ZERO;
+    // Check if caller is the pool's creator (per the manager) or the owner
+    fn only_creator_or_owner(&self, pool_id: B256) -> Result<(), PumpUpErrors> {
+        self.get_curve_params(pool_id)?;
+
+        let sender = msg::sender();
+        if sender == *self.owner {
+            return Ok(());
         }
 
-        // Check if the multiplication would overflow
-        if a > U256::MAX / b {
-            return U256::MAX; // Return max on overflow
+        let (
+            _token_address,
+            creator,
+            _weth_collected,
+            _last_price,
+            _is_transitioned,
+            _bonding_curve_strategy,
+        ) = self.get_pool_info(pool_id)?;
+
+        if sender != creator {
+            return Err(NotCreatorOrOwner {}.into());
         }
+        Ok(())
+    }
+
+    // Check the fixed-point helpers and curve against known values
+    fn math_sanity_holds(&self) -> bool {
+        let params = CurveParameters {
+            initial_price: SCALE_FACTOR,
+            max_price_factor: DEFAULT_MAX_PRICE_FACTOR,
+            steepness: DEFAULT_STEEPNESS,
+            midpoint: DEFAULT_MIDPOINT,
+            total_supply: MILLION * SCALE_FACTOR,
+            curve_kind: CURVE_KIND_SIGMOID,
+            ..Default::default()
+        };
+        let max_price = multiply_fixed_point(params.initial_price, params.max_price_factor);
+        let midpoint_supply = multiply_fixed_point(params.total_supply, params.midpoint);
+
+        // Prices start at the initial price, never fall as supply grows and stay below the max
+        let start_price = self.calculate_sigmoid_price(U256::ZERO, &params);
+        let midpoint_price = self.calculate_sigmoid_price(midpoint_supply, &params);
+        let end_price = self.calculate_sigmoid_price(params.total_supply, &params);
+
+        exp_approx(U256::ZERO) == SCALE_FACTOR
+            && multiply_fixed_point(SCALE_FACTOR, TWO * SCALE_FACTOR) == TWO * SCALE_FACTOR
+            && divide_fixed_point(SCALE_FACTOR, TWO * SCALE_FACTOR) == SCALE_FACTOR / TWO
+            && start_price == params.initial_price
+            && start_price <= midpoint_price
+            && midpoint_price <= end_price
+            && end_price <= max_price
+    }
 
-        a.saturating_mul(b) / SCALE_FACTOR
+    // Whether an account is the owner or was granted a role
+    fn holds_role(&self, role: B256, account: Address) -> bool {
+        account == *self.owner || self.role_members.get(role).get(account)
     }
 
-    fn divide_fixed_point(&self, a: U256, b: U256) -> U256 {
-        // To maintain precision: (a * SCALE_FACTOR) / b
-        if b.is_zero() {
-            return U256::ZERO; // Return 0 for division by zero
+    // Check if caller is the owner or holds a role
+    fn only_role(&self, role: B256) -> Result<(), PumpUpErrors> {
+        let sender = msg::sender();
+        if !self.holds_role(role, sender) {
+            return Err(MissingRole {
+                role,
+                account: sender,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    // Check if caller is the owner
+    fn only_owner(&self) -> Result<(), PumpUpErrors> {
+        if msg::sender() != *self.owner {
+            return Err(NotOwner {}.into());
         }
+        Ok(())
+    }
+}
 
-        // Check if the multiplication would overflow
-        if a > U256::MAX / SCALE_FACTOR {
-            return a / b * SCALE_FACTOR; // Alternative calculation to avoid overflow
+// Reject trades submitted with a deadline the block timestamp has passed (zero means none)
+fn check_deadline(deadline: u64) -> Result<(), PumpUpErrors> {
+    let timestamp = block::timestamp();
+    if deadline != 0 && timestamp > deadline {
+        return Err(Expired {
+            deadline,
+            timestamp,
         }
+        .into());
+    }
+    Ok(())
+}
+
+// Amount with `from` decimals rescaled to `to` decimals, rounded in the given direction
+fn scale_decimals(amount: U256, from: u8, to: u8, rounding: Rounding) -> U256 {
+    if from <= to {
+        return amount.saturating_mul(U256::from(10u8).pow(U256::from(to - from)));
+    }
+
+    let divisor = U256::from(10u8).pow(U256::from(from - to));
+    match rounding {
+        Rounding::Down => amount / divisor,
+        Rounding::Up => amount.div_ceil(divisor),
+    }
+}
+
+// Base asset per 10^18 tokens of a trade (zero when no tokens change hands)
+fn average_price(weth_amount: U256, token_amount: U256) -> U256 {
+    if token_amount.is_zero() {
+        return U256::ZERO;
+    }
+    weth_amount.saturating_mul(SCALE_FACTOR) / token_amount
+}
+
+// Distance in bps between the spot price and a trade's average price
+fn price_impact_bps(spot_price: U256, average_price: U256) -> U256 {
+    if spot_price.is_zero() {
+        return U256::ZERO;
+    }
+    spot_price
+        .abs_diff(average_price)
+        .saturating_mul(BPS_DENOMINATOR)
+        / spot_price
+}
+
+// Whether a pricing model can be chosen directly; piecewise curves need their segments
+fn is_selectable_curve_kind(curve_kind: u8) -> bool {
+    curve_kind != CURVE_KIND_PIECEWISE
+        && REGISTERED_STRATEGIES
+            .iter()
+            .any(|(registered, _)| *registered == curve_kind)
+}
+
+// Uniswap v4 pool id of a pool key, matching PoolIdLibrary.toId
+fn pool_id_from_key(key: &PoolKey) -> B256 {
+    crypto::keccak(PoolKey::abi_encode(key))
+}
+
+// Decode initialize's params as an abi.encode'd CurveParams struct
+#[cfg(not(feature = "legacy-packed-params"))]
+fn decode_curve_params(data: &[u8]) -> Result<CurveParameters, PumpUpErrors> {
+    if data.len() != CurveParams::ENCODED_SIZE.unwrap_or_default() {
+        return Err(InvalidParametersLength {}.into());
+    }
+
+    let params = <CurveParams as SolType>::abi_decode_params(data, true)
+        .map_err(|_| -> PumpUpErrors { MalformedCurveParams {}.into() })?;
+
+    Ok(CurveParameters {
+        initial_price: params.initialPrice,
+        max_price_factor: params.maxPriceFactor,
+        steepness: params.steepness,
+        midpoint: params.midpoint,
+        total_supply: params.totalSupply,
+        curve_kind: CURVE_KIND_SIGMOID,
+        ..Default::default()
+    })
+}
 
-        a.saturating_mul(SCALE_FACTOR) / b
+// Decode initialize's params as 5 U256 values packed in sequence, ignoring trailing bytes
+#[cfg(feature = "legacy-packed-params")]
+fn decode_curve_params(data: &[u8]) -> Result<CurveParameters, PumpUpErrors> {
+    if data.len() < 160 {
+        // 5 * 32 bytes
+        return Err(InvalidParametersLength {}.into());
     }
+
+    Ok(CurveParameters {
+        initial_price: extract_u256_from_bytes(data, 0)?,
+        max_price_factor: extract_u256_from_bytes(data, 32)?,
+        steepness: extract_u256_from_bytes(data, 64)?,
+        midpoint: extract_u256_from_bytes(data, 96)?,
+        total_supply: extract_u256_from_bytes(data, 128)?,
+        curve_kind: CURVE_KIND_SIGMOID,
+        ..Default::default()
+    })
 }
 
 // Helper function to extract U256 from byte array
-fn extract_u256_from_bytes(data: &[u8], offset: usize) -> Result<U256, Vec<u8>> {
+fn extract_u256_from_bytes(data: &[u8], offset: usize) -> Result<U256, PumpUpErrors> {
     if data.len() < offset + 32 {
-        return Err(Vec::<u8>::from("Insufficient data length"));
+        return Err(InsufficientDataLength {}.into());
     }
 
     let mut bytes = [0u8; 32];
@@ -0,0 +1,435 @@
+//!
+//! Linear Bonding Curve for Arbitrum Stylus
+//!
+//! Price rises linearly from the initial price at zero supply to
+//! initial_price * max_price_factor once the full supply is sold.
+//!
+//! The curve math is shared with the sigmoid entrypoint, which can host linear pools itself.
+//!
+
+use alloc::string::String;
+use alloy_primitives::{Address, B256, U256};
+use pumpup_strategy::BondingCurveStrategy;
+use stylus_sdk::{abi::Bytes, evm, msg, prelude::*};
+
+use crate::host::{host, HostCalls};
+use crate::{
+    extract_u256_from_bytes, CurveParameters, InconsistentTokenState, InsufficientLiquidity,
+    InvalidAmount, InvalidParametersLength, InvalidParametersZero, InvalidPoolId,
+    NotPoolStateManager, PoolTransitioned, PumpUpErrors, TokensPurchased, TokensSold,
+    DEFAULT_MAX_PRICE_FACTOR, SCALE_FACTOR, STRATEGY_TYPE, TWO,
+};
+use pumpup_math::arithmetic::{saturated, Arithmetic, MathOp, Saturating};
+use pumpup_math::{divide_fixed_point, mul_div_rounding_down, mul_div_rounding_up, sqrt, Rounding};
+
+pub(crate) const LINEAR_STRATEGY_NAME: &str = "Linear";
+
+// Curve parameters for a linear pool
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct LinearParameters {
+    initial_price: U256,
+    max_price_factor: U256,
+    total_supply: U256,
+}
+
+// Linear pools hosted by the sigmoid entrypoint share its parameter layout
+impl From<&CurveParameters> for LinearParameters {
+    fn from(params: &CurveParameters) -> Self {
+        LinearParameters {
+            initial_price: params.initial_price,
+            max_price_factor: params.max_price_factor.max(SCALE_FACTOR),
+            total_supply: params.total_supply,
+        }
+    }
+}
+
+sol_storage! {
+    #[cfg_attr(feature = "linear", entrypoint)]
+    pub struct LinearBondingCurve {
+        // Admin management
+        address owner;
+
+        // Pool state manager
+        address pool_state_manager;
+
+        // Curve parameters for each pool
+        mapping(bytes32 => uint256) initial_prices;
+        mapping(bytes32 => uint256) max_price_factors;
+        mapping(bytes32 => uint256) total_supplies;
+    }
+}
+
+#[public]
+impl LinearBondingCurve {
+    pub fn constructor(&mut self, pool_state_manager: Address) {
+        self.owner.set(msg::sender());
+        self.pool_state_manager.set(pool_state_manager);
+    }
+
+    // Strategy type identifier
+    pub fn strategy_type(&self) -> String {
+        STRATEGY_TYPE.into()
+    }
+
+    // Strategy name
+    pub fn name(&self) -> String {
+        LINEAR_STRATEGY_NAME.into()
+    }
+
+    // Initialize the strategy for a new pool
+    pub fn initialize(&mut self, pool_id: B256, params: Bytes) -> Result<(), PumpUpErrors> {
+        // Only pool state manager can initialize
+        if msg::sender() != *self.pool_state_manager {
+            return Err(NotPoolStateManager {}.into());
+        }
+
+        let params_bytes = params.0;
+
+        // Parse parameters - 3 U256 values packed in sequence
+        if params_bytes.len() < 96 {
+            return Err(InvalidParametersLength {}.into());
+        }
+
+        let initial_price = extract_u256_from_bytes(&params_bytes, 0)?;
+        let max_price_factor = extract_u256_from_bytes(&params_bytes, 32)?;
+        let total_supply = extract_u256_from_bytes(&params_bytes, 64)?;
+
+        if total_supply.is_zero() || initial_price.is_zero() {
+            return Err(InvalidParametersZero {}.into());
+        }
+
+        // Use the default factor if not provided; a factor below 1.0 would make the curve fall
+        let max_price_factor = if max_price_factor.is_zero() {
+            DEFAULT_MAX_PRICE_FACTOR
+        } else {
+            max_price_factor.max(SCALE_FACTOR)
+        };
+
+        self.initial_prices.setter(pool_id).set(initial_price);
+        self.max_price_factors.setter(pool_id).set(max_price_factor);
+        self.total_supplies.setter(pool_id).set(total_supply);
+
+        Ok(())
+    }
+
+    // Calculate tokens received for a WETH amount (only pool state manager). Takes the trader
+    // like the sigmoid entrypoint so the manager calls every curve the same way.
+    pub fn calculate_buy(
+        &mut self,
+        pool_id: B256,
+        _trader: Address,
+        weth_amount: U256,
+    ) -> Result<(U256, U256), PumpUpErrors> {
+        if msg::sender() != *self.pool_state_manager {
+            return Err(NotPoolStateManager {}.into());
+        }
+
+        let (token_amount, new_price) =
+            BondingCurveStrategy::quote_buy(self, pool_id, weth_amount)?;
+
+        // Emit event - Tokens Purchased
+        evm::log(TokensPurchased {
+            poolId: pool_id,
+            baseAmount: weth_amount,
+            tokenAmount: token_amount,
+            newPrice: new_price,
+        });
+
+        Ok((token_amount, new_price))
+    }
+
+    // Calculate WETH returned for a token amount (only pool state manager)
+    pub fn calculate_sell(
+        &mut self,
+        pool_id: B256,
+        _trader: Address,
+        token_amount: U256,
+    ) -> Result<(U256, U256), PumpUpErrors> {
+        if msg::sender() != *self.pool_state_manager {
+            return Err(NotPoolStateManager {}.into());
+        }
+
+        let (weth_to_return, new_price) =
+            BondingCurveStrategy::quote_sell(self, pool_id, token_amount)?;
+
+        // Emit event - Tokens Sold
+        evm::log(TokensSold {
+            poolId: pool_id,
+            tokenAmount: token_amount,
+            baseAmount: weth_to_return,
+            newPrice: new_price,
+        });
+
+        Ok((weth_to_return, new_price))
+    }
+
+    // Get current token price
+    pub fn get_current_price(&self, pool_id: B256) -> Result<U256, PumpUpErrors> {
+        let (token_address, _creator, _weth_collected, last_price, is_transitioned, _strategy) =
+            host().pool_info(*self.pool_state_manager, pool_id)?;
+
+        if is_transitioned {
+            return Ok(last_price);
+        }
+
+        let params = self.get_params(pool_id)?;
+        let circulating_supply = self.circulating_supply(&token_address, &params)?;
+
+        Ok(price_at(circulating_supply, &params))
+    }
+
+    // Curve parameters for a pool as (initial_price, max_price_factor, total_supply)
+    pub fn get_curve_params(&self, pool_id: B256) -> Result<(U256, U256, U256), PumpUpErrors> {
+        let params = self.get_params(pool_id)?;
+        Ok((
+            params.initial_price,
+            params.max_price_factor,
+            params.total_supply,
+        ))
+    }
+
+    pub fn owner(&self) -> Address {
+        *self.owner
+    }
+}
+
+// Shared strategy interface; calculate_buy and calculate_sell add events on top of the quotes
+impl BondingCurveStrategy for LinearBondingCurve {
+    type Error = PumpUpErrors;
+
+    fn initialize(&mut self, pool_id: B256, params: &[u8]) -> Result<(), PumpUpErrors> {
+        LinearBondingCurve::initialize(self, pool_id, Bytes(params.to_vec()))
+    }
+
+    fn quote_buy(&self, pool_id: B256, weth_amount: U256) -> Result<(U256, U256), PumpUpErrors> {
+        let (token_address, _creator, _weth_collected, _last_price, is_transitioned, _strategy) =
+            host().pool_info(*self.pool_state_manager, pool_id)?;
+
+        if is_transitioned {
+            return Err(PoolTransitioned {}.into());
+        }
+
+        if weth_amount.is_zero() {
+            return Err(InvalidAmount {}.into());
+        }
+
+        let params = self.get_params(pool_id)?;
+        let circulating_supply = self.circulating_supply(&token_address, &params)?;
+
+        let token_amount = tokens_for_weth(circulating_supply, weth_amount, &params);
+        if token_amount.is_zero() {
+            return Err(InsufficientLiquidity {
+                available: U256::ZERO,
+                requested: weth_amount,
+            }
+            .into());
+        }
+
+        let new_price = price_at(circulating_supply + token_amount, &params);
+
+        Ok((token_amount, new_price))
+    }
+
+    fn quote_sell(&self, pool_id: B256, token_amount: U256) -> Result<(U256, U256), PumpUpErrors> {
+        let (token_address, _creator, weth_collected, _last_price, is_transitioned, _strategy) =
+            host().pool_info(*self.pool_state_manager, pool_id)?;
+
+        if is_transitioned {
+            return Err(PoolTransitioned {}.into());
+        }
+
+        if token_amount.is_zero() {
+            return Err(InvalidAmount {}.into());
+        }
+
+        let params = self.get_params(pool_id)?;
+        let circulating_supply = self.circulating_supply(&token_address, &params)?;
+
+        if token_amount > circulating_supply {
+            return Err(InvalidAmount {}.into());
+        }
+
+        let new_circulating_supply = circulating_supply - token_amount;
+        let weth_to_return = cost_between(
+            new_circulating_supply,
+            token_amount,
+            &params,
+            Rounding::Down,
+        );
+
+        if weth_to_return > weth_collected {
+            return Err(InsufficientLiquidity {
+                available: weth_collected,
+                requested: weth_to_return,
+            }
+            .into());
+        }
+
+        let new_price = price_at(new_circulating_supply, &params);
+
+        Ok((weth_to_return, new_price))
+    }
+
+    fn spot_price(&self, pool_id: B256) -> Result<U256, PumpUpErrors> {
+        self.get_current_price(pool_id)
+    }
+}
+
+// Internal functions
+impl LinearBondingCurve {
+    fn get_params(&self, pool_id: B256) -> Result<LinearParameters, PumpUpErrors> {
+        let initial_price = self.initial_prices.get(pool_id);
+
+        if initial_price.is_zero() {
+            return Err(InvalidPoolId {}.into());
+        }
+
+        Ok(LinearParameters {
+            initial_price,
+            max_price_factor: self.max_price_factors.get(pool_id),
+            total_supply: self.total_supplies.get(pool_id),
+        })
+    }
+
+    // Circulating supply (total supply minus the manager's balance), rejecting token reads that
+    // contradict the pool's stored total supply
+    fn circulating_supply(
+        &self,
+        token_address: &Address,
+        params: &LinearParameters,
+    ) -> Result<U256, PumpUpErrors> {
+        let total_token_supply = host().total_supply(*token_address)?;
+        let held_by_manager = host().balance_of(*token_address, *self.pool_state_manager)?;
+
+        if total_token_supply.is_zero() || held_by_manager > total_token_supply {
+            return Err(InconsistentTokenState {}.into());
+        }
+
+        let circulating_supply = total_token_supply - held_by_manager;
+        if circulating_supply > params.total_supply {
+            return Err(InconsistentTokenState {}.into());
+        }
+
+        Ok(circulating_supply)
+    }
+}
+
+// Price range covered by the curve (max price minus initial price)
+fn price_range(params: &LinearParameters) -> U256 {
+    saturated(try_price_range::<Saturating>(params))
+}
+
+// price_range on the given arithmetic
+fn try_price_range<A: Arithmetic>(params: &LinearParameters) -> Result<U256, MathOp> {
+    Ok(
+        A::multiply_fixed_point(params.initial_price, params.max_price_factor)?
+            .saturating_sub(params.initial_price),
+    )
+}
+
+// Price at a given supply: initial_price + price_range * supply / total_supply
+pub(crate) fn price_at(supply: U256, params: &LinearParameters) -> U256 {
+    saturated(try_price_at::<Saturating>(supply, params))
+}
+
+// price_at on the given arithmetic
+pub(crate) fn try_price_at<A: Arithmetic>(
+    supply: U256,
+    params: &LinearParameters,
+) -> Result<U256, MathOp> {
+    let supply = supply.min(params.total_supply);
+    A::add(
+        params.initial_price,
+        A::mul(try_price_range::<A>(params)?, supply)? / params.total_supply,
+    )
+}
+
+// Price change per whole token, the same everywhere on a line: price_range / total_supply
+pub(crate) fn slope_at(params: &LinearParameters) -> U256 {
+    divide_fixed_point(price_range(params), params.total_supply)
+}
+
+// Supply at which the price reaches `price`: (price - initial_price) * total_supply / price_range
+pub(crate) fn supply_at_price(price: U256, params: &LinearParameters) -> U256 {
+    let price_range = price_range(params);
+    if price <= params.initial_price || price_range.is_zero() {
+        return U256::ZERO;
+    }
+
+    ((price - params.initial_price).saturating_mul(params.total_supply) / price_range)
+        .min(params.total_supply)
+}
+
+// WETH cost of token_amount tokens starting at supply, rounded in the given direction:
+// initial_price * t + price_range * (end^2 - start^2) / (2 * total_supply), with the supply
+// range clamped to the curve and tokens past its end at the max price. The squares are exact, so
+// costs of consecutive trades add up to the cost of the whole range before rounding.
+pub(crate) fn cost_between(
+    supply: U256,
+    token_amount: U256,
+    params: &LinearParameters,
+    rounding: Rounding,
+) -> U256 {
+    saturated(try_cost_between::<Saturating>(
+        supply,
+        token_amount,
+        params,
+        rounding,
+    ))
+}
+
+// cost_between on the given arithmetic
+pub(crate) fn try_cost_between<A: Arithmetic>(
+    supply: U256,
+    token_amount: U256,
+    params: &LinearParameters,
+    rounding: Rounding,
+) -> Result<U256, MathOp> {
+    let end_supply = A::add(supply, token_amount)?;
+    let (curve_start, curve_end) = (
+        supply.min(params.total_supply),
+        end_supply.min(params.total_supply),
+    );
+    let price_range = try_price_range::<A>(params)?;
+
+    // end^2 - start^2 = (end - start) * (end + start)
+    let squares = A::mul(curve_end - curve_start, curve_end + curve_start)?;
+    let past_end = end_supply - supply - (curve_end - curve_start);
+
+    let curve_cost = A::add(
+        A::mul_div(params.initial_price, token_amount, SCALE_FACTOR, rounding)?,
+        A::mul_div(
+            price_range,
+            squares,
+            A::mul(params.total_supply, TWO * SCALE_FACTOR)?,
+            rounding,
+        )?,
+    )?;
+    A::add(
+        curve_cost,
+        A::mul_div(price_range, past_end, SCALE_FACTOR, rounding)?,
+    )
+}
+
+// Tokens bought for weth_amount starting at supply, solving the cost quadratic in closed form:
+// t = sqrt(u^2 + 2 * W * T / R) - u with u = p * T / R (all amounts scaled by 10^18). Rounding
+// u up and the rest down keeps t at or below the exact answer.
+fn tokens_for_weth(supply: U256, weth_amount: U256, params: &LinearParameters) -> U256 {
+    let remaining = params.total_supply.saturating_sub(supply);
+    let price = price_at(supply, params);
+    let price_range = price_range(params);
+
+    let token_amount = if price_range.is_zero() {
+        divide_fixed_point(weth_amount, price)
+    } else {
+        let u = mul_div_rounding_up(price, params.total_supply, price_range);
+        let discriminant = u.saturating_mul(u).saturating_add(mul_div_rounding_down(
+            weth_amount.saturating_mul(TWO * SCALE_FACTOR),
+            params.total_supply,
+            price_range,
+        ));
+        sqrt(discriminant).saturating_sub(u)
+    };
+
+    token_amount.min(remaining)
+}
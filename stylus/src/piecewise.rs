@@ -0,0 +1,351 @@
+// Piecewise curves built from consecutive segments, each flat, linear or sigmoid.
+//
+// Segment i runs from the end of segment i - 1 (zero supply at the initial price for the first)
+// to its own end supply and end price, so the price is continuous at every boundary. Costs
+// integrate each segment's closed form over the part of the trade it covers.
+
+use alloy_primitives::U256;
+
+use crate::{
+    CurveParameters, CurveSegment, InvalidCurveSegments, PumpUpErrors, MAX_CURVE_SEGMENTS,
+    MAX_DEFAULT_STEEPNESS, SCALE_FACTOR, TWO,
+};
+use pumpup_math::arithmetic::{saturated, Arithmetic, MathOp, Saturating};
+use pumpup_math::{divide_fixed_point, exp_approx, ln_fixed_point, multiply_fixed_point, Rounding};
+
+// Segment shapes
+pub(crate) const SEGMENT_SHAPE_FLAT: u8 = 0;
+pub(crate) const SEGMENT_SHAPE_LINEAR: u8 = 1;
+pub(crate) const SEGMENT_SHAPE_SIGMOID: u8 = 2;
+
+// Reject segments that do not tile [0, total_supply] with a non-decreasing price
+pub(crate) fn check_segments(params: &CurveParameters) -> Result<(), PumpUpErrors> {
+    if params.segment_count == 0 || params.segment_count > MAX_CURVE_SEGMENTS {
+        return Err(InvalidCurveSegments {}.into());
+    }
+
+    for (i, segment) in segments(params).iter().enumerate() {
+        let (start_supply, start_price) = segment_start(params, i);
+        let shape_valid = match segment.shape {
+            SEGMENT_SHAPE_FLAT => segment.end_price == start_price,
+            SEGMENT_SHAPE_LINEAR => true,
+            SEGMENT_SHAPE_SIGMOID => {
+                !segment.steepness.is_zero()
+                    && segment.steepness <= MAX_DEFAULT_STEEPNESS
+                    && !segment.midpoint.is_zero()
+                    && segment.midpoint < SCALE_FACTOR
+                    && logistic_bounds(segment).0 < logistic_bounds(segment).1
+            }
+            _ => false,
+        };
+
+        if !shape_valid || segment.end_supply <= start_supply || segment.end_price < start_price {
+            return Err(InvalidCurveSegments {}.into());
+        }
+    }
+
+    if segments(params)[params.segment_count - 1].end_supply != params.total_supply {
+        return Err(InvalidCurveSegments {}.into());
+    }
+
+    Ok(())
+}
+
+// Spot price at a given supply
+pub(crate) fn try_price_at<A: Arithmetic>(
+    supply: U256,
+    params: &CurveParameters,
+) -> Result<U256, MathOp> {
+    for (i, segment) in segments(params).iter().enumerate() {
+        if supply <= segment.end_supply {
+            let (start_supply, start_price) = segment_start(params, i);
+            return try_segment_price::<A>(segment, start_supply, start_price, supply);
+        }
+    }
+
+    Ok(max_price(params))
+}
+
+// Price at the end of the last segment, the highest on the curve
+pub(crate) fn max_price(params: &CurveParameters) -> U256 {
+    segments(params)
+        .last()
+        .map_or(params.initial_price, |segment| segment.end_price)
+}
+
+// Price change per whole token at a given supply, taken from the segment the next token is sold
+// in (zero past the last segment)
+pub(crate) fn slope_at(supply: U256, params: &CurveParameters) -> U256 {
+    for (i, segment) in segments(params).iter().enumerate() {
+        if supply < segment.end_supply {
+            let (start_supply, start_price) = segment_start(params, i);
+            return segment_slope(segment, start_supply, start_price, supply);
+        }
+    }
+
+    U256::ZERO
+}
+
+// WETH between two supplies, summing each segment's share of the range rounded in the given
+// direction
+pub(crate) fn try_cost_between<A: Arithmetic>(
+    start_supply: U256,
+    end_supply: U256,
+    params: &CurveParameters,
+    rounding: Rounding,
+) -> Result<U256, MathOp> {
+    let mut cost = U256::ZERO;
+    for (i, segment) in segments(params).iter().enumerate() {
+        let (segment_start_supply, segment_start_price) = segment_start(params, i);
+        let from = start_supply.max(segment_start_supply);
+        let to = end_supply.min(segment.end_supply);
+        if from < to {
+            cost = A::add(
+                cost,
+                segment_cost::<A>(
+                    segment,
+                    segment_start_supply,
+                    segment_start_price,
+                    from,
+                    to,
+                    rounding,
+                )?,
+            )?;
+        }
+    }
+
+    Ok(cost)
+}
+
+// Supply at which the spot price first reaches `price`
+pub(crate) fn supply_at_price(price: U256, params: &CurveParameters) -> U256 {
+    if price <= params.initial_price {
+        return U256::ZERO;
+    }
+
+    for (i, segment) in segments(params).iter().enumerate() {
+        if price > segment.end_price {
+            continue;
+        }
+
+        let (start_supply, start_price) = segment_start(params, i);
+        if price <= start_price {
+            return start_supply;
+        }
+
+        let length = segment.end_supply - start_supply;
+        if segment.shape == SEGMENT_SHAPE_LINEAR {
+            return start_supply
+                + (price - start_price).saturating_mul(length) / (segment.end_price - start_price);
+        }
+
+        // The normalized sigmoid has no convenient inverse, so binary search the segment
+        let (mut low, mut high) = (start_supply, segment.end_supply);
+        while high - low > U256::from(1u8) {
+            let mid = low + (high - low) / TWO;
+            if segment_price(segment, start_supply, start_price, mid) < price {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        return high;
+    }
+
+    params.total_supply
+}
+
+// Segments in use
+fn segments(params: &CurveParameters) -> &[CurveSegment] {
+    &params.segments[..params.segment_count.min(MAX_CURVE_SEGMENTS)]
+}
+
+// Supply and price a segment starts at
+fn segment_start(params: &CurveParameters, index: usize) -> (U256, U256) {
+    if index == 0 {
+        (U256::ZERO, params.initial_price)
+    } else {
+        let previous = &params.segments[index - 1];
+        (previous.end_supply, previous.end_price)
+    }
+}
+
+// Price within a segment. Sigmoid segments rescale the logistic curve over the segment so it
+// starts at the start price and ends at the end price.
+fn segment_price(
+    segment: &CurveSegment,
+    start_supply: U256,
+    start_price: U256,
+    supply: U256,
+) -> U256 {
+    saturated(try_segment_price::<Saturating>(
+        segment,
+        start_supply,
+        start_price,
+        supply,
+    ))
+}
+
+// segment_price on the given arithmetic
+fn try_segment_price<A: Arithmetic>(
+    segment: &CurveSegment,
+    start_supply: U256,
+    start_price: U256,
+    supply: U256,
+) -> Result<U256, MathOp> {
+    let length = segment.end_supply - start_supply;
+    let price_range = segment.end_price - start_price;
+    let distance = supply.clamp(start_supply, segment.end_supply) - start_supply;
+
+    match segment.shape {
+        SEGMENT_SHAPE_LINEAR => A::add(start_price, A::mul(price_range, distance)? / length),
+        SEGMENT_SHAPE_SIGMOID => {
+            let (low, high) = logistic_bounds(segment);
+            let fraction = divide_fixed_point(distance, length);
+            let reached = logistic(segment, fraction).saturating_sub(low);
+            A::add(start_price, A::mul(price_range, reached)? / (high - low))
+        }
+        _ => Ok(start_price),
+    }
+}
+
+// Price change per whole token within a segment. Sigmoid segments scale the logistic slope
+// k * l * (1 - l) by the segment's price range over its logistic range and length.
+fn segment_slope(
+    segment: &CurveSegment,
+    start_supply: U256,
+    start_price: U256,
+    supply: U256,
+) -> U256 {
+    let length = segment.end_supply - start_supply;
+    let price_range = segment.end_price - start_price;
+
+    match segment.shape {
+        SEGMENT_SHAPE_LINEAR => divide_fixed_point(price_range, length),
+        SEGMENT_SHAPE_SIGMOID => {
+            let (low, high) = logistic_bounds(segment);
+            let fraction = divide_fixed_point(supply - start_supply, length);
+            let value = logistic(segment, fraction);
+            let logistic_slope = multiply_fixed_point(
+                segment.steepness,
+                multiply_fixed_point(value, SCALE_FACTOR - value),
+            );
+
+            divide_fixed_point(
+                price_range.saturating_mul(logistic_slope) / (high - low),
+                length,
+            )
+        }
+        _ => U256::ZERO,
+    }
+}
+
+// WETH for the tokens between `from` and `to`, both within the segment, rounded in the given
+// direction. With d the distance into the segment of length L, linear segments cost
+// p0 * t + R * (d2^2 - d1^2) / (2 * L) and sigmoid segments
+// p0 * t + (R / (hi - lo)) * ((L / k) * (softplus(z2) - softplus(z1)) - lo * t)
+fn segment_cost<A: Arithmetic>(
+    segment: &CurveSegment,
+    start_supply: U256,
+    start_price: U256,
+    from: U256,
+    to: U256,
+    rounding: Rounding,
+) -> Result<U256, MathOp> {
+    let token_amount = to - from;
+    let base_cost = A::mul_div(start_price, token_amount, SCALE_FACTOR, rounding)?;
+    match segment.shape {
+        SEGMENT_SHAPE_LINEAR => {
+            let length = segment.end_supply - start_supply;
+            let (from_distance, to_distance) = (from - start_supply, to - start_supply);
+            let squares = A::mul(token_amount, from_distance + to_distance)?;
+            A::add(
+                base_cost,
+                A::mul_div(
+                    segment.end_price - start_price,
+                    squares,
+                    A::mul(length, TWO * SCALE_FACTOR)?,
+                    rounding,
+                )?,
+            )
+        }
+        SEGMENT_SHAPE_SIGMOID => {
+            let length = segment.end_supply - start_supply;
+            let (low, high) = logistic_bounds(segment);
+            let from_fraction = divide_fixed_point(from - start_supply, length);
+            let to_fraction = divide_fixed_point(to - start_supply, length);
+
+            // The excess over the segment's start price, split into the softplus term and the
+            // lo * t offset. Both telescope across consecutive trades, so a negative excess
+            // (softplus is quantized, lo * t is not) is carried rather than clamped.
+            let gain = A::mul_div(
+                divide_fixed_point(length, segment.steepness),
+                softplus(segment, to_fraction).saturating_sub(softplus(segment, from_fraction)),
+                SCALE_FACTOR,
+                rounding,
+            )?;
+            let offset = A::mul_div(low, token_amount, SCALE_FACTOR, rounding.opposite())?;
+
+            let price_range = segment.end_price - start_price;
+            if gain >= offset {
+                A::add(
+                    base_cost,
+                    A::mul_div(price_range, gain - offset, high - low, rounding)?,
+                )
+            } else {
+                Ok(base_cost.saturating_sub(A::mul_div(
+                    price_range,
+                    offset - gain,
+                    high - low,
+                    rounding.opposite(),
+                )?))
+            }
+        }
+        _ => Ok(base_cost),
+    }
+}
+
+// Logistic value at the start and end of a sigmoid segment
+fn logistic_bounds(segment: &CurveSegment) -> (U256, U256) {
+    (
+        logistic(segment, U256::ZERO),
+        logistic(segment, SCALE_FACTOR),
+    )
+}
+
+// Logistic 1 / (1 + e^-z) at z = k * (fraction - m), evaluated with exp of |z| only
+fn logistic(segment: &CurveSegment, fraction: U256) -> U256 {
+    if fraction >= segment.midpoint {
+        let exp_value = exp_approx(multiply_fixed_point(
+            segment.steepness,
+            fraction - segment.midpoint,
+        ));
+        divide_fixed_point(exp_value, SCALE_FACTOR.saturating_add(exp_value))
+    } else {
+        let exp_value = exp_approx(multiply_fixed_point(
+            segment.steepness,
+            segment.midpoint - fraction,
+        ));
+        divide_fixed_point(SCALE_FACTOR, SCALE_FACTOR.saturating_add(exp_value))
+    }
+}
+
+// softplus(z) = ln(1 + e^z) at z = k * (fraction - m), as ln(1 + e^-|z|) (+ z when z > 0)
+fn softplus(segment: &CurveSegment, fraction: U256) -> U256 {
+    let (distance, above_midpoint) = if fraction >= segment.midpoint {
+        (fraction - segment.midpoint, true)
+    } else {
+        (segment.midpoint - fraction, false)
+    };
+
+    let z = multiply_fixed_point(segment.steepness, distance);
+    let tail = ln_fixed_point(
+        SCALE_FACTOR.saturating_add(divide_fixed_point(SCALE_FACTOR, exp_approx(z))),
+    );
+
+    if above_midpoint {
+        z.saturating_add(tail)
+    } else {
+        tail
+    }
+}
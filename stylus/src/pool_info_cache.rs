@@ -0,0 +1,176 @@
+// Pool info read from a manager, remembered for the rest of the transaction so batched calls
+// touching the same pool ask the manager once. Entries live in transient storage (EIP-1153),
+// keyed by manager and pool id. Builds with the `no-transient-storage` feature keep them in
+// memory instead, which only lasts for the current call into the contract.
+//
+// A manager's answer changes when the pool trades, so every trade executed here and every
+// supply hook the manager calls drop the pool's entry.
+
+use alloy_primitives::{Address, B256};
+
+use crate::PoolInfo;
+
+// Cached pool info of a pool under its manager
+pub(crate) fn get(manager: Address, pool_id: B256) -> Option<PoolInfo> {
+    backend::get(manager, pool_id)
+}
+
+// Remember a pool's info for the rest of the transaction
+pub(crate) fn put(manager: Address, pool_id: B256, info: &PoolInfo) {
+    backend::put(manager, pool_id, info)
+}
+
+// Forget a pool's info
+pub(crate) fn clear(manager: Address, pool_id: B256) {
+    backend::clear(manager, pool_id)
+}
+
+// Forget every pool's info, as a new transaction does
+#[cfg(test)]
+pub(crate) fn reset() {
+    backend::reset()
+}
+
+#[cfg(not(any(feature = "no-transient-storage", feature = "export-abi")))]
+mod backend {
+    use alloy_primitives::{Address, B256, U256};
+    use stylus_sdk::crypto;
+
+    use crate::PoolInfo;
+
+    #[link(wasm_import_module = "vm_hooks")]
+    extern "C" {
+        fn transient_load_bytes32(key: *const u8, dest: *mut u8);
+        fn transient_store_bytes32(key: *const u8, value: *const u8);
+    }
+
+    // Namespace of the cache's transient slots
+    const CACHE_TAG: &[u8] = b"pumpup.pool_info_cache";
+
+    // Words of an entry: presence flag, is_transitioned flag and token address; creator;
+    // weth_collected; last_price; bonding_curve_strategy
+    const ENTRY_WORDS: usize = 5;
+
+    const PRESENT_BYTE: usize = 0;
+    const TRANSITIONED_BYTE: usize = 1;
+
+    // First transient slot of a pool's entry
+    fn entry_slot(manager: Address, pool_id: B256) -> U256 {
+        let mut preimage = [0u8; 22 + 20 + 32];
+        preimage[..22].copy_from_slice(CACHE_TAG);
+        preimage[22..42].copy_from_slice(manager.as_slice());
+        preimage[42..].copy_from_slice(pool_id.as_slice());
+        U256::from_be_bytes(crypto::keccak(preimage).0)
+    }
+
+    fn load(slot: U256) -> B256 {
+        let key = B256::from(slot);
+        let mut value = B256::ZERO;
+        unsafe { transient_load_bytes32(key.as_ptr(), value.as_mut_ptr()) };
+        value
+    }
+
+    fn store(slot: U256, value: B256) {
+        let key = B256::from(slot);
+        unsafe { transient_store_bytes32(key.as_ptr(), value.as_ptr()) };
+    }
+
+    pub(super) fn get(manager: Address, pool_id: B256) -> Option<PoolInfo> {
+        let slot = entry_slot(manager, pool_id);
+        let head = load(slot);
+        if head[PRESENT_BYTE] == 0 {
+            return None;
+        }
+
+        let word = |index: usize| load(slot + U256::from(index));
+        Some((
+            Address::from_slice(&head[12..]),
+            Address::from_slice(&word(1)[12..]),
+            U256::from_be_bytes(word(2).0),
+            U256::from_be_bytes(word(3).0),
+            head[TRANSITIONED_BYTE] != 0,
+            word(4),
+        ))
+    }
+
+    pub(super) fn put(manager: Address, pool_id: B256, info: &PoolInfo) {
+        let (token_address, creator, weth_collected, last_price, is_transitioned, strategy) = *info;
+
+        let mut head = B256::ZERO;
+        head[PRESENT_BYTE] = 1;
+        head[TRANSITIONED_BYTE] = u8::from(is_transitioned);
+        head[12..].copy_from_slice(token_address.as_slice());
+
+        let words: [B256; ENTRY_WORDS] = [
+            head,
+            creator.into_word(),
+            weth_collected.into(),
+            last_price.into(),
+            strategy,
+        ];
+        let slot = entry_slot(manager, pool_id);
+        for (index, word) in words.into_iter().enumerate() {
+            store(slot + U256::from(index), word);
+        }
+    }
+
+    pub(super) fn clear(manager: Address, pool_id: B256) {
+        store(entry_slot(manager, pool_id), B256::ZERO);
+    }
+
+    // The test VM drops transient storage itself
+    #[cfg(test)]
+    pub(super) fn reset() {}
+}
+
+#[cfg(any(feature = "no-transient-storage", feature = "export-abi"))]
+mod backend {
+    use alloc::collections::BTreeMap;
+    use alloy_primitives::{Address, B256};
+    use core::cell::RefCell;
+
+    use crate::PoolInfo;
+
+    type Entries = RefCell<BTreeMap<(Address, B256), PoolInfo>>;
+
+    // Every call into the contract gets a fresh, single-threaded instance, so a plain static
+    // lasts exactly one call frame
+    #[cfg(not(test))]
+    struct CallEntries(Entries);
+
+    #[cfg(not(test))]
+    unsafe impl Sync for CallEntries {}
+
+    #[cfg(not(test))]
+    static ENTRIES: CallEntries = CallEntries(RefCell::new(BTreeMap::new()));
+
+    // Native tests share the process across threads, so each gets its own map
+    #[cfg(test)]
+    std::thread_local! {
+        static ENTRIES: Entries = const { RefCell::new(BTreeMap::new()) };
+    }
+
+    fn with_entries<R>(f: impl FnOnce(&Entries) -> R) -> R {
+        #[cfg(not(test))]
+        return f(&ENTRIES.0);
+        #[cfg(test)]
+        return ENTRIES.with(f);
+    }
+
+    pub(super) fn get(manager: Address, pool_id: B256) -> Option<PoolInfo> {
+        with_entries(|entries| entries.borrow().get(&(manager, pool_id)).copied())
+    }
+
+    pub(super) fn put(manager: Address, pool_id: B256, info: &PoolInfo) {
+        with_entries(|entries| entries.borrow_mut().insert((manager, pool_id), *info));
+    }
+
+    pub(super) fn clear(manager: Address, pool_id: B256) {
+        with_entries(|entries| entries.borrow_mut().remove(&(manager, pool_id)));
+    }
+
+    #[cfg(test)]
+    pub(super) fn reset() {
+        with_entries(|entries| entries.borrow_mut().clear());
+    }
+}
@@ -0,0 +1,22 @@
+// Access control roles, identified by keccak256 of their name as in OpenZeppelin's
+// AccessControl. The owner implicitly holds every role and alone grants and revokes them.
+
+use alloy_primitives::{b256, B256};
+
+// keccak256("INITIALIZER_ROLE"): may initialize pools besides the bound pool state manager
+pub(crate) const INITIALIZER_ROLE: B256 =
+    b256!("30d41a597cac127d8249d31298b50e481ee82c3f4a49ff93c76a22735aa9f3ad");
+
+// keccak256("PARAM_ADMIN_ROLE"): may tune curve defaults, launch phases, quote settings and
+// per-pool trading limits
+pub(crate) const PARAM_ADMIN_ROLE: B256 =
+    b256!("8ba45f340316dd72e9cc26667babfd6acf968018782e32378f6cfec521e8a055");
+
+// keccak256("PAUSER_ROLE"): may trigger emergency controls
+pub(crate) const PAUSER_ROLE: B256 =
+    b256!("65d7a28e3265b37a6474929f336521b332c1681b933f6cb9f3376673440d862a");
+
+// Whether a role id is one of the roles above
+pub(crate) fn is_known_role(role: B256) -> bool {
+    [INITIALIZER_ROLE, PARAM_ADMIN_ROLE, PAUSER_ROLE].contains(&role)
+}
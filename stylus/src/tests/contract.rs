@@ -0,0 +1,1316 @@
+// Entrypoint tests against the native VM: pool initialization, quotes read through the mocked
+// token and manager, and access control
+
+use alloy_primitives::{address, keccak256, Address, FixedBytes, B256, I256, U256};
+use alloy_sol_types::SolValue;
+use pumpup_math::arithmetic::MathOp;
+use pumpup_math::multiply_fixed_point;
+use stylus_sdk::abi::Bytes;
+
+use super::vm::{TestVm, BLOCK_TIMESTAMP, CONTRACT, SENDER};
+use crate::host::mock::MockHost;
+use crate::host::HostCalls;
+use crate::roles::{INITIALIZER_ROLE, PARAM_ADMIN_ROLE};
+use crate::{
+    CashbackPaid, CirculatingSupplyUpdated, CurveInitialized, CurveParameters, CurveParams,
+    DefaultsApplied, FeesAccrued, GraduationThresholdReached, IncentiveReserveFunded,
+    PoolAlreadyInitialized, PriceFeedTwapWindowSet, PumpUpErrors, ReferralPaid, SellFloorApplied,
+    SigmoidBondingCurve, TokensPurchased, TokensSold, TransitionReady, UnknownSelector,
+    CURVE_KIND_LINEAR, DEFAULT_MAX_PRICE_FACTOR, DEFAULT_MIDPOINT, DEFAULT_STEEPNESS,
+    HEALTH_QUOTE_TOKEN_DECIMALS, HEALTH_QUOTE_TOKEN_UNSET, OBSERVATION_CAPACITY, SCALE_FACTOR,
+    SOLVER_TOLERANCE, TRADE_DIRECTION_BUY, TRADE_DIRECTION_SELL,
+};
+
+const POOL_ID: B256 = B256::repeat_byte(0x11);
+const TOKEN: Address = address!("0000000000000000000000000000000000007e57");
+const CREATOR: Address = address!("00000000000000000000000000000000000c4ea7");
+const OTHER: Address = address!("0000000000000000000000000000000000000b0b");
+const USDC: Address = address!("000000000000000000000000000000000000a5dc");
+
+// 0.000001 WETH per token over a billion tokens (10^27)
+const INITIAL_PRICE: U256 = U256::from_limbs([1_000_000_000_000, 0, 0, 0]);
+const TOTAL_SUPPLY: U256 = U256::from_limbs([0x9fd0_803c_e800_0000, 0x33b_2e3c, 0, 0]);
+
+fn curve_params(
+    initial_price: U256,
+    max_price_factor: U256,
+    steepness: U256,
+    midpoint: U256,
+) -> Bytes {
+    CurveParams {
+        initialPrice: initial_price,
+        maxPriceFactor: max_price_factor,
+        steepness,
+        midpoint,
+        totalSupply: TOTAL_SUPPLY,
+    }
+    .abi_encode()
+    .into()
+}
+
+// Contract with POOL_ID initialized on the default curve parameters
+fn initialized(vm: &TestVm) -> SigmoidBondingCurve {
+    let mut contract = vm.deploy();
+    let params = curve_params(INITIAL_PRICE, U256::ZERO, U256::ZERO, U256::ZERO);
+    assert!(contract.initialize(POOL_ID, params).is_ok());
+    contract
+}
+
+// Pool state as the manager and token report it: `sold` tokens circulating and `weth_collected`
+// held by the pool
+fn mock_pool(contract: &mut SigmoidBondingCurve, sold: U256, weth_collected: U256) {
+    MockHost::set_pool_info(
+        SENDER,
+        POOL_ID,
+        (
+            TOKEN,
+            CREATOR,
+            weth_collected,
+            U256::ZERO,
+            false,
+            B256::ZERO,
+        ),
+    );
+    MockHost::set_token(TOKEN, TOTAL_SUPPLY, SENDER, TOTAL_SUPPLY - sold);
+    assert!(contract.sync_supply(POOL_ID).is_ok());
+}
+
+#[test]
+fn initialize_stores_curve_parameters() {
+    let vm = TestVm::new();
+    let mut contract = vm.deploy();
+
+    let (max_price_factor, steepness, midpoint) = (
+        U256::from(20u8) * SCALE_FACTOR,
+        U256::from(5u8) * SCALE_FACTOR,
+        SCALE_FACTOR / U256::from(4u8),
+    );
+    let params = curve_params(INITIAL_PRICE, max_price_factor, steepness, midpoint);
+    assert!(contract.initialize(POOL_ID, params).is_ok());
+
+    assert!(contract.pool_exists(POOL_ID));
+    assert_eq!(
+        contract.get_curve_parameters(POOL_ID).ok(),
+        Some((
+            INITIAL_PRICE,
+            max_price_factor,
+            steepness,
+            midpoint,
+            TOTAL_SUPPLY
+        ))
+    );
+    assert!(vm.emitted::<CurveInitialized>());
+    assert!(!vm.emitted::<DefaultsApplied>());
+}
+
+#[test]
+fn initialize_applies_defaults_to_zero_fields() {
+    let vm = TestVm::new();
+    let contract = initialized(&vm);
+
+    assert_eq!(
+        contract.get_curve_parameters(POOL_ID).ok(),
+        Some((
+            INITIAL_PRICE,
+            DEFAULT_MAX_PRICE_FACTOR,
+            DEFAULT_STEEPNESS,
+            DEFAULT_MIDPOINT,
+            TOTAL_SUPPLY
+        ))
+    );
+    assert!(vm.emitted::<DefaultsApplied>());
+}
+
+#[test]
+fn initialize_rejects_invalid_calls() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    let params = curve_params(INITIAL_PRICE, U256::ZERO, U256::ZERO, U256::ZERO);
+
+    assert!(matches!(
+        contract.initialize(POOL_ID, params),
+        Err(PumpUpErrors::PoolAlreadyInitialized(_))
+    ));
+
+    let zero_price = curve_params(U256::ZERO, U256::ZERO, U256::ZERO, U256::ZERO);
+    assert!(matches!(
+        contract.initialize(B256::repeat_byte(0x22), zero_price),
+        Err(PumpUpErrors::InvalidParametersZero(_))
+    ));
+
+    assert!(matches!(
+        contract.initialize(B256::repeat_byte(0x22), Bytes::from(vec![0u8; 64])),
+        Err(PumpUpErrors::InvalidParametersLength(_))
+    ));
+}
+
+#[test]
+fn initialize_is_restricted_to_the_manager() {
+    let vm = TestVm::new();
+    let mut contract = vm.deploy();
+
+    // Hand the manager and ownership (which implies every role) to another account
+    assert!(contract.set_pool_state_manager(OTHER).is_ok());
+    assert!(contract.transfer_ownership(OTHER).is_ok());
+
+    let params = curve_params(INITIAL_PRICE, U256::ZERO, U256::ZERO, U256::ZERO);
+    assert!(matches!(
+        contract.initialize(POOL_ID, params),
+        Err(PumpUpErrors::NotPoolStateManager(_))
+    ));
+    assert!(!contract.pool_exists(POOL_ID));
+}
+
+#[test]
+fn initializer_role_may_initialize() {
+    let vm = TestVm::new();
+    let mut contract = vm.deploy();
+
+    assert!(contract.set_pool_state_manager(OTHER).is_ok());
+    assert!(contract.grant_role(INITIALIZER_ROLE, SENDER).is_ok());
+    assert!(contract.transfer_ownership(OTHER).is_ok());
+
+    let params = curve_params(INITIAL_PRICE, U256::ZERO, U256::ZERO, U256::ZERO);
+    assert!(contract.initialize(POOL_ID, params).is_ok());
+    assert!(contract.pool_exists(POOL_ID));
+}
+
+#[test]
+fn quote_buy_prices_along_the_curve() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    let sold = TOTAL_SUPPLY / U256::from(10u8);
+    mock_pool(&mut contract, sold, U256::ZERO);
+
+    let weth_amount = SCALE_FACTOR;
+    let quote = contract.quote_buy(POOL_ID, SENDER, weth_amount);
+    assert!(quote.is_ok());
+    let (tokens, new_price, weth_unspent) = quote.unwrap_or_default();
+
+    let params = contract.get_curve_params(POOL_ID).unwrap_or_default();
+    assert!(tokens > U256::ZERO);
+    assert_eq!(
+        new_price,
+        contract.calculate_sigmoid_price(sold + tokens, &params)
+    );
+    assert!(new_price > contract.calculate_sigmoid_price(sold, &params));
+    assert!(weth_unspent <= SOLVER_TOLERANCE);
+    assert_eq!(
+        contract.calculate_weth_for_token_amount(sold, tokens, &params, false),
+        weth_amount - weth_unspent
+    );
+}
+
+#[test]
+fn quote_sell_returns_the_curve_integral() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    let sold = TOTAL_SUPPLY / U256::from(10u8);
+    mock_pool(&mut contract, sold, U256::from(1000u16) * SCALE_FACTOR);
+
+    let tokens = sold / U256::from(2u8);
+    let quote = contract.quote_sell(POOL_ID, SENDER, tokens);
+    assert!(quote.is_ok());
+    let (weth_out, new_price) = quote.unwrap_or_default();
+
+    let params = contract.get_curve_params(POOL_ID).unwrap_or_default();
+    assert_eq!(
+        weth_out,
+        contract.calculate_weth_for_token_amount(sold, tokens, &params, true)
+    );
+    assert_eq!(
+        new_price,
+        contract.calculate_sigmoid_price(sold - tokens, &params)
+    );
+
+    assert!(matches!(
+        contract.quote_sell(POOL_ID, SENDER, sold + U256::from(1u8)),
+        Err(PumpUpErrors::InvalidAmount(_))
+    ));
+}
+
+#[test]
+fn token_decimals_scale_amounts_to_the_curve() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    assert_eq!(contract.token_decimals(POOL_ID), 18);
+
+    // A 6-decimal token with a tenth of its supply sold
+    let unit = U256::from(1_000_000_000_000u64);
+    let sold = TOTAL_SUPPLY / U256::from(10u8);
+    MockHost::set_pool_info(
+        SENDER,
+        POOL_ID,
+        (
+            TOKEN,
+            CREATOR,
+            U256::from(1000u16) * SCALE_FACTOR,
+            U256::ZERO,
+            false,
+            B256::ZERO,
+        ),
+    );
+    MockHost::set_token(
+        TOKEN,
+        TOTAL_SUPPLY / unit,
+        SENDER,
+        (TOTAL_SUPPLY - sold) / unit,
+    );
+    MockHost::set_decimals(TOKEN, 6);
+    assert_eq!(contract.sync_supply(POOL_ID).ok(), Some(sold / unit));
+    assert_eq!(contract.token_decimals(POOL_ID), 6);
+
+    // Prices are per whole token, as for an 18-decimal token
+    let params = contract.get_curve_params(POOL_ID).unwrap_or_default();
+    assert_eq!(
+        contract.get_current_price(POOL_ID).ok(),
+        Some(contract.calculate_sigmoid_price(sold, &params))
+    );
+
+    let tokens = sold / U256::from(2u8);
+    let (weth_out, _) = contract
+        .quote_sell(POOL_ID, SENDER, tokens / unit)
+        .unwrap_or_default();
+    assert_eq!(
+        weth_out,
+        contract.calculate_weth_for_token_amount(sold, tokens, &params, true)
+    );
+
+    // Buys return whole token units
+    let (bought, new_price, _) = contract
+        .quote_buy(POOL_ID, SENDER, SCALE_FACTOR)
+        .unwrap_or_default();
+    assert!(bought > U256::ZERO);
+    assert_eq!(
+        new_price,
+        contract.calculate_sigmoid_price(sold + bought * unit, &params)
+    );
+}
+
+#[test]
+fn base_asset_decimals_scale_quotes() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    let sold = TOTAL_SUPPLY / U256::from(10u8);
+    let usdc_unit = U256::from(1_000_000u32);
+    mock_pool(&mut contract, sold, U256::from(1_000_000u32) * usdc_unit);
+    assert_eq!(contract.base_asset(POOL_ID), (Address::ZERO, 18));
+
+    // Decimals must match the asset, and the native currency has 18
+    MockHost::set_decimals(USDC, 6);
+    assert!(matches!(
+        contract.set_base_asset(POOL_ID, USDC, 18),
+        Err(PumpUpErrors::InvalidBaseAsset(_))
+    ));
+    assert!(matches!(
+        contract.set_base_asset(POOL_ID, Address::ZERO, 6),
+        Err(PumpUpErrors::InvalidBaseAsset(_))
+    ));
+    assert!(contract.set_base_asset(POOL_ID, USDC, 6).is_ok());
+    assert_eq!(contract.base_asset(POOL_ID), (USDC, 6));
+
+    // Amounts in and out are USDC units, priced on the 18-decimal curve
+    let params = contract.get_curve_params(POOL_ID).unwrap_or_default();
+    let tokens = sold / U256::from(2u8);
+    let (usdc_out, _) = contract
+        .quote_sell(POOL_ID, SENDER, tokens)
+        .unwrap_or_default();
+    assert_eq!(
+        usdc_out,
+        contract.calculate_weth_for_token_amount(sold, tokens, &params, true)
+            / U256::from(10u64.pow(12))
+    );
+
+    let (bought, new_price, usdc_unspent) = contract
+        .quote_buy(POOL_ID, SENDER, usdc_unit)
+        .unwrap_or_default();
+    assert!(bought > U256::ZERO);
+    assert_eq!(
+        new_price,
+        contract.calculate_sigmoid_price(sold + bought, &params)
+    );
+    let cost = contract.calculate_weth_for_token_amount(sold, bought, &params, false);
+    assert_eq!(
+        usdc_unspent,
+        usdc_unit - cost.div_ceil(U256::from(10u64.pow(12)))
+    );
+
+    // The base asset is fixed once the pool has traded
+    contract.record_trade(
+        POOL_ID,
+        SENDER,
+        TRADE_DIRECTION_BUY,
+        bought,
+        usdc_unit,
+        new_price,
+    );
+    assert!(matches!(
+        contract.set_base_asset(POOL_ID, Address::ZERO, 18),
+        Err(PumpUpErrors::BaseAssetLocked(_))
+    ));
+}
+
+#[test]
+fn supply_at_price_inverts_the_curve() {
+    let vm = TestVm::new();
+    let contract = initialized(&vm);
+    let max_price = INITIAL_PRICE * DEFAULT_MAX_PRICE_FACTOR / SCALE_FACTOR;
+
+    assert_eq!(
+        contract.get_supply_at_price(POOL_ID, INITIAL_PRICE).ok(),
+        Some(U256::ZERO)
+    );
+    assert_eq!(
+        contract.get_supply_at_price(POOL_ID, max_price).ok(),
+        Some(TOTAL_SUPPLY)
+    );
+
+    for percent in [10u8, 50, 90] {
+        let supply = TOTAL_SUPPLY * U256::from(percent) / U256::from(100u8);
+        let price = contract
+            .get_price_at_supply(POOL_ID, supply)
+            .unwrap_or_default();
+        let inverse = contract
+            .get_supply_at_price(POOL_ID, price)
+            .unwrap_or_default();
+        // Within 0.01% of the total supply
+        assert!(inverse.abs_diff(supply) <= TOTAL_SUPPLY / U256::from(10_000u16));
+    }
+}
+
+// Record a trade of the pool at `timestamp` that leaves it at `price`, as executed trades do
+fn trade_at(contract: &mut SigmoidBondingCurve, timestamp: u64, price: u64) {
+    contract.write_observation(POOL_ID, timestamp);
+    contract
+        .last_trades
+        .setter(POOL_ID)
+        .price
+        .set(U256::from(price));
+}
+
+#[test]
+fn consult_averages_prices_over_time() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    assert!(matches!(
+        contract.consult(POOL_ID, 60),
+        Err(PumpUpErrors::InsufficientObservations(_))
+    ));
+
+    let start = BLOCK_TIMESTAMP - 100;
+    trade_at(&mut contract, start, 100);
+    trade_at(&mut contract, start + 10, 300);
+    // A later trade in the same second only moves the price
+    trade_at(&mut contract, start + 10, 200);
+    trade_at(&mut contract, start + 30, 400);
+
+    // 100 for 10s, 200 for 20s, then 400 until now
+    assert_eq!(
+        contract.consult(POOL_ID, 100).ok(),
+        Some(U256::from(330u16))
+    );
+    // Starting between two observations: 200 for 10s, then 400 for 70s
+    assert_eq!(contract.consult(POOL_ID, 80).ok(), Some(U256::from(375u16)));
+    assert_eq!(contract.consult(POOL_ID, 0).ok(), Some(U256::from(400u16)));
+    assert!(matches!(
+        contract.consult(POOL_ID, 101),
+        Err(PumpUpErrors::ObservationTooOld(_))
+    ));
+}
+
+#[test]
+fn observations_keep_a_bounded_window() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+
+    let capacity = OBSERVATION_CAPACITY;
+    let start = BLOCK_TIMESTAMP - capacity - 1;
+    for timestamp in start..=BLOCK_TIMESTAMP {
+        trade_at(&mut contract, timestamp, 1_000);
+    }
+
+    // The first two observations were overwritten
+    assert_eq!(
+        contract.consult(POOL_ID, capacity as u32 - 1).ok(),
+        Some(U256::from(1_000u16))
+    );
+    assert!(matches!(
+        contract.consult(POOL_ID, capacity as u32),
+        Err(PumpUpErrors::ObservationTooOld(_))
+    ));
+}
+
+#[test]
+fn price_feed_reports_spot_or_twap_rounds() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    mock_pool(&mut contract, TOTAL_SUPPLY / U256::from(4u8), U256::ZERO);
+    assert!(matches!(
+        contract.latest_round_data(POOL_ID),
+        Err(PumpUpErrors::InsufficientObservations(_))
+    ));
+
+    let last_trade = BLOCK_TIMESTAMP - 10;
+    trade_at(&mut contract, BLOCK_TIMESTAMP - 100, 1_000);
+    trade_at(&mut contract, last_trade, 3_000);
+
+    let spot_price = contract.get_current_price(POOL_ID).unwrap_or_default();
+    let (round_id, answer, started_at, updated_at, answered_in_round) =
+        contract.latest_round_data(POOL_ID).unwrap_or_default();
+    assert_eq!((round_id, answered_in_round), (2, 2));
+    assert_eq!(answer, I256::try_from(spot_price).unwrap_or_default());
+    assert_eq!(
+        (started_at, updated_at),
+        (U256::from(last_trade), U256::from(last_trade))
+    );
+
+    assert!(contract.set_price_feed_twap_window(POOL_ID, 50).is_ok());
+    assert!(vm.emitted::<PriceFeedTwapWindowSet>());
+    let (_, answer, _, _, _) = contract.latest_round_data(POOL_ID).unwrap_or_default();
+    // 1000 for 40s, then 3000 for 10s
+    assert_eq!(answer, I256::try_from(1_400u16).unwrap_or_default());
+}
+
+#[test]
+fn pool_stats_accumulate_executed_trades() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    assert_eq!(
+        contract.get_pool_stats(POOL_ID),
+        (U256::ZERO, U256::ZERO, 0)
+    );
+
+    let price = INITIAL_PRICE;
+    contract.record_trade(
+        POOL_ID,
+        SENDER,
+        TRADE_DIRECTION_BUY,
+        SCALE_FACTOR,
+        U256::from(300u16),
+        price,
+    );
+    contract.record_trade(
+        POOL_ID,
+        SENDER,
+        TRADE_DIRECTION_BUY,
+        SCALE_FACTOR,
+        U256::from(200u16),
+        price,
+    );
+    contract.record_trade(
+        POOL_ID,
+        SENDER,
+        TRADE_DIRECTION_SELL,
+        SCALE_FACTOR,
+        U256::from(150u16),
+        price,
+    );
+
+    assert_eq!(
+        contract.get_pool_stats(POOL_ID),
+        (U256::from(500u16), U256::from(150u16), 3)
+    );
+}
+
+#[test]
+fn checked_math_reverts_instead_of_clamping() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    let sold = TOTAL_SUPPLY / U256::from(10u8);
+    mock_pool(&mut contract, sold, U256::from(1000u16) * SCALE_FACTOR);
+    let quote = contract
+        .quote_buy(POOL_ID, SENDER, SCALE_FACTOR)
+        .unwrap_or_default();
+
+    assert!(contract.set_checked_math(POOL_ID, true).is_ok());
+    assert!(contract.checked_math(POOL_ID));
+
+    // Curves that never overflow quote the same either way
+    assert_eq!(
+        contract
+            .quote_buy(POOL_ID, SENDER, SCALE_FACTOR)
+            .unwrap_or_default(),
+        quote
+    );
+    assert!(quote.0 > U256::ZERO);
+
+    // A max price past 2^256 saturates unchecked but overflows checked
+    let params = CurveParameters {
+        initial_price: U256::MAX / U256::from(4u8),
+        max_price_factor: U256::from(10u8) * SCALE_FACTOR,
+        total_supply: TOTAL_SUPPLY,
+        curve_kind: CURVE_KIND_LINEAR,
+        ..Default::default()
+    };
+    let saturated = contract.calculate_weth_for_token_amount(sold, SCALE_FACTOR, &params, false);
+    assert!(saturated > U256::ZERO);
+    assert!(matches!(
+        contract.check_curve_math(POOL_ID, sold, SCALE_FACTOR, &params, false),
+        Err(PumpUpErrors::MathOverflow(error)) if error.op == MathOp::Mul as u8
+    ));
+}
+
+#[test]
+fn invariant_guards_reject_corrupted_quotes() {
+    let vm = TestVm::new();
+    let contract = initialized(&vm);
+    let params = contract.get_curve_params(POOL_ID).unwrap_or_default();
+    let (sold, tokens) = (TOTAL_SUPPLY / U256::from(10u8), SCALE_FACTOR);
+
+    let cost = contract.calculate_weth_for_token_amount(sold, tokens, &params, false);
+    let price = contract.calculate_sigmoid_price(sold + tokens, &params);
+    let check = |weth: U256, price: U256| {
+        contract.check_quote_invariants(sold, tokens, weth, price, &params, false)
+    };
+    assert!(check(cost, price).is_ok());
+
+    // Prices outside [initial price, max price] and costs below half the trade's cost
+    let max_price = multiply_fixed_point(INITIAL_PRICE, DEFAULT_MAX_PRICE_FACTOR);
+    for (weth, price) in [
+        (cost, INITIAL_PRICE - U256::from(1u8)),
+        (cost, max_price + U256::from(1u8)),
+        (cost / U256::from(4u8), price),
+    ] {
+        assert!(matches!(
+            check(weth, price),
+            Err(PumpUpErrors::InvariantViolated(_))
+        ));
+    }
+}
+
+#[test]
+fn price_slope_matches_nearby_prices() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    let sold = TOTAL_SUPPLY * U256::from(3u8) / U256::from(10u8);
+    mock_pool(&mut contract, sold, U256::from(1000u16) * SCALE_FACTOR);
+
+    // Central difference over a millionth of the total supply on either side
+    let step = TOTAL_SUPPLY / U256::from(1_000_000u32);
+    let price_at = |supply| {
+        contract
+            .get_price_at_supply(POOL_ID, supply)
+            .unwrap_or_default()
+    };
+    let expected =
+        (price_at(sold + step) - price_at(sold - step)) * SCALE_FACTOR / (step * U256::from(2u8));
+
+    let slope = contract.get_price_slope(POOL_ID).unwrap_or_default();
+    assert!(!slope.is_zero());
+    assert!(slope.abs_diff(expected) <= expected / U256::from(1000u16));
+}
+
+#[test]
+fn valuations_follow_the_current_price() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    let sold = TOTAL_SUPPLY / U256::from(4u8);
+    mock_pool(&mut contract, sold, U256::from(1000u16) * SCALE_FACTOR);
+    let price = contract.get_current_price(POOL_ID).unwrap_or_default();
+
+    assert_eq!(
+        contract.get_market_cap(POOL_ID).ok(),
+        Some(sold * price / SCALE_FACTOR)
+    );
+    assert_eq!(
+        contract.get_fdv(POOL_ID).ok(),
+        Some(TOTAL_SUPPLY * price / SCALE_FACTOR)
+    );
+}
+
+#[test]
+fn remaining_supply_costs_the_rest_of_the_curve() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    let sold = TOTAL_SUPPLY / U256::from(4u8);
+    mock_pool(&mut contract, sold, U256::from(1000u16) * SCALE_FACTOR);
+
+    let remaining = TOTAL_SUPPLY - sold;
+    assert_eq!(contract.get_remaining_supply(POOL_ID).ok(), Some(remaining));
+    let (weth_needed, _) = contract
+        .calculate_weth_for_exact_tokens(POOL_ID, SENDER, remaining)
+        .unwrap_or_default();
+    assert!(!weth_needed.is_zero());
+    assert_eq!(
+        contract.get_weth_to_sell_out(POOL_ID).ok(),
+        Some(weth_needed)
+    );
+}
+
+#[test]
+fn refunded_base_asset_is_not_charged_fees() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    mock_pool(
+        &mut contract,
+        TOTAL_SUPPLY - U256::from(1000u16) * SCALE_FACTOR,
+        U256::from(1000u16) * SCALE_FACTOR,
+    );
+    assert!(contract
+        .set_default_fees(U256::from(100u8), U256::from(50u8))
+        .is_ok());
+
+    // More than the rest of the curve costs, so most of it comes back
+    let weth_amount = U256::from(1_000_000u32) * SCALE_FACTOR;
+    let (_, _, quoted_unspent) = contract
+        .quote_buy(POOL_ID, OTHER, weth_amount)
+        .unwrap_or_default();
+    let (_, _, weth_unspent) = contract
+        .calculate_buy_with_remainder(POOL_ID, OTHER, weth_amount)
+        .unwrap_or_default();
+    assert_eq!(weth_unspent, quoted_unspent);
+    assert!(weth_unspent > weth_amount / U256::from(2u8));
+
+    // Fees round up on what the buy spent
+    let weth_spent = weth_amount - weth_unspent;
+    let fee = |bps: u16| (weth_spent * U256::from(bps)).div_ceil(U256::from(10_000u16));
+    assert_eq!(contract.get_accrued_fees(POOL_ID), (fee(100), fee(50)));
+}
+
+#[test]
+fn exact_quotes_match_executed_buys() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    mock_pool(
+        &mut contract,
+        TOTAL_SUPPLY / U256::from(10u8),
+        U256::from(1000u16) * SCALE_FACTOR,
+    );
+    assert!(contract
+        .set_default_fees(U256::from(100u8), U256::from(50u8))
+        .is_ok());
+    let weth_amount = U256::from(10u8) * SCALE_FACTOR;
+
+    let quoted = contract
+        .calculate_tokens_for_exact_weth(POOL_ID, OTHER, weth_amount)
+        .unwrap_or_default();
+    assert_eq!(
+        contract
+            .calculate_buy(POOL_ID, OTHER, weth_amount)
+            .unwrap_or_default(),
+        quoted
+    );
+    let cache = contract.pool_caches.get(POOL_ID);
+    let (sold, weth_collected) = (cache.circulating_supply.get(), cache.weth_collected.get());
+    mock_pool(&mut contract, sold, weth_collected);
+    vm.end_transaction();
+
+    // Enough base asset to cover the fees and still buy the tokens
+    let (weth_needed, new_price) = contract
+        .calculate_weth_for_exact_tokens(POOL_ID, OTHER, quoted.0)
+        .unwrap_or_default();
+    let (token_amount, executed_price) = contract
+        .calculate_buy(POOL_ID, OTHER, weth_needed)
+        .unwrap_or_default();
+    assert!(token_amount >= quoted.0);
+    assert_eq!(executed_price, new_price);
+
+    // Trade checks apply to the quotes too
+    assert!(contract
+        .set_min_trade_size(POOL_ID, U256::MAX, U256::ZERO)
+        .is_ok());
+    assert!(matches!(
+        contract.calculate_weth_for_exact_tokens(POOL_ID, OTHER, quoted.0),
+        Err(PumpUpErrors::BelowMinimumTrade(_))
+    ));
+    assert!(matches!(
+        contract.calculate_tokens_for_exact_weth(POOL_ID, OTHER, weth_amount),
+        Err(PumpUpErrors::BelowMinimumTrade(_))
+    ));
+}
+
+#[test]
+fn detailed_quotes_report_price_impact() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    let sold = TOTAL_SUPPLY / U256::from(10u8);
+    mock_pool(&mut contract, sold, U256::from(1000u16) * SCALE_FACTOR);
+    let spot_price = contract.get_current_price(POOL_ID).unwrap_or_default();
+
+    let weth_amount = U256::from(10u8) * SCALE_FACTOR;
+    let (tokens, new_price, weth_unspent) = contract
+        .quote_buy(POOL_ID, SENDER, weth_amount)
+        .unwrap_or_default();
+    let (detailed_tokens, before, average, after, impact_bps) = contract
+        .quote_buy_detailed(POOL_ID, SENDER, weth_amount)
+        .unwrap_or_default();
+    assert_eq!(
+        (detailed_tokens, before, after),
+        (tokens, spot_price, new_price)
+    );
+    assert_eq!(
+        average,
+        (weth_amount - weth_unspent) * SCALE_FACTOR / tokens
+    );
+    assert!(before < average && average < after);
+    assert_eq!(
+        impact_bps,
+        (average - before) * U256::from(10_000u16) / before
+    );
+    assert!(!impact_bps.is_zero());
+
+    let (weth_out, new_price) = contract
+        .quote_sell(POOL_ID, SENDER, tokens)
+        .unwrap_or_default();
+    let (detailed_weth_out, before, average, after, impact_bps) = contract
+        .quote_sell_detailed(POOL_ID, SENDER, tokens)
+        .unwrap_or_default();
+    assert_eq!(
+        (detailed_weth_out, before, after),
+        (weth_out, spot_price, new_price)
+    );
+    assert!(after < average && average < before);
+    assert_eq!(
+        impact_bps,
+        (before - average) * U256::from(10_000u16) / before
+    );
+}
+
+#[test]
+fn quotes_surface_external_call_failures() {
+    let vm = TestVm::new();
+    let contract = initialized(&vm);
+
+    // Nothing mocked yet: the manager call fails
+    assert!(matches!(
+        contract.quote_buy(POOL_ID, SENDER, SCALE_FACTOR),
+        Err(PumpUpErrors::ManagerCallFailed(_))
+    ));
+}
+
+#[test]
+fn pools_are_priced_from_the_tracked_supply() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    let params = contract.get_curve_params(POOL_ID).unwrap_or_default();
+    assert_eq!(contract.get_tracked_supply(POOL_ID), (U256::ZERO, true));
+
+    // Tokens moving into or out of the manager don't move the price
+    let sold = TOTAL_SUPPLY / U256::from(10u8);
+    mock_pool(&mut contract, U256::ZERO, U256::ZERO);
+    MockHost::set_token(TOKEN, TOTAL_SUPPLY, SENDER, TOTAL_SUPPLY - sold);
+    assert_eq!(
+        contract.get_current_price(POOL_ID).ok(),
+        Some(contract.calculate_sigmoid_price(U256::ZERO, &params))
+    );
+
+    assert_eq!(contract.notify_buy(POOL_ID, sold).ok(), Some(sold));
+    assert_eq!(
+        contract.notify_sell(POOL_ID, sold / U256::from(2u8)).ok(),
+        Some(sold / U256::from(2u8))
+    );
+    assert_eq!(
+        contract.get_current_price(POOL_ID).ok(),
+        Some(contract.calculate_sigmoid_price(sold / U256::from(2u8), &params))
+    );
+    assert!(vm.emitted::<CirculatingSupplyUpdated>());
+
+    // Syncing adopts the token's view
+    assert_eq!(contract.sync_supply(POOL_ID).ok(), Some(sold));
+    assert_eq!(contract.get_tracked_supply(POOL_ID), (sold, true));
+}
+
+#[test]
+fn supply_hooks_reject_invalid_updates() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+
+    assert!(matches!(
+        contract.notify_sell(POOL_ID, U256::from(1u8)),
+        Err(PumpUpErrors::InvalidAmount(_))
+    ));
+    assert!(matches!(
+        contract.notify_buy(POOL_ID, TOTAL_SUPPLY + U256::from(1u8)),
+        Err(PumpUpErrors::InvalidAmount(_))
+    ));
+
+    // Syncing reads the token and rejects a manager holding more than the total supply
+    mock_pool(&mut contract, U256::ZERO, U256::ZERO);
+    MockHost::set_token(TOKEN, TOTAL_SUPPLY, SENDER, TOTAL_SUPPLY + U256::from(1u8));
+    assert!(matches!(
+        contract.sync_supply(POOL_ID),
+        Err(PumpUpErrors::InconsistentTokenState(_))
+    ));
+    MockHost::fail_token(TOKEN);
+    assert!(matches!(
+        contract.sync_supply(POOL_ID),
+        Err(PumpUpErrors::Erc20CallFailed(_))
+    ));
+
+    // Only the pool's manager reports supply
+    assert!(contract
+        .migrate_pool_manager(SENDER, OTHER, vec![POOL_ID])
+        .is_ok());
+    assert!(matches!(
+        contract.notify_buy(POOL_ID, U256::from(1u8)),
+        Err(PumpUpErrors::NotPoolStateManager(_))
+    ));
+    assert!(matches!(
+        contract.sync_supply(POOL_ID),
+        Err(PumpUpErrors::NotPoolStateManager(_))
+    ));
+}
+
+#[test]
+fn trades_are_executed_only_by_the_pool_manager() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    let sold = TOTAL_SUPPLY / U256::from(10u8);
+    let weth_collected = U256::from(1000u16) * SCALE_FACTOR;
+    mock_pool(&mut contract, sold, weth_collected);
+    let weth_amount = U256::from(10u8) * SCALE_FACTOR;
+    let token_amount = SCALE_FACTOR;
+
+    // Every side effect a trade can have: fees, referral fees, the sell floor drawn from the
+    // insurance reserve, buy cashback and the graduation threshold
+    assert!(contract
+        .set_default_fees(U256::from(100u8), U256::from(50u8))
+        .is_ok());
+    assert!(contract.set_referral_share(U256::from(5_000u16)).is_ok());
+    let insurance_reserve = SCALE_FACTOR;
+    assert!(contract
+        .set_sell_price_floor(POOL_ID, INITIAL_PRICE * U256::from(1000u16))
+        .is_ok());
+    assert!(contract
+        .fund_insurance_reserve(POOL_ID, insurance_reserve)
+        .is_ok());
+    let incentive_reserve = U256::from(1_000_000u32) * SCALE_FACTOR;
+    assert!(contract
+        .set_cashback_bps(POOL_ID, U256::from(500u16))
+        .is_ok());
+    assert!(contract
+        .fund_incentive_reserve(POOL_ID, incentive_reserve)
+        .is_ok());
+    assert!(contract
+        .set_graduation_weth_target(POOL_ID, weth_collected + U256::from(1u8))
+        .is_ok());
+
+    // Quotes never emit trade events
+    assert!(contract.quote_buy(POOL_ID, SENDER, weth_amount).is_ok());
+    assert!(contract.quote_sell(POOL_ID, SENDER, token_amount).is_ok());
+    assert!(contract
+        .calculate_weth_for_exact_tokens(POOL_ID, SENDER, token_amount)
+        .is_ok());
+    assert!(contract
+        .calculate_tokens_for_exact_weth(POOL_ID, SENDER, weth_amount)
+        .is_ok());
+    let (_, _, commitment, _) = contract
+        .commit_quote(POOL_ID, weth_amount)
+        .unwrap_or_default();
+    assert!(!vm.emitted::<TokensPurchased>());
+    assert!(!vm.emitted::<TokensSold>());
+
+    // Anyone but the pool's manager is rejected before anything is emitted or recorded
+    assert!(contract
+        .migrate_pool_manager(SENDER, OTHER, vec![POOL_ID])
+        .is_ok());
+    let not_manager = |result: Result<(U256, U256), PumpUpErrors>| {
+        matches!(result, Err(PumpUpErrors::NotPoolStateManager(_)))
+    };
+    assert!(not_manager(contract.calculate_buy(
+        POOL_ID,
+        SENDER,
+        weth_amount
+    )));
+    assert!(not_manager(contract.calculate_buy_with_slippage(
+        POOL_ID,
+        SENDER,
+        weth_amount,
+        U256::ZERO,
+        0
+    )));
+    assert!(matches!(
+        contract.calculate_buy_with_remainder(POOL_ID, SENDER, weth_amount),
+        Err(PumpUpErrors::NotPoolStateManager(_))
+    ));
+    assert!(not_manager(contract.calculate_buy_with_referrer(
+        POOL_ID,
+        SENDER,
+        weth_amount,
+        CREATOR
+    )));
+    assert!(not_manager(
+        contract.calculate_buy_with_commitment(SENDER, commitment)
+    ));
+    assert!(not_manager(contract.calculate_sell(
+        POOL_ID,
+        SENDER,
+        token_amount
+    )));
+    assert!(not_manager(contract.calculate_sell_with_slippage(
+        POOL_ID,
+        SENDER,
+        token_amount,
+        U256::ZERO,
+        0
+    )));
+    assert!(!vm.emitted::<TokensPurchased>());
+    assert!(!vm.emitted::<TokensSold>());
+    assert_eq!(
+        contract.get_pool_stats(POOL_ID),
+        (U256::ZERO, U256::ZERO, 0)
+    );
+    assert_eq!(contract.get_accrued_fees(POOL_ID), (U256::ZERO, U256::ZERO));
+    assert!(!vm.emitted::<FeesAccrued>());
+    assert!(contract.get_referral_fees(CREATOR).is_zero());
+    assert!(!vm.emitted::<ReferralPaid>());
+    assert_eq!(contract.get_insurance_reserve(POOL_ID), insurance_reserve);
+    assert!(!vm.emitted::<SellFloorApplied>());
+    assert_eq!(contract.incentive_reserves.get(POOL_ID), incentive_reserve);
+    assert!(!vm.emitted::<CashbackPaid>());
+    assert!(!contract.is_transition_pending(POOL_ID));
+    assert!(!vm.emitted::<GraduationThresholdReached>());
+    assert!(matches!(
+        contract.consult(POOL_ID, 0),
+        Err(PumpUpErrors::InsufficientObservations(_))
+    ));
+    assert!(matches!(
+        contract.latest_round_data(POOL_ID),
+        Err(PumpUpErrors::InsufficientObservations(_))
+    ));
+    assert!(contract.last_trades.get(POOL_ID).price.get().is_zero());
+
+    // The manager trades on behalf of the trader it names
+    assert!(contract
+        .migrate_pool_manager(OTHER, SENDER, vec![POOL_ID])
+        .is_ok());
+    assert!(contract.set_trade_log_capacity(1).is_ok());
+    assert!(contract
+        .calculate_sell(POOL_ID, OTHER, token_amount)
+        .is_ok());
+    assert!(vm.emitted::<TokensSold>());
+    assert!(contract.get_insurance_reserve(POOL_ID) < insurance_reserve);
+    assert!(vm.emitted::<SellFloorApplied>());
+
+    let (_, new_price) = contract
+        .calculate_buy_with_referrer(POOL_ID, OTHER, weth_amount, CREATOR)
+        .unwrap_or_default();
+    assert!(vm.emitted::<TokensPurchased>());
+    assert_eq!(
+        contract
+            .trades(POOL_ID, U256::ZERO, U256::from(1u8))
+            .first()
+            .map(|trade| trade.0),
+        Some(OTHER)
+    );
+    let (protocol_fees, creator_fees) = contract.get_accrued_fees(POOL_ID);
+    assert!(!protocol_fees.is_zero() && !creator_fees.is_zero());
+    assert!(vm.emitted::<FeesAccrued>());
+    assert!(!contract.get_referral_fees(CREATOR).is_zero());
+    assert!(vm.emitted::<ReferralPaid>());
+    assert!(contract.incentive_reserves.get(POOL_ID) < incentive_reserve);
+    assert!(vm.emitted::<CashbackPaid>());
+    assert!(contract.is_transition_pending(POOL_ID));
+    assert!(vm.emitted::<GraduationThresholdReached>());
+    assert!(vm.emitted::<TransitionReady>());
+    assert_eq!(contract.consult(POOL_ID, 0).ok(), Some(new_price));
+    let (round_id, answer, _, updated_at, _) =
+        contract.latest_round_data(POOL_ID).unwrap_or_default();
+    assert_eq!(round_id, 1);
+    assert_eq!(
+        answer,
+        I256::try_from(contract.get_current_price(POOL_ID).unwrap_or_default()).unwrap_or_default()
+    );
+    assert_eq!(updated_at, U256::from(BLOCK_TIMESTAMP));
+}
+
+#[test]
+fn early_graduation_announces_the_transition() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    let weth_collected = U256::from(1000u16) * SCALE_FACTOR;
+    mock_pool(
+        &mut contract,
+        TOTAL_SUPPLY / U256::from(10u8),
+        weth_collected,
+    );
+    MockHost::set_pool_info(
+        SENDER,
+        POOL_ID,
+        (TOKEN, SENDER, weth_collected, U256::ZERO, false, B256::ZERO),
+    );
+
+    assert!(contract.request_early_graduation(POOL_ID).is_ok());
+    assert!(contract.execute_early_graduation(POOL_ID).is_ok());
+    assert!(contract.is_transition_pending(POOL_ID));
+    assert!(vm.emitted::<GraduationThresholdReached>());
+    assert!(vm.emitted::<TransitionReady>());
+}
+
+#[test]
+fn incentive_reserve_is_funded_from_the_caller() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    let sold = TOTAL_SUPPLY / U256::from(10u8);
+    let weth_collected = U256::from(1000u16) * SCALE_FACTOR;
+    mock_pool(&mut contract, sold, weth_collected);
+    MockHost::set_pool_info(
+        OTHER,
+        POOL_ID,
+        (
+            TOKEN,
+            CREATOR,
+            weth_collected,
+            U256::ZERO,
+            false,
+            B256::ZERO,
+        ),
+    );
+    assert!(contract
+        .migrate_pool_manager(SENDER, OTHER, vec![POOL_ID])
+        .is_ok());
+
+    // The caller can't deposit more than they hold
+    assert!(matches!(
+        contract.fund_incentive_reserve(POOL_ID, TOTAL_SUPPLY),
+        Err(PumpUpErrors::Erc20CallFailed(_))
+    ));
+    assert!(contract.incentive_reserves.get(POOL_ID).is_zero());
+    assert!(!vm.emitted::<IncentiveReserveFunded>());
+
+    // Deposits go to the pool's manager, which pays the cashback out
+    assert!(contract
+        .fund_incentive_reserve(POOL_ID, SCALE_FACTOR)
+        .is_ok());
+    assert!(contract
+        .fund_incentive_reserve(POOL_ID, SCALE_FACTOR)
+        .is_ok());
+    let reserve = U256::from(2u8) * SCALE_FACTOR;
+    assert!(vm.emitted::<IncentiveReserveFunded>());
+    assert_eq!(contract.incentive_reserves.get(POOL_ID), reserve);
+    assert_eq!(MockHost.balance_of(TOKEN, OTHER).ok(), Some(reserve));
+    assert_eq!(
+        MockHost.balance_of(TOKEN, SENDER).ok(),
+        Some(TOTAL_SUPPLY - sold - reserve)
+    );
+}
+
+#[test]
+fn fee_claims_are_capped_by_the_balance_held() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    mock_pool(
+        &mut contract,
+        TOTAL_SUPPLY / U256::from(10u8),
+        U256::from(1000u16) * SCALE_FACTOR,
+    );
+    assert!(contract
+        .set_default_fees(U256::from(100u8), U256::from(50u8))
+        .is_ok());
+    assert!(contract.calculate_buy(POOL_ID, OTHER, SCALE_FACTOR).is_ok());
+    let (protocol_fees, creator_fees) = contract.get_accrued_fees(POOL_ID);
+    assert!(!protocol_fees.is_zero() && !creator_fees.is_zero());
+
+    // Fees the manager has not forwarded yet can't be claimed
+    assert!(contract.set_quote_token(USDC).is_ok());
+    MockHost::set_token(USDC, TOTAL_SUPPLY, CONTRACT, U256::ZERO);
+    assert!(matches!(
+        contract.claim_protocol_fees(),
+        Err(PumpUpErrors::NothingToClaim(_))
+    ));
+    assert_eq!(
+        contract.get_accrued_fees(POOL_ID),
+        (protocol_fees, creator_fees)
+    );
+}
+
+#[test]
+fn simulated_sequences_match_executed_trades() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    mock_pool(
+        &mut contract,
+        TOTAL_SUPPLY / U256::from(10u8),
+        U256::from(1000u16) * SCALE_FACTOR,
+    );
+    assert!(contract
+        .set_default_fees(U256::from(100u8), U256::from(50u8))
+        .is_ok());
+    // The trader's integrator discount applies to both
+    assert!(contract
+        .set_integrator_discount(OTHER, U256::from(5_000u16))
+        .is_ok());
+
+    let trades = vec![
+        (true, U256::from(10u8) * SCALE_FACTOR),
+        (false, U256::from(1_000_000u32) * SCALE_FACTOR),
+        (true, U256::from(3u8) * SCALE_FACTOR),
+    ];
+    let simulated = contract
+        .simulate_sequence(POOL_ID, OTHER, trades.clone())
+        .unwrap_or_default();
+    assert_eq!(simulated.len(), trades.len());
+
+    for ((is_buy, amount_in), expected) in trades.into_iter().zip(simulated) {
+        let executed = if is_buy {
+            contract.calculate_buy(POOL_ID, OTHER, amount_in)
+        } else {
+            contract.calculate_sell(POOL_ID, OTHER, amount_in)
+        };
+        assert_eq!(executed.unwrap_or_default(), expected);
+
+        // The manager's view follows the trade it executed
+        let cache = contract.pool_caches.get(POOL_ID);
+        let (sold, weth_collected) = (cache.circulating_supply.get(), cache.weth_collected.get());
+        mock_pool(&mut contract, sold, weth_collected);
+        vm.end_transaction();
+    }
+}
+
+#[test]
+fn current_prices_are_read_in_one_call() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    mock_pool(&mut contract, U256::ZERO, U256::ZERO);
+    let sold = TOTAL_SUPPLY / U256::from(4u8);
+    assert!(contract.notify_buy(POOL_ID, sold).is_ok());
+
+    // Initialized but unknown to the manager, and never initialized
+    let unreadable_pool = B256::repeat_byte(0x22);
+    let params = curve_params(INITIAL_PRICE, U256::ZERO, U256::ZERO, U256::ZERO);
+    assert!(contract.initialize(unreadable_pool, params).is_ok());
+    let unknown_pool = B256::repeat_byte(0x33);
+
+    let price = contract.get_current_price(POOL_ID).unwrap_or_default();
+    assert!(price > INITIAL_PRICE);
+    assert_eq!(
+        contract.get_current_prices(vec![POOL_ID, unreadable_pool, unknown_pool, POOL_ID]),
+        vec![price, U256::ZERO, U256::ZERO, price]
+    );
+}
+
+#[test]
+fn pool_info_is_read_once_per_transaction() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    mock_pool(&mut contract, U256::ZERO, U256::ZERO);
+    vm.end_transaction();
+    let reads = MockHost::pool_info_reads();
+
+    assert!(contract.get_current_price(POOL_ID).is_ok());
+    assert!(contract.quote_buy(POOL_ID, SENDER, SCALE_FACTOR).is_ok());
+    assert_eq!(MockHost::pool_info_reads(), reads + 1);
+
+    // A later transaction reads again
+    vm.end_transaction();
+    assert!(contract.get_current_price(POOL_ID).is_ok());
+    assert_eq!(MockHost::pool_info_reads(), reads + 2);
+
+    // So does the same one once the manager reports a trade
+    assert!(contract.notify_buy(POOL_ID, SCALE_FACTOR).is_ok());
+    assert!(contract.get_current_price(POOL_ID).is_ok());
+    assert_eq!(MockHost::pool_info_reads(), reads + 3);
+}
+
+// Calldata of a call to this contract
+fn calldata(signature: &str, args: &[u8]) -> Bytes {
+    let mut data = keccak256(signature)[..4].to_vec();
+    data.extend_from_slice(args);
+    data.into()
+}
+
+#[test]
+fn multicall_batches_calls_and_bubbles_reverts() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+    mock_pool(&mut contract, U256::ZERO, U256::ZERO);
+    let other_pool = B256::repeat_byte(0x22);
+
+    let results = contract.multicall(vec![
+        calldata("poolExists(bytes32)", &POOL_ID.abi_encode()),
+        calldata("poolExists(bytes32)", &other_pool.abi_encode()),
+        calldata("getCurrentPrice(bytes32)", &POOL_ID.abi_encode()),
+    ]);
+    let price = contract.get_current_price(POOL_ID).unwrap_or_default();
+    assert_eq!(
+        results.ok(),
+        Some(vec![
+            true.abi_encode().into(),
+            false.abi_encode().into(),
+            price.abi_encode().into(),
+        ])
+    );
+
+    // Calls run as the caller and may write
+    let params = curve_params(INITIAL_PRICE, U256::ZERO, U256::ZERO, U256::ZERO);
+    let initialize = calldata(
+        "initialize(bytes32,bytes)",
+        &(other_pool, params.0.clone()).abi_encode_params(),
+    );
+    assert!(contract.multicall(vec![initialize.clone()]).is_ok());
+    assert!(contract.pool_exists(other_pool));
+
+    // A failing call reverts with its own error
+    let reverted = contract.multicall(vec![calldata("poolCount()", &[]), initialize]);
+    assert_eq!(
+        reverted.err(),
+        Some(PumpUpErrors::PoolAlreadyInitialized(PoolAlreadyInitialized {}).into())
+    );
+
+    let unknown = contract.multicall(vec![Bytes::from(vec![0xde, 0xad, 0xbe, 0xef])]);
+    assert_eq!(
+        unknown.err(),
+        Some(
+            PumpUpErrors::UnknownSelector(UnknownSelector {
+                selector: FixedBytes([0xde, 0xad, 0xbe, 0xef]),
+            })
+            .into()
+        )
+    );
+}
+
+#[test]
+fn health_check_reads_quote_token_decimals() {
+    let vm = TestVm::new();
+    let mut contract = vm.deploy();
+    assert_eq!(contract.health_check(), HEALTH_QUOTE_TOKEN_UNSET);
+
+    assert!(contract.set_quote_token(TOKEN).is_ok());
+    assert_eq!(contract.health_check(), HEALTH_QUOTE_TOKEN_DECIMALS);
+
+    MockHost::set_decimals(TOKEN, 6);
+    assert_eq!(contract.health_check(), HEALTH_QUOTE_TOKEN_DECIMALS);
+
+    MockHost::set_decimals(TOKEN, 18);
+    assert_eq!(contract.health_check(), 0);
+}
+
+#[test]
+fn param_admin_role_gates_parameter_changes() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+
+    // The owner holds every role
+    assert!(contract.set_default_curve_kind(CURVE_KIND_LINEAR).is_ok());
+    assert_eq!(contract.get_default_curve_kind(), CURVE_KIND_LINEAR);
+
+    // Without ownership or the role the change is refused
+    assert!(contract.transfer_ownership(OTHER).is_ok());
+    assert!(matches!(
+        contract.set_default_curve_kind(0),
+        Err(PumpUpErrors::MissingRole(_))
+    ));
+    assert!(matches!(
+        contract.grant_role(PARAM_ADMIN_ROLE, SENDER),
+        Err(PumpUpErrors::NotOwner(_))
+    ));
+}
+
+#[test]
+fn granted_role_outlives_ownership() {
+    let vm = TestVm::new();
+    let mut contract = initialized(&vm);
+
+    assert!(contract.grant_role(PARAM_ADMIN_ROLE, SENDER).is_ok());
+    assert!(contract.transfer_ownership(OTHER).is_ok());
+
+    // Role holders may tune parameters but not use owner-only entrypoints
+    assert!(contract.has_role(PARAM_ADMIN_ROLE, SENDER));
+    assert!(contract.set_default_curve_kind(CURVE_KIND_LINEAR).is_ok());
+
+    let params = curve_params(INITIAL_PRICE, U256::ZERO, U256::ZERO, U256::ZERO);
+    assert!(matches!(
+        contract.reinitialize(POOL_ID, params),
+        Err(PumpUpErrors::NotOwner(_))
+    ));
+}
@@ -0,0 +1,53 @@
+// The hand-encoded `raw-calls` path against the ABI the typed bindings use
+
+use alloy_primitives::{address, keccak256, B256, U256};
+use alloy_sol_types::SolValue;
+
+use crate::host::raw::{
+    decode_pool_info, BALANCE_OF_SELECTOR, DECIMALS_SELECTOR, GET_POOL_INFO_SELECTOR,
+    TOTAL_SUPPLY_SELECTOR,
+};
+use crate::PoolInfo;
+
+fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature);
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+fn pool_info(is_transitioned: bool) -> PoolInfo {
+    (
+        address!("0000000000000000000000000000000000007e57"),
+        address!("00000000000000000000000000000000000c4ea7"),
+        U256::from(3u8) * U256::from(10u8).pow(U256::from(18u8)),
+        U256::from(1_000_000_000_000u64),
+        is_transitioned,
+        B256::repeat_byte(0x5e),
+    )
+}
+
+#[test]
+fn raw_selectors_match_their_signatures() {
+    assert_eq!(TOTAL_SUPPLY_SELECTOR, selector("totalSupply()"));
+    assert_eq!(BALANCE_OF_SELECTOR, selector("balanceOf(address)"));
+    assert_eq!(DECIMALS_SELECTOR, selector("decimals()"));
+    assert_eq!(GET_POOL_INFO_SELECTOR, selector("getPoolInfo(bytes32)"));
+}
+
+#[test]
+fn raw_pool_info_decodes_abi_encoded_results() {
+    for is_transitioned in [false, true] {
+        let info = pool_info(is_transitioned);
+        assert_eq!(decode_pool_info(&info.abi_encode_params()), Some(info));
+    }
+}
+
+#[test]
+fn raw_pool_info_rejects_malformed_results() {
+    let encoded = pool_info(true).abi_encode_params();
+    assert_eq!(decode_pool_info(&encoded[..191]), None);
+
+    // The bool word must hold exactly 0 or 1
+    let mut dirty_bool = encoded;
+    dirty_bool[4 * 32] = 1;
+    assert_eq!(decode_pool_info(&dirty_bool), None);
+}
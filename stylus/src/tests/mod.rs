@@ -0,0 +1,8 @@
+// Native tests of the sigmoid entrypoint. `vm` stands in for the Stylus VM so entrypoints can run
+// under cargo test; `round_trip` checks the pricing paths need no VM at all. `host` checks the
+// hand-encoded external calls against the ABI.
+
+mod contract;
+mod host;
+mod round_trip;
+mod vm;
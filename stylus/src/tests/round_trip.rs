@@ -0,0 +1,159 @@
+// Round-trip invariants of the buy and sell quotes: buying with X WETH and selling the tokens
+// straight back never returns more than X, and loses at most the solver tolerance. Over any
+// sequence of trades, sells never pay out more WETH than the buys before them paid in, and no
+// valid curve trips the runtime invariant guards.
+
+use alloy_primitives::U256;
+use proptest::prelude::*;
+use stylus_sdk::storage::StorageType;
+
+use crate::piecewise::{SEGMENT_SHAPE_LINEAR, SEGMENT_SHAPE_SIGMOID};
+use crate::{
+    CurveParameters, CurveSegment, SigmoidBondingCurve, CURVE_KIND_EXPONENTIAL, CURVE_KIND_LINEAR,
+    CURVE_KIND_PIECEWISE, CURVE_KIND_SIGMOID, CURVE_KIND_VIRTUAL_RESERVES, SCALE_FACTOR,
+    SOLVER_TOLERANCE,
+};
+
+// Contract handle for the pure pricing paths, which never touch storage
+fn curve_contract() -> SigmoidBondingCurve {
+    unsafe { SigmoidBondingCurve::new(U256::ZERO, 0) }
+}
+
+prop_compose! {
+    // Curves on every selectable pricing model: prices from 1 gwei to 0.001 ETH growing up to
+    // 1000x over a thousand to a billion tokens. Exponential pools take their growth rate from
+    // the steepness, kept at 7 or below (e^7 is ~1100x).
+    fn curve()(
+        curve_kind in prop_oneof![
+            Just(CURVE_KIND_SIGMOID),
+            Just(CURVE_KIND_VIRTUAL_RESERVES),
+            Just(CURVE_KIND_LINEAR),
+            Just(CURVE_KIND_EXPONENTIAL),
+        ],
+        initial_price in 1_000_000_000u64..=1_000_000_000_000_000,
+        max_price_factor in 1u64..=1000,
+        steepness in 1u64..=50,
+        midpoint_percent in 1u64..=99,
+        total_tokens in 1_000u64..=1_000_000_000,
+    ) -> CurveParameters {
+        CurveParameters {
+            initial_price: U256::from(initial_price),
+            max_price_factor: U256::from(max_price_factor) * SCALE_FACTOR,
+            steepness: U256::from(if curve_kind == CURVE_KIND_EXPONENTIAL {
+                1 + (steepness - 1) % 7
+            } else {
+                steepness
+            }) * SCALE_FACTOR,
+            midpoint: U256::from(midpoint_percent) * SCALE_FACTOR / U256::from(100u8),
+            total_supply: U256::from(total_tokens) * SCALE_FACTOR,
+            curve_kind,
+            ..Default::default()
+        }
+    }
+}
+
+prop_compose! {
+    // Piecewise curves of a linear segment up to a random share of the supply followed by a
+    // sigmoid segment to the end
+    fn piecewise_curve()(
+        params in curve(),
+        split_percent in 1u64..=99,
+        split_factor in 1u64..=100,
+    ) -> CurveParameters {
+        let split_supply = params.total_supply * U256::from(split_percent) / U256::from(100u8);
+        let split_price = params.initial_price * U256::from(split_factor);
+
+        let mut params = CurveParameters {
+            curve_kind: CURVE_KIND_PIECEWISE,
+            segment_count: 2,
+            ..params
+        };
+        params.segments[0] = CurveSegment {
+            shape: SEGMENT_SHAPE_LINEAR,
+            end_supply: split_supply,
+            end_price: split_price,
+            ..Default::default()
+        };
+        params.segments[1] = CurveSegment {
+            shape: SEGMENT_SHAPE_SIGMOID,
+            end_supply: params.total_supply,
+            end_price: split_price * U256::from(10u8),
+            steepness: params.steepness,
+            midpoint: params.midpoint,
+        };
+        params
+    }
+}
+
+proptest! {
+    #[test]
+    fn trades_never_overdraw_the_reserve(
+        params in prop_oneof![curve(), piecewise_curve()],
+        trades in proptest::collection::vec((any::<bool>(), 1u64..=1_000_000), 1..40),
+    ) {
+        let contract = curve_contract();
+        let (mut supply, mut reserve) = (U256::ZERO, U256::ZERO);
+
+        // Trades of up to a tenth of the total supply, then everything sold back
+        for (is_buy, size) in trades {
+            let amount = params.total_supply * U256::from(size) / U256::from(10_000_000u32);
+            if is_buy {
+                let tokens = amount.min(params.total_supply - supply);
+                reserve += contract.calculate_weth_for_token_amount(supply, tokens, &params, false);
+                supply += tokens;
+            } else {
+                let tokens = amount.min(supply);
+                let weth_out =
+                    contract.calculate_weth_for_token_amount(supply, tokens, &params, true);
+                prop_assert!(weth_out <= reserve);
+                reserve -= weth_out;
+                supply -= tokens;
+            }
+        }
+
+        let weth_out = contract.calculate_weth_for_token_amount(supply, supply, &params, true);
+        prop_assert!(weth_out <= reserve);
+    }
+
+    #[test]
+    fn curve_quotes_keep_the_invariants(
+        params in prop_oneof![curve(), piecewise_curve()],
+        sold_percent in 0u64..=100,
+        size in 1u64..=10_000_000,
+    ) {
+        let contract = curve_contract();
+        let supply = params.total_supply * U256::from(sold_percent) / U256::from(100u8);
+        let amount = params.total_supply * U256::from(size) / U256::from(10_000_000u32);
+
+        for (tokens, is_selling) in [(amount.min(params.total_supply - supply), false), (amount.min(supply), true)] {
+            let end_supply = if is_selling { supply - tokens } else { supply + tokens };
+            let weth = contract.calculate_weth_for_token_amount(supply, tokens, &params, is_selling);
+            let price = contract.calculate_sigmoid_price(end_supply, &params);
+            prop_assert!(contract
+                .check_quote_invariants(supply, tokens, weth, price, &params, is_selling)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn buy_then_sell_never_profits(
+        params in curve(),
+        sold_percent in 0u64..100,
+        weth_amount in 1u128..=100_000_000_000_000_000_000,
+    ) {
+        let contract = curve_contract();
+        let supply = params.total_supply * U256::from(sold_percent) / U256::from(100u8);
+        let weth_amount = U256::from(weth_amount);
+
+        let tokens = contract.find_token_amount_for_weth(supply, weth_amount, &params, false);
+        let weth_back =
+            contract.calculate_weth_for_token_amount(supply + tokens, tokens, &params, true);
+
+        prop_assert!(weth_back <= weth_amount);
+
+        // A buy capped by the remaining supply leaves the rest of the WETH unspent
+        if supply + tokens < params.total_supply {
+            prop_assert!(weth_amount - weth_back <= SOLVER_TOLERANCE);
+        }
+    }
+}
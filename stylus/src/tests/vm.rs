@@ -0,0 +1,254 @@
+// Native stand-in for the Stylus VM hooks, so contract entrypoints run under `cargo test`.
+//
+// The SDK imports its hostio functions from the VM; natively they resolve to the definitions
+// below, backed by per-test storage and a log of emitted events. Token and manager reads are
+// answered by the mock host, which TestVm resets along with the storage. A test runs as a single
+// transaction unless it calls `end_transaction`. ERC20 transferFrom calls move the mock host's
+// balances; every other external call reverts. The SDK caches msg::sender and the block and
+// chain values process-wide, so those are fixed: every call comes from SENDER at
+// BLOCK_TIMESTAMP, carrying no value.
+
+use alloy_primitives::{address, Address, Keccak256, B256, U256};
+use alloy_sol_types::SolEvent;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::vec::Vec;
+use stylus_sdk::storage::StorageType;
+
+use crate::host::mock::MockHost;
+use crate::pool_info_cache;
+use crate::SigmoidBondingCurve;
+
+// Caller of every entrypoint
+pub(super) const SENDER: Address = address!("00000000000000000000000000000000000a11ce");
+
+// Block timestamp seen by the contract
+pub(super) const BLOCK_TIMESTAMP: u64 = 1_700_000_000;
+
+// Block number, chain id and address of the contract under test
+pub(super) const BLOCK_NUMBER: u64 = 1_000;
+pub(super) const CHAIN_ID: u64 = 42_161;
+pub(super) const CONTRACT: Address = address!("000000000000000000000000000000000000c0de");
+
+// keccak256("transferFrom(address,address,uint256)")
+const TRANSFER_FROM_SELECTOR: [u8; 4] = [0x23, 0xb8, 0x72, 0xdd];
+
+// Serializes tests that use the VM, as the SDK keeps the last call's return data length in a
+// process-wide static
+static VM_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Default)]
+struct VmState {
+    storage: HashMap<B256, B256>,
+    transient: HashMap<B256, B256>,
+    logs: Vec<(Vec<B256>, Vec<u8>)>,
+}
+
+std::thread_local! {
+    static STATE: RefCell<VmState> = RefCell::new(VmState::default());
+}
+
+// Exclusive, freshly reset VM for the duration of a test
+pub(super) struct TestVm {
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl TestVm {
+    pub(super) fn new() -> Self {
+        // A failed test poisons the lock without leaving shared state behind
+        let lock = VM_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        STATE.with(|state| *state.borrow_mut() = VmState::default());
+        MockHost::reset();
+        pool_info_cache::reset();
+
+        TestVm { _lock: lock }
+    }
+
+    // Contract at the root storage slot, constructed by SENDER with SENDER as its pool state
+    // manager
+    pub(super) fn deploy(&self) -> SigmoidBondingCurve {
+        let mut contract = unsafe { SigmoidBondingCurve::new(U256::ZERO, 0) };
+        contract.constructor(SENDER);
+        contract
+    }
+
+    // Start a new transaction, dropping transient storage and any in-memory pool info cache
+    pub(super) fn end_transaction(&self) {
+        STATE.with(|state| state.borrow_mut().transient.clear());
+        pool_info_cache::reset();
+    }
+
+    // Whether an event of type E has been emitted
+    pub(super) fn emitted<E: SolEvent>(&self) -> bool {
+        STATE.with(|state| {
+            state
+                .borrow()
+                .logs
+                .iter()
+                .any(|(topics, _)| topics.first() == Some(&E::SIGNATURE_HASH))
+        })
+    }
+}
+
+unsafe fn read_word(ptr: *const u8) -> B256 {
+    B256::from_slice(std::slice::from_raw_parts(ptr, 32))
+}
+
+unsafe fn write_word(ptr: *mut u8, value: B256) {
+    std::ptr::copy_nonoverlapping(value.as_ptr(), ptr, 32);
+}
+
+// Revert an external call: token and manager reads go through the mock host, and nothing else
+// the tests reach calls out
+unsafe fn external_call(return_data_len: *mut usize) -> u8 {
+    *return_data_len = 0;
+    1
+}
+
+#[no_mangle]
+unsafe extern "C" fn storage_load_bytes32(key: *const u8, dest: *mut u8) {
+    let key = read_word(key);
+    let value = STATE.with(|state| state.borrow().storage.get(&key).copied());
+    write_word(dest, value.unwrap_or_default());
+}
+
+#[no_mangle]
+unsafe extern "C" fn storage_cache_bytes32(key: *const u8, value: *const u8) {
+    let (key, value) = (read_word(key), read_word(value));
+    STATE.with(|state| state.borrow_mut().storage.insert(key, value));
+}
+
+#[no_mangle]
+extern "C" fn storage_flush_cache(_clear: bool) {}
+
+#[no_mangle]
+unsafe extern "C" fn transient_load_bytes32(key: *const u8, dest: *mut u8) {
+    let key = read_word(key);
+    let value = STATE.with(|state| state.borrow().transient.get(&key).copied());
+    write_word(dest, value.unwrap_or_default());
+}
+
+#[no_mangle]
+unsafe extern "C" fn transient_store_bytes32(key: *const u8, value: *const u8) {
+    let (key, value) = (read_word(key), read_word(value));
+    STATE.with(|state| state.borrow_mut().transient.insert(key, value));
+}
+
+#[no_mangle]
+unsafe extern "C" fn native_keccak256(bytes: *const u8, len: usize, output: *mut u8) {
+    let mut hasher = Keccak256::new();
+    hasher.update(std::slice::from_raw_parts(bytes, len));
+    write_word(output, hasher.finalize());
+}
+
+#[no_mangle]
+unsafe extern "C" fn msg_sender(sender: *mut u8) {
+    std::ptr::copy_nonoverlapping(SENDER.as_ptr(), sender, 20);
+}
+
+#[no_mangle]
+extern "C" fn block_timestamp() -> u64 {
+    BLOCK_TIMESTAMP
+}
+
+// Every account holds code
+#[no_mangle]
+unsafe extern "C" fn account_codehash(_address: *const u8, dest: *mut u8) {
+    write_word(dest, B256::repeat_byte(0xc0));
+}
+
+// No account holds native currency
+#[no_mangle]
+unsafe extern "C" fn account_balance(_address: *const u8, dest: *mut u8) {
+    write_word(dest, B256::ZERO);
+}
+
+#[no_mangle]
+unsafe extern "C" fn msg_value(value: *mut u8) {
+    write_word(value, B256::ZERO);
+}
+
+#[no_mangle]
+extern "C" fn block_number() -> u64 {
+    BLOCK_NUMBER
+}
+
+#[no_mangle]
+extern "C" fn chainid() -> u64 {
+    CHAIN_ID
+}
+
+#[no_mangle]
+unsafe extern "C" fn contract_address(address: *mut u8) {
+    std::ptr::copy_nonoverlapping(CONTRACT.as_ptr(), address, 20);
+}
+
+#[no_mangle]
+unsafe extern "C" fn emit_log(data: *const u8, len: usize, topics: usize) {
+    let data = std::slice::from_raw_parts(data, len);
+    let (topic_bytes, body) = data.split_at(topics * 32);
+    let topics = topic_bytes.chunks(32).map(B256::from_slice).collect();
+
+    STATE.with(|state| state.borrow_mut().logs.push((topics, body.to_vec())));
+}
+
+// A transferFrom the mock host can cover succeeds with empty return data, as USDT-style tokens
+// answer
+#[no_mangle]
+unsafe extern "C" fn call_contract(
+    contract: *const u8,
+    calldata: *const u8,
+    calldata_len: usize,
+    _value: *const u8,
+    _gas: u64,
+    return_data_len: *mut usize,
+) -> u8 {
+    let calldata = std::slice::from_raw_parts(calldata, calldata_len);
+    if calldata.len() == 100 && calldata[..4] == TRANSFER_FROM_SELECTOR {
+        let token = Address::from_slice(std::slice::from_raw_parts(contract, 20));
+        let from = Address::from_slice(&calldata[16..36]);
+        let to = Address::from_slice(&calldata[48..68]);
+        let amount = U256::from_be_slice(&calldata[68..100]);
+        if MockHost::transfer_from(token, from, to, amount) {
+            *return_data_len = 0;
+            return 0;
+        }
+    }
+
+    external_call(return_data_len)
+}
+
+#[no_mangle]
+unsafe extern "C" fn static_call_contract(
+    _contract: *const u8,
+    _calldata: *const u8,
+    _calldata_len: usize,
+    _gas: u64,
+    return_data_len: *mut usize,
+) -> u8 {
+    external_call(return_data_len)
+}
+
+#[no_mangle]
+unsafe extern "C" fn delegate_call_contract(
+    _contract: *const u8,
+    _calldata: *const u8,
+    _calldata_len: usize,
+    _gas: u64,
+    return_data_len: *mut usize,
+) -> u8 {
+    external_call(return_data_len)
+}
+
+#[no_mangle]
+extern "C" fn read_return_data(_dest: *mut u8, _offset: usize, _size: usize) -> usize {
+    0
+}
+
+#[no_mangle]
+extern "C" fn return_data_size() -> usize {
+    0
+}
@@ -0,0 +1,166 @@
+// Uniswap v4 tick math used to hand a graduating pool's final curve price to its v4 pool.
+// Prices are sqrt(currency1 / currency0) as Q64.96 values, ticks are log base sqrt(1.0001).
+
+use alloy_primitives::{U256, U512};
+
+use crate::SCALE_FACTOR;
+use pumpup_math::sqrt;
+
+// Tick range supported by Uniswap v4 (log base 1.0001 of 2^-128 and 2^128)
+pub(crate) const MIN_TICK: i32 = -887_272;
+pub(crate) const MAX_TICK: i32 = 887_272;
+
+// sqrtPriceX96 at MIN_TICK and MAX_TICK; valid pool prices lie in [MIN_SQRT_PRICE, MAX_SQRT_PRICE)
+pub(crate) const MIN_SQRT_PRICE: U256 = U256::from_limbs([4_295_128_739, 0, 0, 0]);
+pub(crate) const MAX_SQRT_PRICE: U256 =
+    U256::from_limbs([0x5d95_1d52_6398_8d26, 0xefd1_fc6a_5064_8849, 0xfffd_8963, 0]);
+
+// 2^128 / sqrt(1.0001)^(2^i) for i = 0..20 (Q128.128), as in Uniswap's TickMath
+const SQRT_RATIO_NEG_POWERS: [u128; 20] = [
+    0xfffc_b933_bd6f_ad37_aa2d_162d_1a59_4001,
+    0xfff9_7272_373d_4132_59a4_6990_580e_213a,
+    0xfff2_e50f_5f65_6932_ef12_357c_f3c7_fdcc,
+    0xffe5_caca_7e10_e4e6_1c36_24ea_a094_1cd0,
+    0xffcb_9843_d60f_6159_c9db_5883_5c92_6644,
+    0xff97_3b41_fa98_c081_472e_6896_dfb2_54c0,
+    0xff2e_a164_66c9_6a38_43ec_78b3_26b5_2861,
+    0xfe5d_ee04_6a99_a2a8_11c4_61f1_969c_3053,
+    0xfcbe_86c7_900a_88ae_dcff_c83b_479a_a3a4,
+    0xf987_a725_3ac4_1317_6f2b_074c_f781_5e54,
+    0xf339_2b08_22b7_0005_940c_7a39_8e4b_70f3,
+    0xe715_9475_a2c2_9b74_43b2_9c7f_a6e8_89d9,
+    0xd097_f3bd_fd20_22b8_845a_d8f7_92aa_5825,
+    0xa9f7_4646_2d87_0fdf_8a65_dc1f_90e0_61e5,
+    0x70d8_69a1_56d2_a1b8_90bb_3df6_2baf_32f7,
+    0x31be_135f_97d0_8fd9_8123_1505_542f_cfa6,
+    0x09aa_508b_5b7a_84e1_c677_de54_f3e9_9bc9,
+    0x005d_6af8_dedb_8119_6699_c329_225e_e604,
+    0x0000_2216_e584_f5fa_1ea9_2604_1bed_fe98,
+    0x0000_0000_048a_1703_91f7_dc42_444e_8fa2,
+];
+
+// sqrtPriceX96 at a tick, clamped to the supported tick range
+pub(crate) fn get_sqrt_price_at_tick(tick: i32) -> U256 {
+    let tick = tick.clamp(MIN_TICK, MAX_TICK);
+    let abs_tick = tick.unsigned_abs();
+
+    // Multiply out 1 / sqrt(1.0001)^|tick| one bit of the tick at a time (Q128.128)
+    let mut price = U256::from(1u8) << 128usize;
+    for (bit, ratio) in SQRT_RATIO_NEG_POWERS.iter().enumerate() {
+        if abs_tick & (1 << bit) != 0 {
+            price = (price * U256::from(*ratio)) >> 128usize;
+        }
+    }
+
+    // Positive ticks take the reciprocal
+    if tick > 0 {
+        price = U256::MAX / price;
+    }
+
+    // Q128.128 to Q64.96, rounding up so the result is never below the tick's true price
+    let rounded_up = price.as_limbs()[0] as u32 != 0;
+    (price >> 32usize) + U256::from(rounded_up as u8)
+}
+
+// Greatest tick whose sqrtPriceX96 does not exceed sqrt_price_x96
+pub(crate) fn get_tick_at_sqrt_price(sqrt_price_x96: U256) -> i32 {
+    let mut low = MIN_TICK;
+    let mut high = MAX_TICK;
+
+    while low < high {
+        // Round the midpoint up so the search always narrows
+        let mid = low + (high - low + 1) / 2;
+        if get_sqrt_price_at_tick(mid) <= sqrt_price_x96 {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+
+    low
+}
+
+// sqrtPriceX96 for a token price in WETH (scaled by 10^18), both assets having 18 decimals.
+// currency1 / currency0 is the price itself when the token sorts first, its inverse otherwise.
+// The result is clamped to the range a v4 pool can be initialized at.
+pub(crate) fn sqrt_price_x96_from_price(price: U256, token_is_currency0: bool) -> U256 {
+    if price.is_zero() {
+        return if token_is_currency0 {
+            MIN_SQRT_PRICE
+        } else {
+            MAX_SQRT_PRICE - U256::from(1u8)
+        };
+    }
+
+    // Price ratio scaled by 2^192, computed in 512 bits so neither direction overflows
+    let q192 = U512::from(1u8) << 192usize;
+    let ratio_x192 = if token_is_currency0 {
+        U512::from(price) * q192 / U512::from(SCALE_FACTOR)
+    } else {
+        U512::from(SCALE_FACTOR) * q192 / U512::from(price)
+    };
+
+    let sqrt_price_x96 = if ratio_x192 > U512::from(U256::MAX) {
+        MAX_SQRT_PRICE
+    } else {
+        sqrt(U256::from(ratio_x192))
+    };
+
+    sqrt_price_x96.clamp(MIN_SQRT_PRICE, MAX_SQRT_PRICE - U256::from(1u8))
+}
+
+// Lowest and highest ticks usable at a tick spacing (the full range rounded inwards)
+pub(crate) fn usable_tick_range(tick_spacing: i32) -> (i32, i32) {
+    let max_tick = MAX_TICK / tick_spacing * tick_spacing;
+    (-max_tick, max_tick)
+}
+
+// Largest liquidity that amount0 and amount1 can fund over [sqrt_price_lower, sqrt_price_upper]
+// at sqrt_price_x96, which must lie strictly inside the range. Amounts beyond uint128 (more than
+// any ERC20 supply) are capped so the 512-bit intermediates cannot overflow.
+pub(crate) fn liquidity_for_amounts(
+    sqrt_price_x96: U256,
+    sqrt_price_lower: U256,
+    sqrt_price_upper: U256,
+    amount0: U256,
+    amount1: U256,
+) -> U256 {
+    let (price, lower, upper) = (
+        U512::from(sqrt_price_x96),
+        U512::from(sqrt_price_lower),
+        U512::from(sqrt_price_upper),
+    );
+
+    // L = amount0 * sqrtP * sqrtB / (sqrtB - sqrtP) / Q96 and L = amount1 * Q96 / (sqrtP - sqrtA)
+    let (amount0, amount1) = (
+        U512::from(amount0.min(U256::from(u128::MAX))),
+        U512::from(amount1.min(U256::from(u128::MAX))),
+    );
+    let liquidity0 = (amount0 * price * upper / (upper - price)) >> 96usize;
+    let liquidity1 = (amount1 << 96usize) / (price - lower);
+
+    // Uniswap caps position liquidity at uint128
+    U256::from(liquidity0.min(liquidity1).min(U512::from(u128::MAX)))
+}
+
+// Token amounts (rounded down) backing liquidity over [sqrt_price_lower, sqrt_price_upper] at
+// sqrt_price_x96, as (amount0, amount1)
+pub(crate) fn amounts_for_liquidity(
+    sqrt_price_x96: U256,
+    sqrt_price_lower: U256,
+    sqrt_price_upper: U256,
+    liquidity: U256,
+) -> (U256, U256) {
+    let (price, lower, upper) = (
+        U512::from(sqrt_price_x96),
+        U512::from(sqrt_price_lower),
+        U512::from(sqrt_price_upper),
+    );
+    let liquidity = U512::from(liquidity);
+
+    // amount0 = L * Q96 * (sqrtB - sqrtP) / (sqrtP * sqrtB), amount1 = L * (sqrtP - sqrtA) / Q96
+    let amount0 = (liquidity << 96usize) * (upper - price) / (price * upper);
+    let amount1 = (liquidity * (price - lower)) >> 96usize;
+
+    (U256::from(amount0), U256::from(amount1))
+}
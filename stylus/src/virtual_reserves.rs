@@ -0,0 +1,138 @@
+// Constant-product pricing over virtual WETH/token reserves, as popularized by pump.fun.
+//
+// The pool's curve parameters size the virtual reserves: the price starts at initial_price and
+// reaches initial_price * max_price_factor once total_supply is sold. With V the virtual token
+// reserve and W the virtual WETH reserve, W * V = k stays constant and the spot price is W / V.
+
+use alloy_primitives::U256;
+
+use crate::{CurveParameters, SCALE_FACTOR, TWO};
+use pumpup_math::arithmetic::{saturated, Arithmetic, MathOp, Saturating};
+use pumpup_math::{divide_fixed_point, multiply_fixed_point, sqrt, Rounding};
+
+// Virtual token reserve at zero supply, or None for a flat curve (max price factor of 1.0).
+// Price grows with (V0 / (V0 - supply))^2, so reaching factor F at total supply T needs
+// V0 = T * sqrt(F) / (sqrt(F) - 1).
+fn initial_token_reserve(params: &CurveParameters) -> Option<U256> {
+    let sqrt_factor = sqrt(params.max_price_factor.saturating_mul(SCALE_FACTOR));
+    if sqrt_factor <= SCALE_FACTOR {
+        return None;
+    }
+
+    Some(params.total_supply.saturating_mul(sqrt_factor) / (sqrt_factor - SCALE_FACTOR))
+}
+
+// Invariant k = W0 * V0 of a curve with initial token reserve V0
+fn invariant<A: Arithmetic>(token_reserve: U256, params: &CurveParameters) -> Result<U256, MathOp> {
+    A::mul(
+        A::multiply_fixed_point(params.initial_price, token_reserve)?,
+        token_reserve,
+    )
+}
+
+// Spot price at a given supply: initial_price * (V0 / (V0 - supply))^2
+pub(crate) fn price_at(supply: U256, params: &CurveParameters) -> U256 {
+    saturated(try_price_at::<Saturating>(supply, params))
+}
+
+// price_at on the given arithmetic
+pub(crate) fn try_price_at<A: Arithmetic>(
+    supply: U256,
+    params: &CurveParameters,
+) -> Result<U256, MathOp> {
+    let Some(token_reserve) = initial_token_reserve(params) else {
+        return Ok(params.initial_price);
+    };
+
+    let growth = A::divide_fixed_point(
+        token_reserve,
+        token_reserve - supply.min(params.total_supply),
+    )?;
+    A::multiply_fixed_point(
+        params.initial_price,
+        A::multiply_fixed_point(growth, growth)?,
+    )
+}
+
+// Price change per whole token at a given supply: 2 * price / (V0 - supply)
+pub(crate) fn slope_at(supply: U256, params: &CurveParameters) -> U256 {
+    let Some(token_reserve) = initial_token_reserve(params) else {
+        return U256::ZERO;
+    };
+
+    divide_fixed_point(
+        price_at(supply, params).saturating_mul(TWO),
+        token_reserve - supply.min(params.total_supply),
+    )
+}
+
+// WETH between two supplies: the change in the virtual WETH reserve k / (V0 - supply). Rounding
+// up takes the end reserve rounded up and the start reserve rounded down, and rounding down the
+// reverse, so the result errs in the given direction.
+pub(crate) fn try_cost_between<A: Arithmetic>(
+    start_supply: U256,
+    end_supply: U256,
+    params: &CurveParameters,
+    rounding: Rounding,
+) -> Result<U256, MathOp> {
+    let Some(token_reserve) = initial_token_reserve(params) else {
+        return A::mul_div(
+            params.initial_price,
+            end_supply - start_supply,
+            SCALE_FACTOR,
+            rounding,
+        );
+    };
+
+    let k = invariant::<A>(token_reserve, params)?;
+    let weth_reserve_at = |supply: U256, rounding: Rounding| {
+        let token_reserve_left = token_reserve - supply.min(params.total_supply);
+        A::mul_div(k, U256::from(1u8), token_reserve_left, rounding)
+    };
+
+    Ok(weth_reserve_at(end_supply, rounding)?
+        .saturating_sub(weth_reserve_at(start_supply, rounding.opposite())?))
+}
+
+// Supply at which the spot price reaches `price`: V0 * (1 - sqrt(initial_price / price))
+pub(crate) fn supply_at_price(price: U256, params: &CurveParameters) -> U256 {
+    if price <= params.initial_price {
+        return U256::ZERO;
+    }
+    let Some(token_reserve) = initial_token_reserve(params) else {
+        return params.total_supply;
+    };
+
+    let shrink = sqrt(divide_fixed_point(params.initial_price, price).saturating_mul(SCALE_FACTOR));
+    token_reserve
+        .saturating_sub(multiply_fixed_point(token_reserve, shrink))
+        .min(params.total_supply)
+}
+
+// Tokens bought for (or sold to receive) `weth_amount` at `supply`, solved exactly from the
+// invariant and rounded in the pool's favour: V' = k / (W +- weth_amount)
+pub(crate) fn tokens_for_weth(
+    supply: U256,
+    weth_amount: U256,
+    params: &CurveParameters,
+    is_selling: bool,
+) -> U256 {
+    let Some(token_reserve) = initial_token_reserve(params) else {
+        return divide_fixed_point(weth_amount, params.initial_price);
+    };
+
+    let k = saturated(invariant::<Saturating>(token_reserve, params));
+    let current_token_reserve = token_reserve - supply.min(params.total_supply);
+    let weth_reserve = k / current_token_reserve;
+
+    if is_selling {
+        if weth_amount >= weth_reserve {
+            return supply;
+        }
+        let new_token_reserve = k.div_ceil(weth_reserve - weth_amount);
+        new_token_reserve.saturating_sub(current_token_reserve)
+    } else {
+        let new_token_reserve = k.div_ceil(weth_reserve.saturating_add(weth_amount));
+        current_token_reserve.saturating_sub(new_token_reserve)
+    }
+}
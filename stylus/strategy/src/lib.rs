@@ -0,0 +1,30 @@
+//!
+//! Strategy interface shared by PumpUp bonding curves
+//!
+//! Stylus strategy contracts implement `BondingCurveStrategy`. The fixed-point math lives in
+//! `pumpup-math` and is re-exported as `math` for existing users.
+//!
+
+#![no_std]
+
+use alloy_primitives::{B256, U256};
+
+pub use pumpup_math as math;
+
+// Interface every bonding curve strategy exposes to the pool state manager and integrators.
+// Amounts are in wei and prices are WETH per token scaled by 10^18.
+pub trait BondingCurveStrategy {
+    type Error;
+
+    // Set up a pool from its encoded curve parameters
+    fn initialize(&mut self, pool_id: B256, params: &[u8]) -> Result<(), Self::Error>;
+
+    // Tokens received for a WETH amount and the price after the buy
+    fn quote_buy(&self, pool_id: B256, weth_amount: U256) -> Result<(U256, U256), Self::Error>;
+
+    // WETH returned for a token amount and the price after the sell
+    fn quote_sell(&self, pool_id: B256, token_amount: U256) -> Result<(U256, U256), Self::Error>;
+
+    // Current price of a pool
+    fn spot_price(&self, pool_id: B256) -> Result<U256, Self::Error>;
+}